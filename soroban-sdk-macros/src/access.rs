@@ -0,0 +1,177 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Attribute, Error, Ident, LitStr, Meta, Path, Token,
+};
+
+/// An access rule or guard of an exported contract function, declared with an
+/// attribute on the function in a `contractimpl`.
+pub enum AccessRule {
+    /// `#[only_admin]` – Requires the auth of the admin.
+    OnlyAdmin,
+    /// `#[require_auth(arg)]` – Requires the auth of the address `arg`.
+    RequireAuth(Ident),
+    /// `#[only_role("role", arg)]` – Requires the address `arg` to have the
+    /// role, and requires its auth.
+    OnlyRole(LitStr, Ident),
+    /// `#[nonreentrant]` – Fails if a nonreentrant function of the contract
+    /// is executing.
+    NonReentrant,
+    /// `#[when_not_paused]` – Fails if the contract is paused.
+    WhenNotPaused,
+}
+
+struct OnlyRoleArgs {
+    role: LitStr,
+    account: Ident,
+}
+
+impl Parse for OnlyRoleArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let role = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let account = input.parse()?;
+        Ok(Self { role, account })
+    }
+}
+
+impl AccessRule {
+    /// Returns the argument of the function the rule references, if any.
+    pub fn arg(&self) -> Option<&Ident> {
+        match self {
+            AccessRule::OnlyAdmin | AccessRule::NonReentrant | AccessRule::WhenNotPaused => None,
+            AccessRule::RequireAuth(arg) | AccessRule::OnlyRole(_, arg) => Some(arg),
+        }
+    }
+
+    /// Returns the line of documentation that describes the rule.
+    fn doc(&self) -> String {
+        match self {
+            AccessRule::OnlyAdmin => " Requires the authorization of the admin.".to_string(),
+            AccessRule::RequireAuth(arg) => format!(" Requires the authorization of `{arg}`."),
+            AccessRule::OnlyRole(role, arg) => format!(
+                " Requires `{arg}` to have the `{}` role, and the authorization of `{arg}`.",
+                role.value()
+            ),
+            AccessRule::NonReentrant => {
+                " Fails if called while a nonreentrant function of the contract is executing."
+                    .to_string()
+            }
+            AccessRule::WhenNotPaused => {
+                " Fails if called while the contract is paused.".to_string()
+            }
+        }
+    }
+
+    /// Returns the check of the rule, to run before the function is called
+    /// with the argument the rule references bound to `arg`.
+    pub fn check(&self, crate_path: &Path, arg: Option<&Ident>) -> TokenStream2 {
+        match self {
+            AccessRule::OnlyAdmin => quote! {
+                #crate_path::access::require_admin(&env);
+            },
+            AccessRule::RequireAuth(_) => quote! {
+                #crate_path::Address::require_auth(&#arg);
+            },
+            AccessRule::OnlyRole(role, _) => quote! {
+                #crate_path::access::require_role(
+                    &env,
+                    &#crate_path::Symbol::new(&env, #role),
+                    &#arg,
+                );
+            },
+            AccessRule::NonReentrant => quote! {
+                #crate_path::security::ReentrancyGuard::enter(&env);
+            },
+            AccessRule::WhenNotPaused => quote! {
+                #crate_path::security::Pausable::require_not_paused(&env);
+            },
+        }
+    }
+
+    /// Returns the code to run after the function is called, if any.
+    pub fn exit(&self, crate_path: &Path) -> Option<TokenStream2> {
+        match self {
+            AccessRule::NonReentrant => Some(quote! {
+                #crate_path::security::ReentrancyGuard::exit(&env);
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn is_access_attr(attr: &Attribute) -> bool {
+    let path = attr.path();
+    path.is_ident("only_admin")
+        || path.is_ident("require_auth")
+        || path.is_ident("only_role")
+        || path.is_ident("nonreentrant")
+        || path.is_ident("when_not_paused")
+}
+
+fn parse_path_attr(attr: &Attribute, rule: AccessRule) -> Result<AccessRule, Error> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(rule),
+        _ => Err(Error::new_spanned(attr, "attribute takes no arguments")),
+    }
+}
+
+fn parse_access_attr(attr: &Attribute) -> Result<AccessRule, Error> {
+    let path = attr.path();
+    if path.is_ident("only_admin") {
+        parse_path_attr(attr, AccessRule::OnlyAdmin)
+    } else if path.is_ident("nonreentrant") {
+        parse_path_attr(attr, AccessRule::NonReentrant)
+    } else if path.is_ident("when_not_paused") {
+        parse_path_attr(attr, AccessRule::WhenNotPaused)
+    } else if path.is_ident("require_auth") {
+        Ok(AccessRule::RequireAuth(attr.parse_args()?))
+    } else {
+        let args: OnlyRoleArgs = attr.parse_args()?;
+        let role = args.role.value();
+        if role.is_empty()
+            || role.len() > 32
+            || !role.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(Error::new(
+                args.role.span(),
+                "role must be a symbol of 1 to 32 characters in a-zA-Z0-9_",
+            ));
+        }
+        Ok(AccessRule::OnlyRole(args.role, args.account))
+    }
+}
+
+/// Removes the access rule attributes from the attributes of a function, and
+/// returns the rules they declare. A line of documentation is added to the
+/// attributes for each rule, so that the rules appear in the docs of the
+/// function in the contract spec and in clients.
+pub fn take_access_rules(attrs: &mut Vec<Attribute>) -> Result<Vec<AccessRule>, Error> {
+    let mut rules = Vec::new();
+    let mut errors = Vec::<Error>::new();
+    attrs.retain(|attr| {
+        if !is_access_attr(attr) {
+            return true;
+        }
+        match parse_access_attr(attr) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => errors.push(e),
+        }
+        false
+    });
+    if let Some(error) = errors.into_iter().reduce(|mut a, e| {
+        a.combine(e);
+        a
+    }) {
+        return Err(error);
+    }
+    if !rules.is_empty() && attrs.iter().any(|a| a.path().is_ident("doc")) {
+        attrs.push(parse_quote!(#[doc = ""]));
+    }
+    for rule in &rules {
+        let doc = rule.doc();
+        attrs.push(parse_quote!(#[doc = #doc]));
+    }
+    Ok(rules)
+}
@@ -1,14 +1,17 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::{DataEnum, DataStruct, Ident, Path, Visibility};
+use syn::{DataEnum, DataStruct, Fields, Ident, Path, Visibility};
 
+/// Derives arbitrary for a struct. Skipped fields are not included in the
+/// prototype, and are set to their default values.
 pub fn derive_arbitrary_struct(
     path: &Path,
     vis: &Visibility,
     ident: &Ident,
     data: &DataStruct,
+    skipped: &[&Ident],
 ) -> TokenStream2 {
-    derive_arbitrary_struct_common(path, vis, ident, data, FieldType::Named)
+    derive_arbitrary_struct_common(path, vis, ident, data, skipped, FieldType::Named)
 }
 
 pub fn derive_arbitrary_struct_tuple(
@@ -17,7 +20,7 @@ pub fn derive_arbitrary_struct_tuple(
     ident: &Ident,
     data: &DataStruct,
 ) -> TokenStream2 {
-    derive_arbitrary_struct_common(path, vis, ident, data, FieldType::Unnamed)
+    derive_arbitrary_struct_common(path, vis, ident, data, &[], FieldType::Unnamed)
 }
 
 enum FieldType {
@@ -25,18 +28,32 @@ enum FieldType {
     Unnamed,
 }
 
+/// Returns the type of the fields of a variant, or none for unit variants.
+fn variant_field_type(fields: &Fields) -> Option<FieldType> {
+    match fields {
+        Fields::Named(_) => Some(FieldType::Named),
+        Fields::Unnamed(_) => Some(FieldType::Unnamed),
+        Fields::Unit => None,
+    }
+}
+
 fn derive_arbitrary_struct_common(
     path: &Path,
     vis: &Visibility,
     ident: &Ident,
     data: &DataStruct,
+    skipped: &[&Ident],
     field_type: FieldType,
 ) -> TokenStream2 {
     let arbitrary_type_ident = format_ident!("Arbitrary{}", ident);
 
+    let is_skipped =
+        |field: &&syn::Field| field.ident.as_ref().is_some_and(|i| skipped.contains(&i));
+
     let arbitrary_type_fields: Vec<TokenStream2> = data
         .fields
         .iter()
+        .filter(|field| !is_skipped(field))
         .map(|field| {
             let field_type = &field.ty;
             match &field.ident {
@@ -59,6 +76,11 @@ fn derive_arbitrary_struct_common(
         .iter()
         .enumerate()
         .map(|(i, field)| match &field.ident {
+            Some(ident) if is_skipped(&field) => {
+                quote! {
+                    #ident: Default::default()
+                }
+            }
             Some(ident) => {
                 quote! {
                     #ident: #path::IntoVal::into_val(&v.#ident, env)
@@ -121,7 +143,7 @@ pub fn derive_arbitrary_enum(
         .variants
         .iter()
         .map(|variant| {
-            let mut field_types = None;
+            let field_types = variant_field_type(&variant.fields);
             let variant_ident = &variant.ident;
             let fields: Vec<TokenStream2> = variant
                 .fields
@@ -130,13 +152,11 @@ pub fn derive_arbitrary_enum(
                     let field_type = &field.ty;
                     match &field.ident {
                         Some(ident) => {
-                            field_types = Some(FieldType::Named);
                             quote! {
                                 #ident: <#field_type as #path::testutils::arbitrary::SorobanArbitrary>::Prototype
                             }
                         }
                         None => {
-                            field_types = Some(FieldType::Unnamed);
                             quote! {
                                 <#field_type as #path::testutils::arbitrary::SorobanArbitrary>::Prototype
                             }
@@ -168,7 +188,7 @@ pub fn derive_arbitrary_enum(
         .variants
         .iter()
         .map(|variant| {
-            let mut field_types = None;
+            let field_types = variant_field_type(&variant.fields);
             let variant_ident = &variant.ident;
             let fields: Vec<TokenStream2> = variant
                 .fields
@@ -197,13 +217,11 @@ pub fn derive_arbitrary_enum(
                 .map(|(i, field)| {
                     match &field.ident {
                        Some(ident) => {
-                            field_types = Some(FieldType::Named);
                             quote! {
                                 #ident: #path::IntoVal::into_val(#ident, env)
                             }
                         }
                         None => {
-                            field_types = Some(FieldType::Unnamed);
                             let ident = format_ident!("field_{}", i);
                             quote! {
                                 #path::IntoVal::into_val(#ident, env)
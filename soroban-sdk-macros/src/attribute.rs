@@ -1,4 +1,4 @@
-use syn::Attribute;
+use syn::{spanned::Spanned, Attribute, Data, Error, Field};
 
 /// Returns true if the attribute is an attribute that should be preserved and
 /// passed through to code generated for the item the attribute is on.
@@ -8,3 +8,32 @@ pub fn pass_through_attr_to_gen_code(attr: &Attribute) -> bool {
         || attr.path().is_ident("allow")
         || attr.path().is_ident("deny")
 }
+
+/// Removes the `contracttype` attributes from the fields of the struct. The
+/// attributes are read by the `contracttype` macro as field attributes, and are
+/// not attribute macros in their own right.
+pub fn strip_contracttype_field_attrs(data: &mut Data) {
+    if let Data::Struct(s) = data {
+        for field in s.fields.iter_mut() {
+            field
+                .attrs
+                .retain(|attr| !attr.path().is_ident("contracttype"));
+        }
+    }
+}
+
+/// Returns an error if the field has `contracttype` attributes, for fields of
+/// structs that do not support field attributes, such as tuple structs.
+pub fn check_no_contracttype_field_attrs(field: &Field) -> Result<(), Error> {
+    match field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("contracttype"))
+    {
+        Some(attr) => Err(Error::new(
+            attr.span(),
+            "contracttype field attributes are only supported on the fields of structs with named fields",
+        )),
+        None => Ok(()),
+    }
+}
@@ -43,6 +43,8 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                 mock_all_auths: bool,
                 #[doc(hidden)]
                 allow_non_root_auth: bool,
+                #[doc(hidden)]
+                sign_with: Option<&'a [&'a dyn #crate_path::testutils::AuthSigner]>,
             }
 
             impl<'a> #client_ident<'a> {
@@ -54,6 +56,7 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                         mock_auths: None,
                         mock_all_auths: false,
                         allow_non_root_auth: false,
+                        sign_with: None,
                     }
                 }
 
@@ -73,6 +76,7 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                         mock_auths: self.mock_auths.clone(),
                         mock_all_auths: false,
                         allow_non_root_auth: false,
+                        sign_with: None,
                     }
                 }
 
@@ -89,6 +93,7 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                         mock_auths: Some(mock_auths),
                         mock_all_auths: false,
                         allow_non_root_auth: false,
+                        sign_with: None,
                     }
                 }
 
@@ -106,6 +111,7 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                         mock_auths: None,
                         mock_all_auths: true,
                         allow_non_root_auth: false,
+                        sign_with: None,
                     }
                 }
 
@@ -126,6 +132,27 @@ pub fn derive_client_type(crate_path: &Path, ty: &str, name: &str) -> TokenStrea
                         mock_auths: None,
                         mock_all_auths: true,
                         allow_non_root_auth: true,
+                        sign_with: None,
+                    }
+                }
+
+                /// Sign the authorizations required by the invocation with the
+                /// signers, and set them in the environment so that the
+                /// invocation is performed in enforcing auth mode.
+                ///
+                /// Authorizations required by addresses that have no signer
+                /// fail.
+                ///
+                /// See `soroban_sdk::testutils::signed_auths` for more details.
+                pub fn sign_with(&self, signers: &'a [&'a dyn #crate_path::testutils::AuthSigner]) -> Self {
+                    Self {
+                        env: self.env.clone(),
+                        address: self.address.clone(),
+                        set_auths: None,
+                        mock_auths: None,
+                        mock_all_auths: false,
+                        allow_non_root_auth: false,
+                        sign_with: Some(signers),
                     }
                 }
             }
@@ -235,6 +262,8 @@ pub fn derive_client_impl(crate_path: &Path, name: &str, fns: &[syn_ext::Fn]) ->
                         let old_auth_manager = self.env.in_contract().not().then(||
                             self.env.host().snapshot_auth_manager().unwrap()
                         );
+                        use #crate_path::{IntoVal,FromVal};
+                        let args = #crate_path::vec![&self.env, #(#fn_input_names.into_val(&self.env)),*];
                         {
                             if let Some(set_auths) = self.set_auths {
                                 self.env.set_auths(set_auths);
@@ -249,12 +278,20 @@ pub fn derive_client_impl(crate_path: &Path, name: &str, fns: &[syn_ext::Fn]) ->
                                     self.env.mock_all_auths();
                                 }
                             }
+                            if let Some(sign_with) = self.sign_with {
+                                self.env.set_auths(&#crate_path::testutils::signed_auths(
+                                    &self.env,
+                                    &self.address,
+                                    &#fn_name_symbol,
+                                    &args,
+                                    sign_with,
+                                ));
+                            }
                         }
-                        use #crate_path::{IntoVal,FromVal};
                         let res = self.env.invoke_contract(
                             &self.address,
                             &#fn_name_symbol,
-                            #crate_path::vec![&self.env, #(#fn_input_names.into_val(&self.env)),*],
+                            args,
                         );
                         if let Some(old_auth_manager) = old_auth_manager {
                             self.env.host().set_auth_manager(old_auth_manager).unwrap();
@@ -268,6 +305,8 @@ pub fn derive_client_impl(crate_path: &Path, name: &str, fns: &[syn_ext::Fn]) ->
                         let old_auth_manager = self.env.in_contract().not().then(||
                             self.env.host().snapshot_auth_manager().unwrap()
                         );
+                        use #crate_path::{IntoVal,FromVal};
+                        let args = #crate_path::vec![&self.env, #(#fn_input_names.into_val(&self.env)),*];
                         {
                             if let Some(set_auths) = self.set_auths {
                                 self.env.set_auths(set_auths);
@@ -278,12 +317,20 @@ pub fn derive_client_impl(crate_path: &Path, name: &str, fns: &[syn_ext::Fn]) ->
                             if self.mock_all_auths {
                                 self.env.mock_all_auths();
                             }
+                            if let Some(sign_with) = self.sign_with {
+                                self.env.set_auths(&#crate_path::testutils::signed_auths(
+                                    &self.env,
+                                    &self.address,
+                                    &#fn_name_symbol,
+                                    &args,
+                                    sign_with,
+                                ));
+                            }
                         }
-                        use #crate_path::{IntoVal,FromVal};
                         let res = self.env.try_invoke_contract(
                             &self.address,
                             &#fn_name_symbol,
-                            #crate_path::vec![&self.env, #(#fn_input_names.into_val(&self.env)),*],
+                            args,
                         );
                         if let Some(old_auth_manager) = old_auth_manager {
                             self.env.host().set_auth_manager(old_auth_manager).unwrap();
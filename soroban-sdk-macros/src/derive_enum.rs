@@ -1,20 +1,35 @@
-use itertools::MultiUnzip;
+use itertools::Itertools;
 use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
-use syn::{spanned::Spanned, Attribute, DataEnum, Error, Fields, Ident, Path, Visibility};
+use syn::{
+    spanned::Spanned, Attribute, DataEnum, Error, Fields, Generics, Ident, Path, Type, Visibility,
+};
 
+use soroban_spec::marker::ENUM_VARIANT_STRUCT_DOC;
 use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::{
-    Error as XdrError, ScSpecEntry, ScSpecTypeDef, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0,
-    ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0, StringM, VecM, WriteXdr, SCSYMBOL_LIMIT,
+    Error as XdrError, ScSpecEntry, ScSpecTypeDef, ScSpecTypeUdt, ScSpecUdtStructFieldV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+    ScSpecUdtUnionV0, StringM, VecM, WriteXdr, SCSYMBOL_LIMIT,
 };
 
-use crate::{doc::docs_from_attrs, map_type::map_type, DEFAULT_XDR_RW_LIMITS};
+use crate::{
+    doc::docs_from_attrs,
+    generic::{
+        check_no_param_instances, conversion_where_clauses, derive_instances_spec,
+        derive_spec_template, mark_type_params, name_marker, type_params,
+    },
+    map_type::map_type,
+    spec_template::{derive_spec_type_def_udt, derive_xdr, mark_resolved_types},
+    DEFAULT_XDR_RW_LIMITS,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn derive_type_enum(
     path: &Path,
     vis: &Visibility,
     enum_ident: &Ident,
+    generics: &Generics,
     attrs: &[Attribute],
     data: &DataEnum,
     spec: bool,
@@ -22,7 +37,13 @@ pub fn derive_type_enum(
 ) -> TokenStream2 {
     // Collect errors as they are encountered and emit them at the end.
     let mut errors = Vec::<Error>::new();
+    let params = type_params(generics).unwrap_or_else(|e| {
+        errors.push(e);
+        vec![]
+    });
+    let is_generic = !params.is_empty();
 
+    let mut types = Vec::<Type>::new();
     let variants = &data.variants;
     if variants.is_empty() {
         errors.push(Error::new(
@@ -30,14 +51,15 @@ pub fn derive_type_enum(
             format!("enum {} must have variants", enum_ident),
         ));
     }
-    let (spec_cases, case_name_str_lits, try_froms, try_intos, try_from_xdrs, into_xdrs): (
-        Vec<_>,
-        Vec<_>,
-        Vec<_>,
-        Vec<_>,
-        Vec<_>,
-        Vec<_>,
-    ) = variants
+    let (
+        spec_cases,
+        spec_structs,
+        case_name_str_lits,
+        try_froms,
+        try_intos,
+        try_from_xdrs,
+        into_xdrs,
+    ): (Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>) = variants
         .iter()
         .enumerate()
         .map(|(case_num, variant)| {
@@ -59,31 +81,35 @@ pub fn derive_type_enum(
             }
 
             match variant.fields {
-                Fields::Named(_) => {
-                    errors.push(Error::new(
-                        variant.fields.span(),
-                        format!("enum variant {} has unsupported named fields", case_ident),
-                    ));
-                }
-                Fields::Unnamed(_) if variant.fields.is_empty() => {
-                    // Empty tuples are unsupported because it would require extra complexity
-                    // to distinguish them from unit-style variants.
+                Fields::Named(_) if is_generic => {
                     errors.push(Error::new(
                         variant.fields.span(),
-                        format!("enum variant {} is unsupported 0-element tuple", case_ident),
+                        format!(
+                            "enum variant {} has named fields, which are unsupported on generic enums",
+                            case_ident
+                        ),
                     ));
                 }
                 _ => {}
             }
-            let is_unit_variant = variant.fields == Fields::Unit;
-            if !is_unit_variant {
-                let VariantTokens {
-                    spec_case,
-                    try_from,
-                    try_into,
-                    try_from_xdr,
-                    into_xdr,
-                } = map_tuple_variant(
+            let VariantTokens {
+                spec_case,
+                spec_struct,
+                try_from,
+                try_into,
+                try_from_xdr,
+                into_xdr,
+            } = match &variant.fields {
+                Fields::Unit => map_empty_variant(
+                    path,
+                    enum_ident,
+                    &case_num_lit,
+                    &case_name_str_lit,
+                    case_name,
+                    case_ident,
+                    &variant.attrs,
+                ),
+                Fields::Named(_) => map_named_variant(
                     path,
                     enum_ident,
                     &case_num_lit,
@@ -93,23 +119,8 @@ pub fn derive_type_enum(
                     &variant.attrs,
                     &variant.fields,
                     &mut errors,
-                );
-                (
-                    spec_case,
-                    case_name_str_lit,
-                    try_from,
-                    try_into,
-                    try_from_xdr,
-                    into_xdr,
-                )
-            } else {
-                let VariantTokens {
-                    spec_case,
-                    try_from,
-                    try_into,
-                    try_from_xdr,
-                    into_xdr,
-                } = map_empty_variant(
+                ),
+                Fields::Unnamed(_) => map_tuple_variant(
                     path,
                     enum_ident,
                     &case_num_lit,
@@ -117,27 +128,62 @@ pub fn derive_type_enum(
                     case_name,
                     case_ident,
                     &variant.attrs,
-                );
-                (
-                    spec_case,
-                    case_name_str_lit,
-                    try_from,
-                    try_into,
-                    try_from_xdr,
-                    into_xdr,
-                )
-            }
+                    &variant.fields,
+                    &params,
+                    &mut types,
+                    &mut errors,
+                ),
+            };
+            (
+                spec_case,
+                spec_struct,
+                case_name_str_lit,
+                try_from,
+                try_into,
+                try_from_xdr,
+                into_xdr,
+            )
         })
         .multiunzip();
 
+    // Spec of the instances of generic types referenced by the variants.
+    let field_types = variants.iter().flat_map(|v| v.fields.iter().map(|f| &f.ty));
+    let instances_spec = if spec {
+        derive_instances_spec(path, field_types.clone()).unwrap_or_else(|e| {
+            errors.push(e);
+            TokenStream2::new()
+        })
+    } else {
+        TokenStream2::new()
+    };
+
     // If errors have occurred, render them instead.
     if !errors.is_empty() {
         let compile_errors = errors.iter().map(Error::to_compile_error);
         return quote! { #(#compile_errors)* };
     }
 
-    // Generated code spec.
-    let spec_gen = if spec {
+    // Generated code spec. Generic types have no spec entry of their own, and
+    // instead have a template that a spec entry is generated from for each
+    // instance of the type referenced in the contract.
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (try_from_where, try_into_where) =
+        conversion_where_clauses(path, generics, &params, field_types);
+    let spec_gen = if is_generic {
+        let spec_entry = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+            doc: docs_from_attrs(attrs),
+            lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
+            name: name_marker(),
+            cases: spec_cases.try_into().unwrap(),
+        });
+        let template = derive_spec_template(path, &spec_entry, params.len(), &types);
+        let where_clause = &generics.where_clause;
+        Some(quote! {
+            impl #impl_generics #enum_ident #ty_generics #where_clause {
+                #template
+            }
+        })
+    } else if spec {
         let spec_entry = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
             doc: docs_from_attrs(attrs),
             lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
@@ -145,16 +191,36 @@ pub fn derive_type_enum(
             cases: spec_cases.try_into().unwrap(),
         });
         let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
-        let spec_xdr_lit = proc_macro2::Literal::byte_string(spec_xdr.as_slice());
-        let spec_xdr_len = spec_xdr.len();
+        let (spec_xdr_len, spec_xdr_value) = derive_xdr(path, &spec_xdr, &types);
         let spec_ident = format_ident!("__SPEC_XDR_TYPE_{}", enum_ident.to_string().to_uppercase());
+        // Variants with named fields have their fields exported as a struct
+        // spec entry of their own.
+        let spec_structs = spec_structs
+            .into_iter()
+            .flatten()
+            .map(|(mut spec_struct, types)| {
+                spec_struct.lib = lib.as_deref().unwrap_or_default().try_into().unwrap();
+                let spec_struct_ident = format_ident!(
+                    "__SPEC_XDR_TYPE_{}",
+                    spec_struct.name.to_utf8_string_lossy().to_uppercase()
+                );
+                let spec_entry = ScSpecEntry::UdtStructV0(spec_struct);
+                let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+                let (spec_xdr_len, spec_xdr_value) = derive_xdr(path, &spec_xdr, &types);
+                quote! {
+                    #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
+                    pub static #spec_struct_ident: [u8; #spec_xdr_len] = #spec_xdr_value;
+                }
+            });
         Some(quote! {
             #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
             pub static #spec_ident: [u8; #spec_xdr_len] = #enum_ident::spec_xdr();
 
+            #(#spec_structs)*
+
             impl #enum_ident {
                 pub const fn spec_xdr() -> [u8; #spec_xdr_len] {
-                    *#spec_xdr_lit
+                    #spec_xdr_value
                 }
             }
         })
@@ -162,11 +228,18 @@ pub fn derive_type_enum(
         None
     };
 
+    // Contract types are referenced in specs by name. Generic types are
+    // referenced by the names of their instances.
+    let spec_type_def =
+        (!is_generic).then(|| derive_spec_type_def_udt(path, enum_ident, &enum_ident.to_string()));
+
     // Output.
     let mut output = quote! {
         #spec_gen
+        #spec_type_def
+        #instances_spec
 
-        impl #path::TryFromVal<#path::Env, #path::Val> for #enum_ident {
+        impl #impl_generics #path::TryFromVal<#path::Env, #path::Val> for #enum_ident #ty_generics #try_from_where {
             type Error = #path::ConversionError;
             #[inline(always)]
             fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
@@ -182,10 +255,10 @@ pub fn derive_type_enum(
             }
         }
 
-        impl #path::TryFromVal<#path::Env, #enum_ident> for #path::Val {
+        impl #impl_generics #path::TryFromVal<#path::Env, #enum_ident #ty_generics> for #path::Val #try_into_where {
             type Error = #path::ConversionError;
             #[inline(always)]
-            fn try_from_val(env: &#path::Env, val: &#enum_ident) -> Result<Self, #path::ConversionError> {
+            fn try_from_val(env: &#path::Env, val: &#enum_ident #ty_generics) -> Result<Self, #path::ConversionError> {
                 use #path::{TryIntoVal,TryFromVal};
                 match val {
                     #(#try_intos,)*
@@ -194,8 +267,9 @@ pub fn derive_type_enum(
         }
     };
 
-    // Additional output when testutils are enabled.
-    if cfg!(feature = "testutils") {
+    // Additional output when testutils are enabled. Generic types are only
+    // convertible to and from Val.
+    if cfg!(feature = "testutils") && !is_generic {
         let arbitrary_tokens = crate::arbitrary::derive_arbitrary_enum(path, vis, enum_ident, data);
         output.extend(quote! {
             impl #path::TryFromVal<#path::Env, #path::xdr::ScVec> for #enum_ident {
@@ -272,6 +346,7 @@ pub fn derive_type_enum(
 
 struct VariantTokens {
     spec_case: ScSpecUdtUnionCaseV0,
+    spec_struct: Option<(ScSpecUdtStructV0, Vec<Type>)>,
     try_from: TokenStream2,
     try_into: TokenStream2,
     try_from_xdr: TokenStream2,
@@ -323,6 +398,7 @@ fn map_empty_variant(
 
     VariantTokens {
         spec_case,
+        spec_struct: None,
         try_from,
         try_into,
         try_from_xdr,
@@ -330,6 +406,7 @@ fn map_empty_variant(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn map_tuple_variant(
     path: &Path,
     enum_ident: &Ident,
@@ -339,16 +416,27 @@ fn map_tuple_variant(
     case_ident: &Ident,
     attrs: &[Attribute],
     fields: &Fields,
+    params: &[Ident],
+    types: &mut Vec<Type>,
     errors: &mut Vec<Error>,
 ) -> VariantTokens {
     let spec_case = {
         let field_types = fields
             .iter()
-            .map(|f| match map_type(&f.ty, false) {
-                Ok(t) => t,
-                Err(e) => {
+            .map(|f| {
+                if let Err(e) = check_no_param_instances(&f.ty, params) {
                     errors.push(e);
-                    ScSpecTypeDef::I32
+                }
+                match map_type(&f.ty, false) {
+                    Ok(mut t) => {
+                        mark_resolved_types(&mut t, &f.ty, params, types);
+                        mark_type_params(&mut t, params);
+                        t
+                    }
+                    Err(e) => {
+                        errors.push(e);
+                        ScSpecTypeDef::I32
+                    }
                 }
             })
             .collect::<Vec<_>>();
@@ -388,7 +476,7 @@ fn map_tuple_variant(
             .enumerate()
             .map(|(_i, _f)| {
                 quote! {
-                    iter.next().ok_or(#path::ConversionError)??.try_into_val(env)?
+                    iter.next().ok_or(#path::ConversionError)??.try_into_val(env).map_err(|_| #path::ConversionError)?
                 }
             })
             .collect::<Vec<_>>();
@@ -408,7 +496,7 @@ fn map_tuple_variant(
             .map(|(i, _f)| {
                 let binding_name = format_ident!("value{i}");
                 let field_conv = quote! {
-                    #binding_name.try_into_val(env)?
+                    #binding_name.try_into_val(env).map_err(|_| #path::ConversionError)?
                 };
                 let tup_elem_type = quote! {
                     #path::Val
@@ -462,6 +550,174 @@ fn map_tuple_variant(
 
     VariantTokens {
         spec_case,
+        spec_struct: None,
+        try_from,
+        try_into,
+        try_from_xdr,
+        into_xdr,
+    }
+}
+
+/// Maps a variant with named fields. The fields are stored as a map in the
+/// same way as the fields of a struct, following the name of the variant, and
+/// are exported in the spec as a struct named after the enum and the variant,
+/// with the doc set to [`ENUM_VARIANT_STRUCT_DOC`] to mark it as synthesized.
+#[allow(clippy::too_many_arguments)]
+fn map_named_variant(
+    path: &Path,
+    enum_ident: &Ident,
+    case_num_lit: &Literal,
+    case_name_str_lit: &Literal,
+    case_name: &str,
+    case_ident: &Ident,
+    attrs: &[Attribute],
+    fields: &Fields,
+    errors: &mut Vec<Error>,
+) -> VariantTokens {
+    let struct_name = format!("{enum_ident}{case_ident}");
+    let fields = fields
+        .iter()
+        .sorted_by_key(|field| field.ident.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+    let field_count_usize = fields.len();
+    let field_idents = fields
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let field_names = field_idents
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>();
+    let field_idx_lits = (0..field_count_usize).map(Literal::usize_unsuffixed);
+    let mut types = Vec::<Type>::new();
+
+    let spec_fields = fields
+        .iter()
+        .zip(&field_names)
+        .map(|(field, field_name)| ScSpecUdtStructFieldV0 {
+            doc: docs_from_attrs(&field.attrs),
+            name: field_name.clone().try_into().unwrap_or_else(|_| {
+                const MAX: u32 = 30;
+                errors.push(Error::new(
+                    field.ident.span(),
+                    format!(
+                        "enum variant field name is too long: {}, max is {MAX}",
+                        field_name.len()
+                    ),
+                ));
+                StringM::<MAX>::default()
+            }),
+            type_: match map_type(&field.ty, false) {
+                Ok(mut t) => {
+                    mark_resolved_types(&mut t, &field.ty, &[], &mut types);
+                    t
+                }
+                Err(e) => {
+                    errors.push(e);
+                    ScSpecTypeDef::I32
+                }
+            },
+        })
+        .collect::<Vec<_>>();
+    let spec_struct = ScSpecUdtStructV0 {
+        doc: ENUM_VARIANT_STRUCT_DOC.try_into().unwrap(),
+        lib: StringM::default(),
+        name: struct_name.clone().try_into().unwrap_or_else(|_| {
+            const MAX: u32 = 60;
+            errors.push(Error::new(
+                case_ident.span(),
+                format!(
+                    "enum and variant name is too long: {}, max is {MAX}",
+                    struct_name.len()
+                ),
+            ));
+            StringM::<MAX>::default()
+        }),
+        fields: spec_fields.try_into().unwrap_or_else(|_| {
+            errors.push(Error::new(
+                case_ident.span(),
+                format!("enum variant {case_ident} has too many fields"),
+            ));
+            VecM::default()
+        }),
+    };
+    let spec_case = ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+        doc: docs_from_attrs(attrs),
+        name: case_name.try_into().unwrap_or_else(|_| StringM::default()),
+        type_: [ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: spec_struct.name.clone(),
+        })]
+        .try_into()
+        .unwrap(),
+    });
+
+    let try_from = quote! {
+        #case_num_lit => {
+            if iter.len() > 1 {
+                return Err(#path::ConversionError);
+            }
+            const KEYS: [&'static str; #field_count_usize] = [#(#field_names),*];
+            let mut vals: [#path::Val; #field_count_usize] = [#path::Val::VOID.to_val(); #field_count_usize];
+            let map: #path::MapObject = iter.next().ok_or(#path::ConversionError)??.try_into().map_err(|_| #path::ConversionError)?;
+            env.map_unpack_to_slice(map, &KEYS, &mut vals).map_err(|_| #path::ConversionError)?;
+            Self::#case_ident {
+                #(#field_idents: vals[#field_idx_lits].try_into_val(env).map_err(|_| #path::ConversionError)?,)*
+            }
+        }
+    };
+    let try_into = quote! {
+        #enum_ident::#case_ident { #(ref #field_idents,)* } => {
+            use #path::EnvBase;
+            const KEYS: [&'static str; #field_count_usize] = [#(#field_names),*];
+            let vals: [#path::Val; #field_count_usize] = [
+                #(#field_idents.try_into_val(env).map_err(|_| #path::ConversionError)?),*
+            ];
+            let map: #path::Val = env.map_new_from_slices(&KEYS, &vals).map_err(|_| #path::ConversionError)?.into();
+            let tup: (#path::Val, #path::Val) = (#path::Symbol::try_from_val(env, &#case_name_str_lit)?.to_val(), map);
+            tup.try_into_val(env).map_err(Into::into)
+        }
+    };
+    let try_from_xdr = quote! {
+        #case_name => {
+            if iter.len() > 1 {
+                return Err(#path::xdr::Error::Invalid);
+            }
+            let map = match iter.next() {
+                Some(#path::xdr::ScVal::Map(Some(map))) => map,
+                _ => return Err(#path::xdr::Error::Invalid),
+            };
+            if map.len() != #field_count_usize {
+                return Err(#path::xdr::Error::Invalid);
+            }
+            map.validate()?;
+            Self::#case_ident {
+                #(#field_idents: {
+                    let key: #path::xdr::ScVal = #path::xdr::ScSymbol(#field_names.try_into().map_err(|_| #path::xdr::Error::Invalid)?).into();
+                    let idx = map.binary_search_by_key(&key, |entry| entry.key.clone()).map_err(|_| #path::xdr::Error::Invalid)?;
+                    let rv: #path::Val = (&map[idx].val.clone()).try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
+                    rv.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?
+                },)*
+            }
+        }
+    };
+    let into_xdr = quote! {
+        #enum_ident::#case_ident { #(#field_idents,)* } => {
+            let map = #path::xdr::ScMap::sorted_from::<_, #path::xdr::ScMapEntry>(alloc::vec![
+                #(#path::xdr::ScMapEntry {
+                    key: #path::xdr::ScSymbol(#field_names.try_into().map_err(|_| #path::xdr::Error::Invalid)?).into(),
+                    val: #field_idents.try_into().map_err(|_| #path::xdr::Error::Invalid)?,
+                },)*
+            ])?;
+            (
+                #path::xdr::ScSymbol(#case_name.try_into().map_err(|_| #path::xdr::Error::Invalid)?),
+                #path::xdr::ScVal::Map(Some(map)),
+            ).try_into().map_err(|_| #path::xdr::Error::Invalid)?
+        }
+    };
+
+    VariantTokens {
+        spec_case,
+        spec_struct: Some((spec_struct, types)),
         try_from,
         try_into,
         try_from_xdr,
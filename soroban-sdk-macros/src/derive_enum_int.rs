@@ -7,7 +7,7 @@ use syn::{spanned::Spanned, Attribute, DataEnum, Error, ExprLit, Ident, Lit, Pat
 
 use stellar_xdr::{ScSpecEntry, ScSpecUdtEnumCaseV0, WriteXdr};
 
-use crate::{doc::docs_from_attrs, DEFAULT_XDR_RW_LIMITS};
+use crate::{doc::docs_from_attrs, spec_template::derive_spec_type_def_udt, DEFAULT_XDR_RW_LIMITS};
 
 // TODO: Add conversions to/from ScVal types.
 
@@ -91,9 +91,12 @@ pub fn derive_type_enum_int(
         None
     };
 
+    let spec_type_def = derive_spec_type_def_udt(path, enum_ident, &enum_ident.to_string());
+
     // Output.
     let mut output = quote! {
         #spec_gen
+        #spec_type_def
 
         impl #path::TryFromVal<#path::Env, #path::Val> for #enum_ident {
             type Error = #path::ConversionError;
@@ -5,7 +5,7 @@ use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::{ScSpecEntry, ScSpecUdtErrorEnumCaseV0, ScSpecUdtErrorEnumV0, StringM, WriteXdr};
 use syn::{spanned::Spanned, Attribute, DataEnum, Error, ExprLit, Ident, Lit, Path};
 
-use crate::{doc::docs_from_attrs, DEFAULT_XDR_RW_LIMITS};
+use crate::{doc::docs_from_attrs, spec_template::derive_spec_type_def_udt, DEFAULT_XDR_RW_LIMITS};
 
 pub fn derive_type_error_enum_int(
     path: &Path,
@@ -89,9 +89,12 @@ pub fn derive_type_error_enum_int(
         None
     };
 
+    let spec_type_def = derive_spec_type_def_udt(path, enum_ident, &enum_ident.to_string());
+
     // Output.
     quote! {
         #spec_gen
+        #spec_type_def
 
         impl TryFrom<#path::Error> for #enum_ident {
             type Error = #path::Error;
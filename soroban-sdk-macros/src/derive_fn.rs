@@ -1,4 +1,4 @@
-use crate::{attribute::pass_through_attr_to_gen_code, map_type::map_type};
+use crate::{access::AccessRule, attribute::pass_through_attr_to_gen_code, map_type::map_type};
 use itertools::MultiUnzip;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
@@ -19,6 +19,7 @@ pub fn derive_pub_fn(
     inputs: &Punctuated<FnArg, Comma>,
     trait_ident: Option<&Ident>,
     client_ident: &str,
+    access_rules: &[AccessRule],
 ) -> Result<TokenStream2, TokenStream2> {
     // Collect errors as they are encountered and emit them at the end.
     let mut errors = Vec::<Error>::new();
@@ -50,9 +51,9 @@ pub fn derive_pub_fn(
     });
 
     // Prepare the argument inputs.
-    let (wrap_args, passthrough_calls, wrap_calls): (Vec<_>, Vec<_>, Vec<_>) = inputs
-        .iter()
-        .skip(if env_input.is_some() { 1 } else { 0 })
+    let fn_inputs = inputs.iter().skip(if env_input.is_some() { 1 } else { 0 });
+    let (wrap_args, passthrough_calls, wrap_calls): (Vec<_>, Vec<_>, Vec<_>) = fn_inputs
+        .clone()
         .enumerate()
         .map(|(i, a)| match a {
             FnArg::Typed(pat_ty) => {
@@ -120,6 +121,65 @@ pub fn derive_pub_fn(
         quote! {}
     };
 
+    // Prepare the checks of the access rules, that run on the converted
+    // arguments before the function is called.
+    let access_checks: Vec<TokenStream2> = access_rules
+        .iter()
+        .map(|rule| {
+            let arg = rule.arg().and_then(|name| {
+                let i = fn_inputs.clone().position(|a| match a {
+                    FnArg::Typed(pat_ty) => {
+                        matches!(&*pat_ty.pat, Pat::Ident(p) if &p.ident == name)
+                    }
+                    FnArg::Receiver(_) => false,
+                });
+                if i.is_none() {
+                    errors.push(Error::new(
+                        name.span(),
+                        format!("access rule references unknown argument `{name}`"),
+                    ));
+                }
+                i.map(|i| format_ident!("arg_{}", i))
+            });
+            rule.check(crate_path, arg.as_ref())
+        })
+        .collect();
+    let access_exits: Vec<TokenStream2> = access_rules
+        .iter()
+        .rev()
+        .filter_map(|rule| rule.exit(crate_path))
+        .collect();
+    let call_body = if access_checks.is_empty() {
+        quote! {
+            <_ as #crate_path::IntoVal<#crate_path::Env, #crate_path::Val>>::into_val(
+                #[allow(deprecated)]
+                &#call(
+                    #env_call
+                    #(#wrap_calls),*
+                ),
+                &env
+            )
+        }
+    } else {
+        quote! {
+            #(
+                #[allow(deprecated)]
+                let #passthrough_calls = #wrap_calls;
+            )*
+            #(#access_checks)*
+            let rv = <_ as #crate_path::IntoVal<#crate_path::Env, #crate_path::Val>>::into_val(
+                #[allow(deprecated)]
+                &#call(
+                    #env_call
+                    #(#passthrough_calls),*
+                ),
+                &env
+            );
+            #(#access_exits)*
+            rv
+        }
+    };
+
     // If errors have occurred, render them instead.
     if !errors.is_empty() {
         let compile_errors = errors.iter().map(Error::to_compile_error);
@@ -160,14 +220,7 @@ pub fn derive_pub_fn(
             #[deprecated(note = #deprecated_note)]
             pub fn invoke_raw(env: #crate_path::Env, #(#wrap_args),*) -> #crate_path::Val {
                 #use_trait;
-                <_ as #crate_path::IntoVal<#crate_path::Env, #crate_path::Val>>::into_val(
-                    #[allow(deprecated)]
-                    &#call(
-                        #env_call
-                        #(#wrap_calls),*
-                    ),
-                    &env
-                )
+                #call_body
             }
 
             #testutils_only_code
@@ -8,14 +8,20 @@ use stellar_xdr::{
 use syn::TypeReference;
 use syn::{
     punctuated::Punctuated, spanned::Spanned, token::Comma, Attribute, Error, FnArg, Ident, Pat,
-    ReturnType, Type, TypePath,
+    Path, ReturnType, Type, TypePath,
 };
 
 use crate::attribute::pass_through_attr_to_gen_code;
-use crate::{doc::docs_from_attrs, map_type::map_type, DEFAULT_XDR_RW_LIMITS};
+use crate::{
+    doc::docs_from_attrs,
+    map_type::map_type,
+    spec_template::{derive_xdr, mark_resolved_types},
+    DEFAULT_XDR_RW_LIMITS,
+};
 
 #[allow(clippy::too_many_arguments)]
 pub fn derive_fn_spec(
+    path: &Path,
     ty: &Type,
     ident: &Ident,
     attrs: &[Attribute],
@@ -26,6 +32,9 @@ pub fn derive_fn_spec(
     // Collect errors as they are encountered and emit them at the end.
     let mut errors = Vec::<Error>::new();
 
+    // Collect the types referenced whose spec types are resolved by the compiler.
+    let mut types = Vec::<Type>::new();
+
     // Prepare the env input.
     let env_input = inputs.first().and_then(|a| match a {
         FnArg::Typed(pat_type) => {
@@ -69,7 +78,8 @@ pub fn derive_fn_spec(
                 let allow_hash = ident == "__check_auth" && i == 0;
 
                 match map_type(&pat_type.ty, allow_hash) {
-                    Ok(type_) => {
+                    Ok(mut type_) => {
+                        mark_resolved_types(&mut type_, &pat_type.ty, &[], &mut types);
                         let name = name.try_into().unwrap_or_else(|_| {
                             const MAX: u32 = 30;
                             errors.push(Error::new(
@@ -108,7 +118,10 @@ pub fn derive_fn_spec(
     // Prepare the output.
     let spec_result = match output {
         ReturnType::Type(_, ty) => vec![match map_type(ty, true) {
-            Ok(spec) => spec,
+            Ok(mut spec) => {
+                mark_resolved_types(&mut spec, ty, &[], &mut types);
+                spec
+            }
             Err(e) => {
                 errors.push(e);
                 ScSpecTypeDef::I32
@@ -146,8 +159,7 @@ pub fn derive_fn_spec(
         outputs: spec_result.try_into().unwrap(),
     });
     let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
-    let spec_xdr_lit = proc_macro2::Literal::byte_string(spec_xdr.as_slice());
-    let spec_xdr_len = spec_xdr.len();
+    let (spec_xdr_len, spec_xdr_value) = derive_xdr(path, &spec_xdr, &types);
     let spec_ident = format_ident!("__SPEC_XDR_FN_{}", ident.to_string().to_uppercase());
     let spec_fn_ident = format_ident!("spec_xdr_{}", ident.to_string());
 
@@ -184,7 +196,7 @@ pub fn derive_fn_spec(
             #[allow(non_snake_case)]
             #(#attrs)*
             pub const fn #spec_fn_ident() -> [u8; #spec_xdr_len] {
-                *#spec_xdr_lit
+                #spec_xdr_value
             }
         }
     })
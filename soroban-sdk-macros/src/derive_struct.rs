@@ -1,23 +1,52 @@
+use darling::FromField;
 use itertools::Itertools;
 use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
-use syn::{Attribute, DataStruct, Error, Ident, Path, Visibility};
+use syn::{
+    spanned::Spanned, Attribute, DataStruct, Error, Field, Generics, Ident, Path, Type, Visibility,
+};
 
 use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::{
     ScSpecEntry, ScSpecTypeDef, ScSpecUdtStructFieldV0, ScSpecUdtStructV0, StringM, WriteXdr,
 };
 
-use crate::{doc::docs_from_attrs, map_type::map_type, DEFAULT_XDR_RW_LIMITS};
+use crate::{
+    doc::docs_from_attrs,
+    generic::{
+        check_no_param_instances, conversion_where_clauses, derive_instances_spec,
+        derive_spec_template, mark_type_params, name_marker, type_params,
+    },
+    map_type::map_type,
+    spec_template::{derive_spec_type_def_udt, derive_xdr, mark_resolved_types},
+    DEFAULT_XDR_RW_LIMITS,
+};
+
+// TODO: Better handling of partial types and types with private fields.
 
-// TODO: Add field attribute for including/excluding fields in types.
-// TODO: Better handling of partial types and types without all their fields and
-// types with private fields.
+/// Field attributes of contract type structs, e.g.
+/// `#[contracttype(rename = "bal")]`.
+#[derive(Debug, FromField)]
+#[darling(attributes(contracttype))]
+struct FieldArgs {
+    /// The name of the field in storage and in the spec, instead of the name
+    /// of the field.
+    rename: Option<String>,
+    /// The field is set to its default value when missing from a stored value.
+    #[darling(default)]
+    default: bool,
+    /// The field is not stored, not included in the spec, and is set to its
+    /// default value when converted from a stored value.
+    #[darling(default)]
+    skip: bool,
+}
 
+#[allow(clippy::too_many_arguments)]
 pub fn derive_type_struct(
     path: &Path,
     vis: &Visibility,
     ident: &Ident,
+    generics: &Generics,
     attrs: &[Attribute],
     data: &DataStruct,
     spec: bool,
@@ -25,15 +54,67 @@ pub fn derive_type_struct(
 ) -> TokenStream2 {
     // Collect errors as they are encountered and emit them at the end.
     let mut errors = Vec::<Error>::new();
+    let params = type_params(generics).unwrap_or_else(|e| {
+        errors.push(e);
+        vec![]
+    });
+    let is_generic = !params.is_empty();
     let fields = &data.fields;
-    let field_count_usize: usize = fields.len();
-    let (spec_fields, field_idents, field_names, field_idx_lits, try_from_xdrs, try_into_xdrs): (Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>) = fields
+
+    // Fields that are skipped are not stored. Stored fields are stored under
+    // their names, or the name they are renamed to, in the order of the names.
+    let mut skipped = Vec::<&Ident>::new();
+    let stored: Vec<(&Field, String, bool)> = fields
+        .iter()
+        .filter_map(|field| {
+            let field_ident = field.ident.as_ref().unwrap();
+            let args = match FieldArgs::from_field(field) {
+                Ok(args) => args,
+                Err(e) => {
+                    errors.push(e.into());
+                    return None;
+                }
+            };
+            if args.skip {
+                if args.rename.is_some() || args.default {
+                    errors.push(Error::new(
+                        field.span(),
+                        "skipped fields cannot also be renamed or have a default",
+                    ));
+                }
+                skipped.push(field_ident);
+                return None;
+            }
+            let field_name = args.rename.unwrap_or_else(|| field_ident.to_string());
+            if !field_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                errors.push(Error::new(
+                    field.span(),
+                    format!("struct field name {field_name:?} must only contain the characters a-zA-Z0-9_"),
+                ));
+            }
+            Some((field, field_name, args.default))
+        })
+        .sorted_by(|(_, a, _), (_, b, _)| a.cmp(b))
+        .collect();
+    for ((_, a, _), (field, b, _)) in stored.iter().tuple_windows() {
+        if a == b {
+            errors.push(Error::new(
+                field.span(),
+                format!("struct field name {b:?} is used by more than one field"),
+            ));
+        }
+    }
+    let field_count_usize: usize = stored.len();
+    let has_defaults = stored.iter().any(|(_, _, default)| *default);
+    let required_count_usize = stored.iter().filter(|(_, _, default)| !*default).count();
+
+    let mut types = Vec::<Type>::new();
+    let (spec_fields, field_idents, field_names, field_idx_lits, field_reads, try_from_xdrs, try_into_xdrs): (Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>, Vec<_>) = stored
         .iter()
-        .sorted_by_key(|field| field.ident.as_ref().unwrap().to_string())
         .enumerate()
-        .map(|(field_num, field)| {
+        .map(|(field_num, (field, field_name, default))| {
             let field_ident = field.ident.as_ref().unwrap();
-            let field_name = field_ident.to_string();
+            let field_name = field_name.clone();
             let field_idx_lit = Literal::usize_unsuffixed(field_num);
             let spec_field = ScSpecUdtStructFieldV0 {
                 doc: docs_from_attrs(&field.attrs),
@@ -43,19 +124,59 @@ pub fn derive_type_struct(
                     StringM::<MAX>::default()
                 }),
                 type_: match map_type(&field.ty, false) {
-                    Ok(t) => t,
+                    Ok(mut t) => {
+                        mark_resolved_types(&mut t, &field.ty, &params, &mut types);
+                        mark_type_params(&mut t, &params);
+                        t
+                    }
                     Err(e) => {
                         errors.push(e);
                         ScSpecTypeDef::I32
                     }
                 },
             };
-            let try_from_xdr = quote! {
-                #field_ident: {
-                    let key: #path::xdr::ScVal = #path::xdr::ScSymbol(#field_name.try_into().map_err(|_| #path::xdr::Error::Invalid)?).into();
-                    let idx = map.binary_search_by_key(&key, |entry| entry.key.clone()).map_err(|_| #path::xdr::Error::Invalid)?;
-                    let rv: #path::Val = (&map[idx].val.clone()).try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
-                    rv.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?
+            if let Err(e) = check_no_param_instances(&field.ty, &params) {
+                errors.push(e);
+            }
+            // Reads of the field from a map, used when the struct has fields
+            // with defaults, that may be missing from the map.
+            let field_read = if *default {
+                quote! {
+                    #field_ident: match map.get(#path::Symbol::new(env, #field_name)) {
+                        Some(v) => {
+                            len += 1;
+                            v.try_into_val(env).map_err(|_| ConversionError)?
+                        }
+                        None => Default::default(),
+                    }
+                }
+            } else {
+                quote! {
+                    #field_ident: map.get(#path::Symbol::new(env, #field_name)).ok_or(ConversionError)?.try_into_val(env).map_err(|_| ConversionError)?
+                }
+            };
+            let try_from_xdr = if *default {
+                quote! {
+                    #field_ident: {
+                        let key: #path::xdr::ScVal = #path::xdr::ScSymbol(#field_name.try_into().map_err(|_| #path::xdr::Error::Invalid)?).into();
+                        match map.binary_search_by_key(&key, |entry| entry.key.clone()) {
+                            Ok(idx) => {
+                                len += 1;
+                                let rv: #path::Val = (&map[idx].val.clone()).try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
+                                rv.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?
+                            }
+                            Err(_) => Default::default(),
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    #field_ident: {
+                        let key: #path::xdr::ScVal = #path::xdr::ScSymbol(#field_name.try_into().map_err(|_| #path::xdr::Error::Invalid)?).into();
+                        let idx = map.binary_search_by_key(&key, |entry| entry.key.clone()).map_err(|_| #path::xdr::Error::Invalid)?;
+                        let rv: #path::Val = (&map[idx].val.clone()).try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
+                        rv.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?
+                    }
                 }
             };
             let try_into_xdr = quote! {
@@ -64,18 +185,51 @@ pub fn derive_type_struct(
                     val: (&val.#field_ident).try_into().map_err(|_| #path::xdr::Error::Invalid)?,
                 }
             };
-            (spec_field, field_ident, field_name, field_idx_lit, try_from_xdr, try_into_xdr)
+            (spec_field, field_ident, field_name, field_idx_lit, field_read, try_from_xdr, try_into_xdr)
         })
         .multiunzip();
 
+    // Spec of the instances of generic types referenced by the fields.
+    let instances_spec = if spec {
+        derive_instances_spec(path, stored.iter().map(|(f, _, _)| &f.ty)).unwrap_or_else(|e| {
+            errors.push(e);
+            TokenStream2::new()
+        })
+    } else {
+        TokenStream2::new()
+    };
+
     // If errors have occurred, render them instead.
     if !errors.is_empty() {
         let compile_errors = errors.iter().map(Error::to_compile_error);
         return quote! { #(#compile_errors)* };
     }
 
-    // Generated code spec.
-    let spec_gen = if spec {
+    // Generated code spec. Generic types have no spec entry of their own, and
+    // instead have a template that a spec entry is generated from for each
+    // instance of the type referenced in the contract.
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (try_from_where, try_into_where) = conversion_where_clauses(
+        path,
+        generics,
+        &params,
+        stored.iter().map(|(f, _, _)| &f.ty),
+    );
+    let spec_gen = if is_generic {
+        let spec_entry = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc: docs_from_attrs(attrs),
+            lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
+            name: name_marker(),
+            fields: spec_fields.try_into().unwrap(),
+        });
+        let template = derive_spec_template(path, &spec_entry, params.len(), &types);
+        let where_clause = &generics.where_clause;
+        Some(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #template
+            }
+        })
+    } else if spec {
         let spec_entry = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
             doc: docs_from_attrs(attrs),
             lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
@@ -83,8 +237,7 @@ pub fn derive_type_struct(
             fields: spec_fields.try_into().unwrap(),
         });
         let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
-        let spec_xdr_lit = proc_macro2::Literal::byte_string(spec_xdr.as_slice());
-        let spec_xdr_len = spec_xdr.len();
+        let (spec_xdr_len, spec_xdr_value) = derive_xdr(path, &spec_xdr, &types);
         let spec_ident = format_ident!("__SPEC_XDR_TYPE_{}", ident.to_string().to_uppercase());
         Some(quote! {
             #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
@@ -92,7 +245,7 @@ pub fn derive_type_struct(
 
             impl #ident {
                 pub const fn spec_xdr() -> [u8; #spec_xdr_len] {
-                    *#spec_xdr_lit
+                    #spec_xdr_value
                 }
             }
         })
@@ -100,27 +253,62 @@ pub fn derive_type_struct(
         None
     };
 
+    // Contract types are referenced in specs by name. Generic types are
+    // referenced by the names of their instances.
+    let spec_type_def =
+        (!is_generic).then(|| derive_spec_type_def_udt(path, ident, &ident.to_string()));
+
+    // Structs with fields with defaults are read field by field, as the fields
+    // may be missing, and other structs are read with all fields at once.
+    let try_from_val = if has_defaults {
+        quote! {
+            impl #impl_generics #path::TryFromVal<#path::Env, #path::Val> for #ident #ty_generics #try_from_where {
+                type Error = #path::ConversionError;
+                fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
+                    use #path::{TryIntoVal,ConversionError,Val,Map,Symbol};
+                    let map: Map<Symbol, Val> = val.try_into_val(env).map_err(|_| ConversionError)?;
+                    let mut len: u32 = #required_count_usize as u32;
+                    let result = Self {
+                        #(#field_reads,)*
+                        #(#skipped: Default::default(),)*
+                    };
+                    if map.len() != len {
+                        return Err(ConversionError);
+                    }
+                    Ok(result)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #path::TryFromVal<#path::Env, #path::Val> for #ident #ty_generics #try_from_where {
+                type Error = #path::ConversionError;
+                fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
+                    use #path::{TryIntoVal,EnvBase,ConversionError,Val,MapObject};
+                    const KEYS: [&'static str; #field_count_usize] = [#(#field_names),*];
+                    let mut vals: [Val; #field_count_usize] = [Val::VOID.to_val(); #field_count_usize];
+                    let map: MapObject = val.try_into().map_err(|_| ConversionError)?;
+                    env.map_unpack_to_slice(map, &KEYS, &mut vals).map_err(|_| ConversionError)?;
+                    Ok(Self {
+                        #(#field_idents: vals[#field_idx_lits].try_into_val(env).map_err(|_| #path::ConversionError)?,)*
+                        #(#skipped: Default::default(),)*
+                    })
+                }
+            }
+        }
+    };
+
     // Output.
     let mut output = quote! {
         #spec_gen
+        #spec_type_def
+        #instances_spec
 
-        impl #path::TryFromVal<#path::Env, #path::Val> for #ident {
-            type Error = #path::ConversionError;
-            fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
-                use #path::{TryIntoVal,EnvBase,ConversionError,Val,MapObject};
-                const KEYS: [&'static str; #field_count_usize] = [#(#field_names),*];
-                let mut vals: [Val; #field_count_usize] = [Val::VOID.to_val(); #field_count_usize];
-                let map: MapObject = val.try_into().map_err(|_| ConversionError)?;
-                env.map_unpack_to_slice(map, &KEYS, &mut vals).map_err(|_| ConversionError)?;
-                Ok(Self {
-                    #(#field_idents: vals[#field_idx_lits].try_into_val(env).map_err(|_| #path::ConversionError)?,)*
-                })
-            }
-        }
+        #try_from_val
 
-        impl #path::TryFromVal<#path::Env, #ident> for #path::Val {
+        impl #impl_generics #path::TryFromVal<#path::Env, #ident #ty_generics> for #path::Val #try_into_where {
             type Error = #path::ConversionError;
-            fn try_from_val(env: &#path::Env, val: &#ident) -> Result<Self, #path::ConversionError> {
+            fn try_from_val(env: &#path::Env, val: &#ident #ty_generics) -> Result<Self, #path::ConversionError> {
                 use #path::{TryIntoVal,EnvBase,ConversionError,Val};
                 const KEYS: [&'static str; #field_count_usize] = [#(#field_names),*];
                 let vals: [Val; #field_count_usize] = [
@@ -131,9 +319,11 @@ pub fn derive_type_struct(
         }
     };
 
-    // Additional output when testutils are enabled.
-    if cfg!(feature = "testutils") {
-        let arbitrary_tokens = crate::arbitrary::derive_arbitrary_struct(path, vis, ident, data);
+    // Additional output when testutils are enabled. Generic types are only
+    // convertible to and from Val.
+    if cfg!(feature = "testutils") && !is_generic {
+        let arbitrary_tokens =
+            crate::arbitrary::derive_arbitrary_struct(path, vis, ident, data, &skipped);
         output.extend(quote!{
             impl #path::TryFromVal<#path::Env, #path::xdr::ScMap> for #ident {
                 type Error = #path::xdr::Error;
@@ -142,13 +332,17 @@ pub fn derive_type_struct(
                     use #path::xdr::Validate;
                     use #path::TryIntoVal;
                     let map = val;
-                    if map.len() != #field_count_usize {
-                        return Err(#path::xdr::Error::Invalid);
-                    }
                     map.validate()?;
-                    Ok(Self{
+                    #[allow(unused_mut)]
+                    let mut len = #required_count_usize;
+                    let result = Self{
                         #(#try_from_xdrs,)*
-                    })
+                        #(#skipped: Default::default(),)*
+                    };
+                    if map.len() != len {
+                        return Err(#path::xdr::Error::Invalid);
+                    }
+                    Ok(result)
                 }
             }
 
@@ -170,7 +364,7 @@ pub fn derive_type_struct(
                 fn try_from(val: &#ident) -> Result<Self, #path::xdr::Error> {
                     extern crate alloc;
                     use #path::TryFromVal;
-                    #path::xdr::ScMap::sorted_from(alloc::vec![
+                    #path::xdr::ScMap::sorted_from::<_, #path::xdr::ScMapEntry>(alloc::vec![
                         #(#try_into_xdrs,)*
                     ])
                 }
@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{spanned::Spanned, DataStruct, Error, Fields, Generics, Ident, Path, Type, Visibility};
+
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::WriteXdr;
+
+use crate::{
+    attribute::check_no_contracttype_field_attrs,
+    generic::derive_instances_spec,
+    map_type::map_type,
+    spec_template::{derive_xdr, mark_resolved_types},
+    DEFAULT_XDR_RW_LIMITS,
+};
+
+/// Derives a transparent contract type, a tuple struct with a single field
+/// that is converted to and from the field, and that is referenced in specs as
+/// the spec type of the field. Transparent types have no spec entry of their
+/// own.
+pub fn derive_type_struct_transparent(
+    path: &Path,
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    data: &DataStruct,
+    spec: bool,
+) -> TokenStream2 {
+    if !generics.params.is_empty() {
+        return Error::new(
+            generics.span(),
+            "generics are unsupported on transparent contract types",
+        )
+        .to_compile_error();
+    }
+    let field = match &data.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+        _ => {
+            return Error::new(
+                data.fields.span(),
+                "transparent contract types must be tuple structs with a single field",
+            )
+            .to_compile_error()
+        }
+    };
+    if let Err(e) = check_no_contracttype_field_attrs(field) {
+        return e.to_compile_error();
+    }
+    let field_ty = &field.ty;
+
+    // The spec type of the field, with the user-defined types it references
+    // resolved by the compiler.
+    let mut types = Vec::<Type>::new();
+    let spec_type_def = match map_type(field_ty, false) {
+        Ok(mut t) => {
+            mark_resolved_types(&mut t, field_ty, &[], &mut types);
+            t
+        }
+        Err(e) => return e.to_compile_error(),
+    };
+    let spec_type_def_xdr = spec_type_def.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+    let (_, spec_type_def_value) = derive_xdr(path, &spec_type_def_xdr, &types);
+
+    // Spec of the instances of generic types referenced by the field.
+    let instances_spec = if spec {
+        match derive_instances_spec(path, [field_ty]) {
+            Ok(instances_spec) => instances_spec,
+            Err(e) => return e.to_compile_error(),
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    // Output.
+    let mut output = quote! {
+        #instances_spec
+
+        impl #path::spec_type::SpecTypeDef for #ident {
+            const SPEC_TYPE_DEF: #path::spec_type::SpecTypeDefXdr =
+                #path::spec_type::SpecTypeDefXdr::from_parts(&[&#spec_type_def_value]);
+        }
+
+        impl #path::TryFromVal<#path::Env, #path::Val> for #ident {
+            type Error = #path::ConversionError;
+            #[inline(always)]
+            fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
+                use #path::TryIntoVal;
+                Ok(Self(val.try_into_val(env).map_err(|_| #path::ConversionError)?))
+            }
+        }
+
+        impl #path::TryFromVal<#path::Env, #ident> for #path::Val {
+            type Error = #path::ConversionError;
+            #[inline(always)]
+            fn try_from_val(env: &#path::Env, val: &#ident) -> Result<Self, #path::ConversionError> {
+                use #path::TryIntoVal;
+                (&val.0).try_into_val(env).map_err(|_| #path::ConversionError)
+            }
+        }
+    };
+
+    // Additional output when testutils are enabled.
+    if cfg!(feature = "testutils") {
+        let arbitrary_tokens =
+            crate::arbitrary::derive_arbitrary_struct_tuple(path, vis, ident, data);
+        output.extend(quote! {
+            impl #path::TryFromVal<#path::Env, #path::xdr::ScVal> for #ident {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from_val(env: &#path::Env, val: &#path::xdr::ScVal) -> Result<Self, #path::xdr::Error> {
+                    use #path::TryIntoVal;
+                    let rv: #path::Val = val.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
+                    Ok(Self(rv.try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?))
+                }
+            }
+
+            impl TryFrom<&#ident> for #path::xdr::ScVal {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from(val: &#ident) -> Result<Self, #path::xdr::Error> {
+                    (&val.0).try_into().map_err(|_| #path::xdr::Error::Invalid)
+                }
+            }
+
+            impl TryFrom<#ident> for #path::xdr::ScVal {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from(val: #ident) -> Result<Self, #path::xdr::Error> {
+                    (&val).try_into()
+                }
+            }
+
+            #arbitrary_tokens
+        });
+    }
+    output
+}
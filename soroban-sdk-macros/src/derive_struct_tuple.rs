@@ -1,19 +1,32 @@
 use itertools::MultiUnzip;
 use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
-use syn::{Attribute, DataStruct, Error, Ident, Path, Visibility};
+use syn::{Attribute, DataStruct, Error, Generics, Ident, Path, Type, Visibility};
 
+use soroban_spec::marker::TUPLE_STRUCT_DOC;
 use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::{
     ScSpecEntry, ScSpecTypeDef, ScSpecUdtStructFieldV0, ScSpecUdtStructV0, StringM, WriteXdr,
 };
 
-use crate::{doc::docs_from_attrs, map_type::map_type, DEFAULT_XDR_RW_LIMITS};
+use crate::{
+    attribute::check_no_contracttype_field_attrs,
+    doc::{docs_from_attrs, docs_with_marker},
+    generic::{
+        check_no_param_instances, conversion_where_clauses, derive_instances_spec,
+        derive_spec_template, mark_type_params, name_marker, type_params,
+    },
+    map_type::map_type,
+    spec_template::{derive_spec_type_def_udt, derive_xdr, mark_resolved_types},
+    DEFAULT_XDR_RW_LIMITS,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn derive_type_struct_tuple(
     path: &Path,
     vis: &Visibility,
     ident: &Ident,
+    generics: &Generics,
     attrs: &[Attribute],
     data: &DataStruct,
     spec: bool,
@@ -21,9 +34,15 @@ pub fn derive_type_struct_tuple(
 ) -> TokenStream2 {
     // Collect errors as they are encountered and emit them at the end.
     let mut errors = Vec::<Error>::new();
+    let params = type_params(generics).unwrap_or_else(|e| {
+        errors.push(e);
+        vec![]
+    });
+    let is_generic = !params.is_empty();
 
     let fields = &data.fields;
     let field_count_usize: usize = fields.len();
+    let mut types = Vec::<Type>::new();
 
     let (field_specs, field_idx_lits, try_from_xdrs, try_into_xdrs): (Vec<_>, Vec<_>, Vec<_>, Vec<_>) = fields
         .iter()
@@ -37,13 +56,23 @@ pub fn derive_type_struct_tuple(
                 doc: docs_from_attrs(&field.attrs),
                 name: field_name.try_into().unwrap_or_else(|_| StringM::default()),
                 type_: match map_type(&field.ty, false) {
-                    Ok(t) => t,
+                    Ok(mut t) => {
+                        mark_resolved_types(&mut t, &field.ty, &params, &mut types);
+                        mark_type_params(&mut t, &params);
+                        t
+                    }
                     Err(e) => {
                         errors.push(e);
                         ScSpecTypeDef::I32
                     }
                 },
             };
+            if let Err(e) = check_no_param_instances(&field.ty, &params) {
+                errors.push(e);
+            }
+            if let Err(e) = check_no_contracttype_field_attrs(field) {
+                errors.push(e);
+            }
             let try_from_xdr = quote! {
                 #field_idx_lit: {
                     let rv: #path::Val = (&vec[#field_idx_lit].clone()).try_into_val(env).map_err(|_| #path::xdr::Error::Invalid)?;
@@ -57,23 +86,58 @@ pub fn derive_type_struct_tuple(
         })
         .multiunzip();
 
+    // Spec of the instances of generic types referenced by the fields.
+    let instances_spec = if spec {
+        derive_instances_spec(path, fields.iter().map(|f| &f.ty)).unwrap_or_else(|e| {
+            errors.push(e);
+            TokenStream2::new()
+        })
+    } else {
+        TokenStream2::new()
+    };
+
     // If errors have occurred, render them instead.
     if !errors.is_empty() {
         let compile_errors = errors.iter().map(Error::to_compile_error);
         return quote! { #(#compile_errors)* };
     }
 
-    // Generated code spec.
-    let spec_gen = if spec {
+    // Generated code spec. Generic types have no spec entry of their own, and
+    // instead have a template that a spec entry is generated from for each
+    // instance of the type referenced in the contract.
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (try_from_where, try_into_where) =
+        conversion_where_clauses(path, generics, &params, fields.iter().map(|f| &f.ty));
+    // Tuple structs with no fields are marked, as structs with no fields are
+    // otherwise stored as maps.
+    let doc = if field_count_usize == 0 {
+        docs_with_marker(TUPLE_STRUCT_DOC, attrs)
+    } else {
+        docs_from_attrs(attrs)
+    };
+    let spec_gen = if is_generic {
         let spec_entry = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
-            doc: docs_from_attrs(attrs),
+            doc,
+            lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
+            name: name_marker(),
+            fields: field_specs.try_into().unwrap(),
+        });
+        let template = derive_spec_template(path, &spec_entry, params.len(), &types);
+        let where_clause = &generics.where_clause;
+        Some(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #template
+            }
+        })
+    } else if spec {
+        let spec_entry = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc,
             lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
             name: ident.to_string().try_into().unwrap(),
             fields: field_specs.try_into().unwrap(),
         });
         let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
-        let spec_xdr_lit = proc_macro2::Literal::byte_string(spec_xdr.as_slice());
-        let spec_xdr_len = spec_xdr.len();
+        let (spec_xdr_len, spec_xdr_value) = derive_xdr(path, &spec_xdr, &types);
         let spec_ident = format_ident!("__SPEC_XDR_TYPE_{}", ident.to_string().to_uppercase());
         Some(quote! {
             #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
@@ -81,7 +145,7 @@ pub fn derive_type_struct_tuple(
 
             impl #ident {
                 pub const fn spec_xdr() -> [u8; #spec_xdr_len] {
-                    *#spec_xdr_lit
+                    #spec_xdr_value
                 }
             }
         })
@@ -89,11 +153,18 @@ pub fn derive_type_struct_tuple(
         None
     };
 
+    // Contract types are referenced in specs by name. Generic types are
+    // referenced by the names of their instances.
+    let spec_type_def =
+        (!is_generic).then(|| derive_spec_type_def_udt(path, ident, &ident.to_string()));
+
     // Output.
     let mut output = quote! {
         #spec_gen
+        #spec_type_def
+        #instances_spec
 
-        impl #path::TryFromVal<#path::Env, #path::Val> for #ident {
+        impl #impl_generics #path::TryFromVal<#path::Env, #path::Val> for #ident #ty_generics #try_from_where {
             type Error = #path::ConversionError;
             #[inline(always)]
             fn try_from_val(env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
@@ -107,10 +178,10 @@ pub fn derive_type_struct_tuple(
             }
         }
 
-        impl #path::TryFromVal<#path::Env, #ident> for #path::Val {
+        impl #impl_generics #path::TryFromVal<#path::Env, #ident #ty_generics> for #path::Val #try_into_where {
             type Error = #path::ConversionError;
             #[inline(always)]
-            fn try_from_val(env: &#path::Env, val: &#ident) -> Result<Self, #path::ConversionError> {
+            fn try_from_val(env: &#path::Env, val: &#ident #ty_generics) -> Result<Self, #path::ConversionError> {
                 use #path::{TryIntoVal,EnvBase,ConversionError,Val};
                 let vals: [Val; #field_count_usize] = [
                     #((&val.#field_idx_lits).try_into_val(env).map_err(|_| ConversionError)?),*
@@ -120,8 +191,9 @@ pub fn derive_type_struct_tuple(
         }
     };
 
-    // Additional output when testutils are enabled.
-    if cfg!(feature = "testutils") {
+    // Additional output when testutils are enabled. Generic types are only
+    // convertible to and from Val.
+    if cfg!(feature = "testutils") && !is_generic {
         let arbitrary_tokens =
             crate::arbitrary::derive_arbitrary_struct_tuple(path, vis, ident, data);
         output.extend(quote! {
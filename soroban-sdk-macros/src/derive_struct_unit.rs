@@ -0,0 +1,115 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Attribute, DataStruct, Ident, Path, Visibility};
+
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{ScSpecEntry, ScSpecUdtStructV0, WriteXdr};
+
+use soroban_spec::marker::UNIT_STRUCT_DOC;
+
+use crate::{
+    doc::docs_with_marker, spec_template::derive_spec_type_def_udt, DEFAULT_XDR_RW_LIMITS,
+};
+
+/// Derives a unit struct, that is stored as void, and that is exported in the
+/// spec as a struct with no fields marked with [`UNIT_STRUCT_DOC`].
+pub fn derive_type_struct_unit(
+    path: &Path,
+    vis: &Visibility,
+    ident: &Ident,
+    attrs: &[Attribute],
+    data: &DataStruct,
+    spec: bool,
+    lib: &Option<String>,
+) -> TokenStream2 {
+    // Generated code spec.
+    let spec_gen = if spec {
+        let spec_entry = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc: docs_with_marker(UNIT_STRUCT_DOC, attrs),
+            lib: lib.as_deref().unwrap_or_default().try_into().unwrap(),
+            name: ident.to_string().try_into().unwrap(),
+            fields: [].try_into().unwrap(),
+        });
+        let spec_xdr = spec_entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+        let spec_xdr_lit = proc_macro2::Literal::byte_string(spec_xdr.as_slice());
+        let spec_xdr_len = spec_xdr.len();
+        let spec_ident = format_ident!("__SPEC_XDR_TYPE_{}", ident.to_string().to_uppercase());
+        Some(quote! {
+            #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
+            pub static #spec_ident: [u8; #spec_xdr_len] = #ident::spec_xdr();
+
+            impl #ident {
+                pub const fn spec_xdr() -> [u8; #spec_xdr_len] {
+                    *#spec_xdr_lit
+                }
+            }
+        })
+    } else {
+        None
+    };
+    let spec_type_def = derive_spec_type_def_udt(path, ident, &ident.to_string());
+
+    // Output.
+    let mut output = quote! {
+        #spec_gen
+        #spec_type_def
+
+        impl #path::TryFromVal<#path::Env, #path::Val> for #ident {
+            type Error = #path::ConversionError;
+            #[inline(always)]
+            fn try_from_val(_env: &#path::Env, val: &#path::Val) -> Result<Self, #path::ConversionError> {
+                if val.is_void() {
+                    Ok(Self)
+                } else {
+                    Err(#path::ConversionError)
+                }
+            }
+        }
+
+        impl #path::TryFromVal<#path::Env, #ident> for #path::Val {
+            type Error = #path::ConversionError;
+            #[inline(always)]
+            fn try_from_val(_env: &#path::Env, _val: &#ident) -> Result<Self, #path::ConversionError> {
+                Ok(#path::Val::VOID.to_val())
+            }
+        }
+    };
+
+    // Additional output when testutils are enabled.
+    if cfg!(feature = "testutils") {
+        let arbitrary_tokens =
+            crate::arbitrary::derive_arbitrary_struct(path, vis, ident, data, &[]);
+        output.extend(quote! {
+            impl #path::TryFromVal<#path::Env, #path::xdr::ScVal> for #ident {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from_val(_env: &#path::Env, val: &#path::xdr::ScVal) -> Result<Self, #path::xdr::Error> {
+                    if let #path::xdr::ScVal::Void = val {
+                        Ok(Self)
+                    } else {
+                        Err(#path::xdr::Error::Invalid)
+                    }
+                }
+            }
+
+            impl TryFrom<&#ident> for #path::xdr::ScVal {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from(_val: &#ident) -> Result<Self, #path::xdr::Error> {
+                    Ok(#path::xdr::ScVal::Void)
+                }
+            }
+
+            impl TryFrom<#ident> for #path::xdr::ScVal {
+                type Error = #path::xdr::Error;
+                #[inline(always)]
+                fn try_from(val: #ident) -> Result<Self, #path::xdr::Error> {
+                    (&val).try_into()
+                }
+            }
+
+            #arbitrary_tokens
+        });
+    }
+    output
+}
@@ -0,0 +1,378 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    spanned::Spanned,
+    Error, FnArg, GenericArgument, Ident, ImplItem, ItemImpl, ItemTrait, Path, PathArguments,
+    ReturnType, Signature, Token, TraitItem, TraitItemFn, Type, TypePath,
+};
+
+use crate::{access::take_access_rules, syn_ext};
+
+/// Derives a contract trait, a trait whose default functions are exported by
+/// the contracts that implement it with `#[contractimpl(contracttrait)]`,
+/// unless the implementation overrides them.
+///
+/// Alongside the trait a macro is generated and exported with the same name as
+/// the trait, so that it is imported wherever the trait is. The macro is
+/// defined under a name unique to the trait, since exported macros are defined
+/// at the root of the crate. The implementation
+/// invokes the macro with the functions it overrides, and the macro adds the
+/// signatures of the default functions of the trait and forwards both to
+/// [`derive_trait_default_fns`].
+///
+/// The signatures are expanded in the crate implementing the trait, where the
+/// types they name may not be in scope. So the types are replaced by their
+/// names, and a hidden supertrait is added to the trait with an associated
+/// type for each name, that the implementation resolves them through.
+pub fn derive_contract_trait(crate_path: &Path, mut item: ItemTrait) -> TokenStream2 {
+    if !item.generics.params.is_empty() {
+        return Error::new(
+            item.generics.span(),
+            "generics are unsupported on contract traits",
+        )
+        .to_compile_error();
+    }
+
+    // Collect the signatures of the default functions, with their attributes
+    // as written, and remove the access rules from the functions of the trait.
+    let mut errors = Vec::<Error>::new();
+    let mut default_fns = Vec::<TraitItemFn>::new();
+    let mut aliases = Vec::<(Ident, Path)>::new();
+    for trait_item in item.items.iter_mut() {
+        let TraitItem::Fn(f) = trait_item else {
+            continue;
+        };
+        if f.default.is_some() {
+            let mut sig = f.sig.clone();
+            alias_sig_types(Some(crate_path), &mut sig, &mut aliases);
+            default_fns.push(TraitItemFn {
+                sig,
+                default: None,
+                semi_token: Some(Default::default()),
+                ..f.clone()
+            });
+        }
+        match take_access_rules(&mut f.attrs) {
+            Ok(rules) if !rules.is_empty() && f.default.is_none() => errors.push(Error::new(
+                f.sig.ident.span(),
+                "access rules are only supported on functions with a default implementation",
+            )),
+            Ok(_) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    if !errors.is_empty() {
+        let compile_errors = errors.iter().map(Error::to_compile_error);
+        return quote! {
+            #item
+            #(#compile_errors)*
+        };
+    }
+
+    let vis = item.vis.clone();
+    let ident = item.ident.clone();
+    let types_ident = format_ident!("__{ident}Types");
+    let types = if aliases.is_empty() {
+        quote! {}
+    } else {
+        item.colon_token.get_or_insert_with(Default::default);
+        item.supertraits.push(parse_quote!(#types_ident));
+        let (names, paths): (Vec<_>, Vec<_>) = aliases
+            .iter()
+            .map(|(name, path)| (format_ident!("__{name}"), path))
+            .unzip();
+        quote! {
+            #[doc(hidden)]
+            #vis trait #types_ident {
+                #(type #names;)*
+            }
+
+            impl<T: ?Sized> #types_ident for T {
+                #(type #names = #paths;)*
+            }
+        }
+    };
+    // Exported macros are defined at the root of the crate, so the name of the
+    // macro includes a hash of the trait and of where it is defined, to keep
+    // traits with the same name in different modules from colliding.
+    let trait_hash = format!(
+        "{:x}",
+        Sha256::digest(format!("{:?}{}", ident.span(), quote!(#item)).as_bytes())
+    );
+    let macro_ident = format_ident!("__contracttrait_{ident}_{trait_hash}");
+    quote! {
+        #item
+
+        #types
+
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #macro_ident {
+            ($($impl:tt)*) => {
+                #crate_path::contractimpl_trait_default_fns! {
+                    $($impl)*
+                    trait #ident {
+                        #(#default_fns)*
+                    }
+                }
+            };
+        }
+
+        #[doc(hidden)]
+        #vis use #macro_ident as #ident;
+    }
+}
+
+/// The input of the `contractimpl_trait_default_fns` macro: the crate path,
+/// the implementation of the trait with the functions it overrides, and the
+/// trait with its default functions.
+pub struct TraitDefaultFnsInput {
+    pub crate_path: Path,
+    pub imp: ItemImpl,
+    pub item: ItemTrait,
+}
+
+impl Parse for TraitDefaultFnsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        if key != "crate_path" {
+            return Err(Error::new(key.span(), "expected `crate_path`"));
+        }
+        input.parse::<Token![=]>()?;
+        let crate_path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let imp = input.parse()?;
+        let item = input.parse()?;
+        Ok(Self {
+            crate_path,
+            imp,
+            item,
+        })
+    }
+}
+
+/// Derives the exports of the default functions of a contract trait that the
+/// implementation does not override, as functions of the contract that
+/// forward to the trait.
+///
+/// The functions are exported from a hidden module that defines the names of
+/// the types in their signatures as aliases of the associated types of the
+/// supertrait added by [`derive_contract_trait`], so that the signatures keep
+/// the names the spec is derived from.
+pub fn derive_trait_default_fns(
+    crate_path: &Path,
+    imp: &ItemImpl,
+    item: &ItemTrait,
+) -> TokenStream2 {
+    let Some((_, trait_path, _)) = &imp.trait_ else {
+        return Error::new(imp.span(), "expected an implementation of a trait").to_compile_error();
+    };
+    // The hidden module is a child of the module of the implementation, so
+    // paths relative to that module are made relative to its parent.
+    let trait_path = path_in_child_mod(trait_path);
+    let ty = match &*imp.self_ty {
+        Type::Path(TypePath { qself: None, path }) => Type::Path(TypePath {
+            qself: None,
+            path: path_in_child_mod(path),
+        }),
+        ty => ty.clone(),
+    };
+    let overridden = imp
+        .items
+        .iter()
+        .filter_map(|i| match i {
+            ImplItem::Fn(f) => Some(&f.sig.ident),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut errors = Vec::<Error>::new();
+    let mut aliases = Vec::<(Ident, Path)>::new();
+    let fns = syn_ext::trait_methods(item)
+        .filter(|f| !overridden.contains(&f.sig.ident))
+        .map(|f| {
+            let attrs = &f.attrs;
+            let sig = &f.sig;
+            let ident = &sig.ident;
+            alias_sig_types(None, &mut sig.clone(), &mut aliases);
+            let args = sig
+                .inputs
+                .iter()
+                .map(syn_ext::fn_arg_ident)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|e| {
+                    errors.push(e);
+                    vec![]
+                });
+            quote! {
+                #(#attrs)*
+                pub #sig {
+                    <Self as #trait_path>::#ident(#(#args),*)
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        let compile_errors = errors.iter().map(Error::to_compile_error);
+        return quote! { #(#compile_errors)* };
+    }
+    if fns.is_empty() {
+        return quote! {};
+    }
+
+    let mod_hash = format!(
+        "{:x}",
+        Sha256::digest(quote!(#ty #trait_path).to_string().as_bytes())
+    );
+    let mod_ident = format_ident!("__contracttrait_{mod_hash}");
+    let (names, assoc_names): (Vec<_>, Vec<_>) = aliases
+        .iter()
+        .map(|(name, _)| (name, format_ident!("__{name}")))
+        .unzip();
+    let crate_path_str = quote!(#crate_path).to_string();
+    quote! {
+        #[doc(hidden)]
+        mod #mod_ident {
+            use super::*;
+
+            pub trait __Types {
+                #(type #assoc_names;)*
+            }
+
+            impl<T: #trait_path + ?Sized> __Types for T {
+                #(type #assoc_names = T::#assoc_names;)*
+            }
+
+            #(type #names = <#ty as __Types>::#assoc_names;)*
+
+            #[#crate_path::contractimpl(crate_path = #crate_path_str)]
+            impl #ty {
+                #(#fns)*
+            }
+        }
+
+        // The items generated for the functions are exported from the module
+        // of the implementation, as they are for the functions it implements.
+        #[doc(hidden)]
+        pub use #mod_ident::*;
+    }
+}
+
+/// Returns the path, for use in a child module of the module it is written in.
+fn path_in_child_mod(path: &Path) -> Path {
+    let mut path = path.clone();
+    match path.segments.first() {
+        Some(first) if path.leading_colon.is_none() && first.ident == "self" => {
+            path.segments[0].ident = Ident::new("super", first.ident.span());
+        }
+        Some(first) if path.leading_colon.is_none() && first.ident == "super" => {
+            path.segments
+                .insert(0, Ident::new("super", first.ident.span()).into());
+        }
+        _ => {}
+    }
+    path
+}
+
+/// Replaces the types in the signature of a default function of a contract
+/// trait with their names, collecting the names with the paths they replace.
+/// Where different paths have the same name the first is aliased, and the
+/// functions using the others fail to compile where they forward to the trait.
+///
+/// The generic types of the SDK and of core, that the names of cannot be
+/// aliased, are qualified with the crate path instead, if one is given. Other
+/// generic types are left as written, to resolve in the implementing crate.
+fn alias_sig_types(
+    crate_path: Option<&Path>,
+    sig: &mut Signature,
+    aliases: &mut Vec<(Ident, Path)>,
+) {
+    for input in sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = input {
+            alias_types(crate_path, &mut pat_type.ty, aliases);
+        }
+    }
+    if let ReturnType::Type(_, ty) = &mut sig.output {
+        alias_types(crate_path, ty, aliases);
+    }
+}
+
+fn alias_types(crate_path: Option<&Path>, ty: &mut Type, aliases: &mut Vec<(Ident, Path)>) {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            let Some(last) = path.segments.last_mut() else {
+                return;
+            };
+            match &mut last.arguments {
+                PathArguments::None => {
+                    let name = last.ident.clone();
+                    if name == "Self" || is_primitive(&name) {
+                        return;
+                    }
+                    if !aliases.iter().any(|(n, _)| *n == name) {
+                        aliases.push((name.clone(), path.clone()));
+                    }
+                    *path = name.into();
+                }
+                PathArguments::AngleBracketed(args) => {
+                    for arg in args.args.iter_mut() {
+                        if let GenericArgument::Type(ty) = arg {
+                            alias_types(crate_path, ty, aliases);
+                        }
+                    }
+                    if let (Some(crate_path), 1) = (crate_path, path.segments.len()) {
+                        let segment = path.segments[0].clone();
+                        let name = &segment.ident;
+                        let qualified: Option<Path> = match &name.to_string()[..] {
+                            "Vec" | "Map" | "BytesN" => Some(parse_quote!(#crate_path::#name)),
+                            "Hash" => Some(parse_quote!(#crate_path::crypto::#name)),
+                            "Option" => Some(parse_quote!(::core::option::#name)),
+                            "Result" => Some(parse_quote!(::core::result::#name)),
+                            _ => None,
+                        };
+                        if let Some(mut qualified) = qualified {
+                            *qualified.segments.last_mut().unwrap() = segment;
+                            *path = qualified;
+                        }
+                    }
+                }
+                PathArguments::Parenthesized(_) => {}
+            }
+        }
+        Type::Reference(r) => alias_types(crate_path, &mut r.elem, aliases),
+        Type::Paren(p) => alias_types(crate_path, &mut p.elem, aliases),
+        Type::Tuple(t) => {
+            for elem in t.elems.iter_mut() {
+                alias_types(crate_path, elem, aliases);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_primitive(name: &Ident) -> bool {
+    matches!(
+        &name.to_string()[..],
+        "bool"
+            | "char"
+            | "str"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "usize"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "isize"
+            | "f32"
+            | "f64"
+    )
+}
@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use soroban_spec::marker::doc_with_marker;
 use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::StringM;
 use syn::{Attribute, Expr, ExprLit, Lit, Meta, MetaNameValue};
@@ -26,3 +27,13 @@ pub fn docs_from_attrs(attrs: &[Attribute]) -> StringM<DOCS_MAX_LEN> {
     docs.truncate(DOCS_MAX_LEN as usize);
     docs.try_into().unwrap()
 }
+
+/// Returns the docs of the attributes with the marker set as the first line,
+/// for spec entries that are marked with one of the markers in
+/// [`soroban_spec::marker`].
+pub fn docs_with_marker(marker: &str, attrs: &[Attribute]) -> StringM<DOCS_MAX_LEN> {
+    let docs = docs_from_attrs(attrs).to_utf8_string_lossy();
+    let mut docs = doc_with_marker(marker, &docs).into_bytes();
+    docs.truncate(DOCS_MAX_LEN as usize);
+    docs.try_into().unwrap()
+}
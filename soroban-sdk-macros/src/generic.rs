@@ -0,0 +1,337 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::quote;
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{ScSpecEntry, ScSpecTypeDef, ScSpecTypeUdt, StringM, WriteXdr};
+use syn::{
+    parse_quote, spanned::Spanned, Error, GenericArgument, GenericParam, Generics, Ident, Path,
+    PathArguments, Type, TypePath, WhereClause,
+};
+
+use crate::{
+    map_type::map_type,
+    spec_template::{derive_xdr, mark_resolved_types, Template},
+    DEFAULT_XDR_RW_LIMITS,
+};
+
+// Names substituted into the spec entry of a generic type to mark where the
+// name of the instance and the type arguments of the instance are placed. The
+// names contain nul bytes so that they cannot collide with the names of types
+// or fields.
+const NAME_MARKER: &str = "\0soroban_generic_name\0";
+const PARAM_MARKER_PREFIX: &str = "\0soroban_generic_param_";
+
+/// Types with generic arguments that are mapped to builtin spec types, and so
+/// are not generic contract types.
+const BUILTIN_GENERIC_TYPES: &[&str] = &["Result", "Option", "Vec", "Map", "BytesN", "Hash"];
+
+/// Returns the type parameters of the generics, or an error if the generics
+/// contain lifetime or const parameters, which are unsupported on contract
+/// types.
+pub fn type_params(generics: &Generics) -> Result<Vec<Ident>, Error> {
+    generics
+        .params
+        .iter()
+        .map(|p| match p {
+            GenericParam::Type(t) => Ok(t.ident.clone()),
+            GenericParam::Lifetime(_) | GenericParam::Const(_) => Err(Error::new(
+                p.span(),
+                "contract types support only type parameters",
+            )),
+        })
+        .collect()
+}
+
+/// Replaces the type parameters referenced in the spec type with markers that
+/// are filled in with the spec types of the type arguments of each instance.
+pub fn mark_type_params(t: &mut ScSpecTypeDef, params: &[Ident]) {
+    match t {
+        ScSpecTypeDef::Udt(u) => {
+            if let Some(i) = params
+                .iter()
+                .position(|p| u.name.as_vec() == p.to_string().as_bytes())
+            {
+                *t = param_marker(i);
+            }
+        }
+        ScSpecTypeDef::Option(o) => mark_type_params(&mut o.value_type, params),
+        ScSpecTypeDef::Result(r) => {
+            mark_type_params(&mut r.ok_type, params);
+            mark_type_params(&mut r.error_type, params);
+        }
+        ScSpecTypeDef::Vec(v) => mark_type_params(&mut v.element_type, params),
+        ScSpecTypeDef::Map(m) => {
+            mark_type_params(&mut m.key_type, params);
+            mark_type_params(&mut m.value_type, params);
+        }
+        ScSpecTypeDef::Tuple(t) => {
+            let mut types = t.value_types.to_vec();
+            for t in &mut types {
+                mark_type_params(t, params);
+            }
+            t.value_types = types.try_into().unwrap();
+        }
+        _ => {}
+    }
+}
+
+/// Returns an error if the type contains an instance of a generic contract
+/// type that has a type argument that references the type parameters, as
+/// spec entries cannot be generated for such instances.
+pub fn check_no_param_instances(t: &Type, params: &[Ident]) -> Result<(), Error> {
+    for instance in instances(t) {
+        for arg in instance_args(&instance) {
+            if references_params(arg, params) {
+                return Err(Error::new(
+                    instance.span(),
+                    "generic contract types cannot be instantiated with the type parameters of another contract type",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name used in the spec for the instance of a generic contract
+/// type, formed from the name of the type and the names of the spec types of
+/// the type arguments, e.g. `Page_Address` for `Page<Address>`.
+pub fn instance_name(ident: &Ident, args: &[ScSpecTypeDef]) -> String {
+    let mut name = ident.to_string();
+    for a in args {
+        name.push('_');
+        name.push_str(&spec_type_name(a));
+    }
+    name
+}
+
+fn spec_type_name(t: &ScSpecTypeDef) -> String {
+    match t {
+        ScSpecTypeDef::Val => "Val".to_string(),
+        ScSpecTypeDef::Bool => "bool".to_string(),
+        ScSpecTypeDef::Void => "void".to_string(),
+        ScSpecTypeDef::Error => "Error".to_string(),
+        ScSpecTypeDef::U32 => "u32".to_string(),
+        ScSpecTypeDef::I32 => "i32".to_string(),
+        ScSpecTypeDef::U64 => "u64".to_string(),
+        ScSpecTypeDef::I64 => "i64".to_string(),
+        ScSpecTypeDef::Timepoint => "Timepoint".to_string(),
+        ScSpecTypeDef::Duration => "Duration".to_string(),
+        ScSpecTypeDef::U128 => "u128".to_string(),
+        ScSpecTypeDef::I128 => "i128".to_string(),
+        ScSpecTypeDef::U256 => "U256".to_string(),
+        ScSpecTypeDef::I256 => "I256".to_string(),
+        ScSpecTypeDef::Bytes => "Bytes".to_string(),
+        ScSpecTypeDef::String => "String".to_string(),
+        ScSpecTypeDef::Symbol => "Symbol".to_string(),
+        ScSpecTypeDef::Address => "Address".to_string(),
+        ScSpecTypeDef::MuxedAddress => "MuxedAddress".to_string(),
+        ScSpecTypeDef::Option(o) => format!("Option_{}", spec_type_name(&o.value_type)),
+        ScSpecTypeDef::Result(r) => format!(
+            "Result_{}_{}",
+            spec_type_name(&r.ok_type),
+            spec_type_name(&r.error_type)
+        ),
+        ScSpecTypeDef::Vec(v) => format!("Vec_{}", spec_type_name(&v.element_type)),
+        ScSpecTypeDef::Map(m) => format!(
+            "Map_{}_{}",
+            spec_type_name(&m.key_type),
+            spec_type_name(&m.value_type)
+        ),
+        ScSpecTypeDef::Tuple(t) => {
+            let mut name = "Tuple".to_string();
+            for t in t.value_types.iter() {
+                name.push('_');
+                name.push_str(&spec_type_name(t));
+            }
+            name
+        }
+        ScSpecTypeDef::BytesN(b) => format!("BytesN{}", b.n),
+        ScSpecTypeDef::Udt(u) => u.name.to_utf8_string_lossy(),
+    }
+}
+
+/// Returns the tokens of the consts holding the spec template of a generic
+/// contract type, that are used to generate the spec entries of each instance
+/// of the type.
+///
+/// The spec entry must have its name set to [`name_marker`], its type
+/// parameters marked with [`mark_type_params`], and the types it references
+/// marked with [`mark_resolved_types`].
+///
+/// [`mark_resolved_types`]: crate::spec_template::mark_resolved_types
+pub fn derive_spec_template(
+    path: &Path,
+    entry: &ScSpecEntry,
+    param_count: usize,
+    types: &[Type],
+) -> TokenStream2 {
+    let xdr = entry.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+    let name_marker = StringM::<60>::try_from(NAME_MARKER)
+        .unwrap()
+        .to_xdr(DEFAULT_XDR_RW_LIMITS)
+        .unwrap();
+    let arg_markers = [name_marker]
+        .into_iter()
+        .chain((0..param_count).map(|i| param_marker(i).to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap()))
+        .collect::<Vec<_>>();
+    let (segments, holes) = Template::new(&xdr, &arg_markers, types).to_tokens(path);
+    quote! {
+        #[doc(hidden)]
+        pub const SPEC_XDR_TEMPLATE: &'static [&'static [u8]] = #segments;
+        #[doc(hidden)]
+        pub const SPEC_XDR_TEMPLATE_HOLES: &'static [usize] = #holes;
+    }
+}
+
+/// Returns the name to set on the spec entry of a generic contract type, for
+/// use with [`derive_spec_template`].
+pub fn name_marker() -> StringM<60> {
+    NAME_MARKER.try_into().unwrap()
+}
+
+fn param_marker(i: usize) -> ScSpecTypeDef {
+    ScSpecTypeDef::Udt(ScSpecTypeUdt {
+        name: format!("{PARAM_MARKER_PREFIX}{i}\0").try_into().unwrap(),
+    })
+}
+
+/// Returns the tokens of the spec entries of the instances of generic
+/// contract types that are referenced in the types, with each distinct
+/// instance included once.
+pub fn derive_instances_spec<'a>(
+    path: &Path,
+    types: impl IntoIterator<Item = &'a Type>,
+) -> Result<TokenStream2, Error> {
+    let mut names = Vec::<String>::new();
+    let mut output = TokenStream2::new();
+    for t in types {
+        for instance in instances(t) {
+            let ty = Type::Path(instance.clone());
+            let ScSpecTypeDef::Udt(udt) = map_type(&ty, false)? else {
+                continue;
+            };
+            let name = udt.name.to_utf8_string_lossy();
+            if names.contains(&name) {
+                continue;
+            }
+            names.push(name);
+            let name_xdr = udt.name.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+            let name_arg = Literal::byte_string(&name_xdr);
+            let args = instance_args(&instance)
+                .into_iter()
+                .map(|a| {
+                    let mut def = map_type(a, false)?;
+                    let mut types = Vec::new();
+                    mark_resolved_types(&mut def, a, &[], &mut types);
+                    let xdr = def.to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap();
+                    let (_, value) = derive_xdr(path, &xdr, &types);
+                    Ok(quote! { &#value })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            output.extend(quote! {
+                const _: () = {
+                    const TEMPLATE: &[&[u8]] = <#ty>::SPEC_XDR_TEMPLATE;
+                    const HOLES: &[usize] = <#ty>::SPEC_XDR_TEMPLATE_HOLES;
+                    const ARGS: &[&[u8]] = &[#name_arg, #(#args),*];
+                    const LEN: usize = #path::spec_generic::instance_len(TEMPLATE, HOLES, ARGS);
+                    #[used]
+                    #[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
+                    static SPEC_XDR: [u8; LEN] = #path::spec_generic::instance::<LEN>(TEMPLATE, HOLES, ARGS);
+                };
+            });
+        }
+    }
+    Ok(output)
+}
+
+/// Returns the instances of generic contract types referenced in the type,
+/// including instances nested in the type arguments of other types.
+fn instances(t: &Type) -> Vec<TypePath> {
+    let mut instances = Vec::new();
+    collect_instances(t, &mut instances);
+    instances
+}
+
+fn collect_instances(t: &Type, instances: &mut Vec<TypePath>) {
+    match t {
+        Type::Path(p @ TypePath { qself: None, path }) => {
+            if let Some(segment) = path.segments.last() {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if !BUILTIN_GENERIC_TYPES.contains(&segment.ident.to_string().as_str()) {
+                        instances.push(p.clone());
+                    }
+                    for a in &args.args {
+                        if let GenericArgument::Type(t) = a {
+                            collect_instances(t, instances);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Tuple(t) => t.elems.iter().for_each(|t| collect_instances(t, instances)),
+        Type::Reference(r) => collect_instances(&r.elem, instances),
+        _ => {}
+    }
+}
+
+fn instance_args(instance: &TypePath) -> Vec<&Type> {
+    match instance.path.segments.last().map(|s| &s.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns true if the type references any of the type parameters.
+pub fn references_params(t: &Type, params: &[Ident]) -> bool {
+    match t {
+        Type::Path(TypePath { qself: None, path }) => {
+            path.get_ident().is_some_and(|i| params.contains(i))
+                || path.segments.iter().any(|s| match &s.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().any(|a| match a {
+                        GenericArgument::Type(t) => references_params(t, params),
+                        _ => false,
+                    }),
+                    _ => false,
+                })
+        }
+        Type::Tuple(t) => t.elems.iter().any(|t| references_params(t, params)),
+        Type::Reference(r) => references_params(&r.elem, params),
+        Type::Array(a) => references_params(&a.elem, params),
+        Type::Paren(p) => references_params(&p.elem, params),
+        Type::Group(g) => references_params(&g.elem, params),
+        _ => false,
+    }
+}
+
+/// Returns the where clauses for the conversions of a contract type to and
+/// from `Val`, bounding each field type that references the type parameters
+/// by the conversion of that field.
+pub fn conversion_where_clauses<'a>(
+    path: &Path,
+    generics: &Generics,
+    params: &[Ident],
+    field_types: impl IntoIterator<Item = &'a Type>,
+) -> (WhereClause, WhereClause) {
+    let mut try_from = generics.clone().make_where_clause().clone();
+    let mut try_into = try_from.clone();
+    let mut bounded = Vec::<&Type>::new();
+    for t in field_types {
+        if !references_params(t, params) || bounded.contains(&t) {
+            continue;
+        }
+        bounded.push(t);
+        try_from
+            .predicates
+            .push(parse_quote!(#t: #path::TryFromVal<#path::Env, #path::Val>));
+        try_into
+            .predicates
+            .push(parse_quote!(#path::Val: #path::TryFromVal<#path::Env, #t>));
+    }
+    (try_from, try_into)
+}
@@ -1,5 +1,6 @@
 extern crate proc_macro;
 
+mod access;
 mod arbitrary;
 mod attribute;
 mod derive_args;
@@ -10,13 +11,20 @@ mod derive_error_enum_int;
 mod derive_fn;
 mod derive_spec_fn;
 mod derive_struct;
+mod derive_struct_transparent;
 mod derive_struct_tuple;
+mod derive_struct_unit;
+mod derive_trait;
 mod doc;
+mod generic;
 mod map_type;
 mod path;
+mod spec_template;
 mod symbol;
 mod syn_ext;
 
+use access::take_access_rules;
+use attribute::strip_contracttype_field_attrs;
 use derive_args::{derive_args_impl, derive_args_type};
 use derive_client::{derive_client_impl, derive_client_type};
 use derive_enum::derive_type_enum;
@@ -25,20 +33,25 @@ use derive_error_enum_int::derive_type_error_enum_int;
 use derive_fn::{derive_contract_function_registration_ctor, derive_pub_fn};
 use derive_spec_fn::derive_fn_spec;
 use derive_struct::derive_type_struct;
+use derive_struct_transparent::derive_type_struct_transparent;
 use derive_struct_tuple::derive_type_struct_tuple;
+use derive_struct_unit::derive_type_struct_unit;
+use derive_trait::{derive_contract_trait, derive_trait_default_fns, TraitDefaultFnsInput};
+use generic::derive_instances_spec;
 
 use darling::{ast::NestedMeta, FromMeta};
 use proc_macro::TokenStream;
 use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote};
 use sha2::{Digest, Sha256};
-use std::{fmt::Write, fs};
+use std::{collections::HashMap, fmt::Write, fs};
 use syn::{
-    parse_macro_input, parse_str, spanned::Spanned, Data, DeriveInput, Error, Fields, ItemImpl,
-    ItemStruct, LitStr, Path, Type, Visibility,
+    parse_macro_input, parse_str, spanned::Spanned, Data, DeriveInput, Error, Fields, FnArg,
+    ImplItem, ItemImpl, ItemStruct, ItemTrait, LitStr, Path, ReturnType, Type, Visibility,
 };
 use syn_ext::HasFnsItem;
 
+use soroban_spec::meta::ContractMeta;
 use soroban_spec_rust::{generate_from_wasm, GenerateFromFileError};
 
 use stellar_xdr::curr as stellar_xdr;
@@ -69,6 +82,8 @@ fn default_crate_path() -> Path {
 
 #[derive(Debug, FromMeta)]
 struct ContractSpecArgs {
+    #[darling(default = "default_crate_path")]
+    crate_path: Path,
     name: Type,
     export: Option<bool>,
 }
@@ -92,9 +107,41 @@ pub fn contractspecfn(metadata: TokenStream, input: TokenStream) -> TokenStream
 
     let derived: Result<proc_macro2::TokenStream, proc_macro2::TokenStream> = methods
         .iter()
-        .map(|m| derive_fn_spec(&args.name, m.ident, m.attrs, m.inputs, m.output, export))
+        .map(|m| {
+            derive_fn_spec(
+                &args.crate_path,
+                &args.name,
+                m.ident,
+                m.attrs,
+                m.inputs,
+                m.output,
+                export,
+            )
+        })
         .collect();
 
+    // Spec entries for the instances of generic types referenced by the
+    // functions, generated once for all the functions.
+    let derived = derived.and_then(|derived| {
+        if !export {
+            return Ok(derived);
+        }
+        let types = methods.iter().flat_map(|m| {
+            let inputs = m.inputs.iter().filter_map(|a| match a {
+                FnArg::Typed(pat_type) => Some(&*pat_type.ty),
+                FnArg::Receiver(_) => None,
+            });
+            let output = match m.output {
+                ReturnType::Type(_, ty) => Some(&**ty),
+                ReturnType::Default => None,
+            };
+            inputs.chain(output)
+        });
+        let instances =
+            derive_instances_spec(&args.crate_path, types).map_err(|e| e.to_compile_error())?;
+        Ok(quote! { #derived #instances })
+    });
+
     match derived {
         Ok(derived_ok) => quote! {
             #input2
@@ -190,6 +237,8 @@ pub fn contract(metadata: TokenStream, input: TokenStream) -> TokenStream {
 struct ContractImplArgs {
     #[darling(default = "default_crate_path")]
     crate_path: Path,
+    #[darling(default)]
+    contracttrait: bool,
 }
 
 #[proc_macro_attribute]
@@ -207,7 +256,46 @@ pub fn contractimpl(metadata: TokenStream, input: TokenStream) -> TokenStream {
     let crate_path = &args.crate_path;
     let crate_path_str = quote!(#crate_path).to_string();
 
-    let imp = parse_macro_input!(input as ItemImpl);
+    let mut imp = parse_macro_input!(input as ItemImpl);
+    if args.contracttrait && imp.trait_.is_none() {
+        return Error::new(
+            imp.span(),
+            "contracttrait is only supported on implementations of traits",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    // Take the access rules from the attributes of the methods, leaving in
+    // their place docs that describe them.
+    let is_trait_impl = imp.trait_.is_some();
+    let mut access_rules = HashMap::new();
+    let mut access_errors = Vec::<Error>::new();
+    for item in imp.items.iter_mut() {
+        let ImplItem::Fn(m) = item else { continue };
+        match take_access_rules(&mut m.attrs) {
+            Ok(rules) if rules.is_empty() => {}
+            Ok(_) if !is_trait_impl && !matches!(m.vis, Visibility::Public(_)) => {
+                access_errors.push(Error::new(
+                    m.sig.ident.span(),
+                    "access rules are only supported on exported contract functions",
+                ));
+            }
+            Ok(rules) => {
+                access_rules.insert(m.sig.ident.clone(), rules);
+            }
+            Err(e) => access_errors.push(e),
+        }
+    }
+    if !access_errors.is_empty() {
+        let compile_errors = access_errors.iter().map(Error::to_compile_error);
+        return quote! {
+            #imp
+            #(#compile_errors)*
+        }
+        .into();
+    }
+
     let trait_ident = imp.trait_.as_ref().and_then(|x| x.1.get_ident());
     let ty = &imp.self_ty;
     let ty_str = quote!(#ty).to_string();
@@ -250,6 +338,7 @@ pub fn contractimpl(metadata: TokenStream, input: TokenStream) -> TokenStream {
                 &m.sig.inputs,
                 trait_ident,
                 &client_ident,
+                access_rules.get(ident).map_or(&[], Vec::as_slice),
             )
         })
         .collect();
@@ -259,7 +348,7 @@ pub fn contractimpl(metadata: TokenStream, input: TokenStream) -> TokenStream {
             let mut output = quote! {
                 #[#crate_path::contractargs(name = #args_ident, impl_only = true)]
                 #[#crate_path::contractclient(crate_path = #crate_path_str, name = #client_ident, impl_only = true)]
-                #[#crate_path::contractspecfn(name = #ty_str)]
+                #[#crate_path::contractspecfn(crate_path = #crate_path_str, name = #ty_str)]
                 #imp
                 #derived_ok
             };
@@ -270,6 +359,22 @@ pub fn contractimpl(metadata: TokenStream, input: TokenStream) -> TokenStream {
                 pub_methods.into_iter(),
             );
             output.extend(quote! { #cfs });
+            // Export the default functions of the contract trait that are not
+            // overridden, by invoking the macro generated with the trait.
+            if let (true, Some((_, trait_path, _))) = (args.contracttrait, &imp.trait_) {
+                let overridden = imp.items.iter().filter_map(|i| match i {
+                    ImplItem::Fn(f) => Some(&f.sig.ident),
+                    _ => None,
+                });
+                output.extend(quote! {
+                    #trait_path! {
+                        crate_path = #crate_path,
+                        impl #trait_path for #ty {
+                            #(fn #overridden() {})*
+                        }
+                    }
+                });
+            }
             output.into()
         }
         Err(derived_err) => quote! {
@@ -280,6 +385,97 @@ pub fn contractimpl(metadata: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+#[derive(Debug, FromMeta)]
+struct ContractTraitArgs {
+    #[darling(default = "default_crate_path")]
+    crate_path: Path,
+}
+
+#[proc_macro_attribute]
+pub fn contracttrait(metadata: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match NestedMeta::parse_meta_list(metadata.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(darling::Error::from(e).write_errors());
+        }
+    };
+    let args = match ContractTraitArgs::from_list(&args) {
+        Ok(v) => v,
+        Err(e) => return e.write_errors().into(),
+    };
+    let item = parse_macro_input!(input as ItemTrait);
+    derive_contract_trait(&args.crate_path, item).into()
+}
+
+#[proc_macro]
+pub fn contractimpl_trait_default_fns(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as TraitDefaultFnsInput);
+    derive_trait_default_fns(&input.crate_path, &input.imp, &input.item).into()
+}
+
+#[derive(Debug, FromMeta)]
+struct ContractUpgradeableArgs {
+    #[darling(default = "default_crate_path")]
+    crate_path: Path,
+}
+
+#[proc_macro_attribute]
+pub fn contractupgradeable(metadata: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match NestedMeta::parse_meta_list(metadata.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(darling::Error::from(e).write_errors());
+        }
+    };
+    let args = match ContractUpgradeableArgs::from_list(&args) {
+        Ok(v) => v,
+        Err(e) => return e.write_errors().into(),
+    };
+    let crate_path = &args.crate_path;
+    let crate_path_str = quote!(#crate_path).to_string();
+
+    let imp = parse_macro_input!(input as ItemImpl);
+    let is_upgradeable = imp
+        .trait_
+        .as_ref()
+        .and_then(|(_, path, _)| path.segments.last())
+        .is_some_and(|s| s.ident == "Upgradeable");
+    if !is_upgradeable {
+        return Error::new(
+            imp.span(),
+            "contractupgradeable must be applied to an impl of the Upgradeable trait",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let ty = &imp.self_ty;
+
+    quote! {
+        #imp
+
+        #[#crate_path::contractimpl(crate_path = #crate_path_str)]
+        impl #ty {
+            /// Returns the version of the data stored by the contract.
+            pub fn version(env: #crate_path::Env) -> u32 {
+                #crate_path::upgrade::version(&env)
+            }
+
+            /// Replaces the code of the contract with the Wasm identified by
+            /// the hash. Invoke `migrate` after the upgrade.
+            pub fn upgrade(env: #crate_path::Env, new_wasm_hash: #crate_path::BytesN<32>) {
+                #crate_path::upgrade::upgrade::<Self>(&env, new_wasm_hash)
+            }
+
+            /// Migrates the data stored by the contract to the version of the
+            /// code, and returns the new version.
+            pub fn migrate(env: #crate_path::Env) -> u32 {
+                #crate_path::upgrade::migrate::<Self>(&env)
+            }
+        }
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn contractmetabuiltin(_metadata: TokenStream) -> TokenStream {
     // The following two lines assume that the soroban-sdk-macros crate always
@@ -382,6 +578,8 @@ struct ContractTypeArgs {
     crate_path: Path,
     lib: Option<String>,
     export: Option<bool>,
+    #[darling(default)]
+    transparent: bool,
 }
 
 #[proc_macro_attribute]
@@ -407,25 +605,40 @@ pub fn contracttype(metadata: TokenStream, input: TokenStream) -> TokenStream {
     } else {
         matches!(input.vis, Visibility::Public(_))
     };
+    let generics = &input.generics;
     let derived = match &input.data {
+        Data::Struct(s) if args.transparent => {
+            derive_type_struct_transparent(&args.crate_path, vis, ident, generics, s, gen_spec)
+        }
+        _ if args.transparent => Error::new(
+            input.span(),
+            "only tuple structs with a single field are supported as transparent contract types",
+        )
+        .to_compile_error(),
         Data::Struct(s) => match s.fields {
-            Fields::Named(_) => {
-                derive_type_struct(&args.crate_path, vis, ident, attrs, s, gen_spec, &args.lib)
-            }
+            Fields::Named(_) => derive_type_struct(
+                &args.crate_path,
+                vis,
+                ident,
+                generics,
+                attrs,
+                s,
+                gen_spec,
+                &args.lib,
+            ),
             Fields::Unnamed(_) => derive_type_struct_tuple(
                 &args.crate_path,
                 vis,
                 ident,
+                generics,
                 attrs,
                 s,
                 gen_spec,
                 &args.lib,
             ),
-            Fields::Unit => Error::new(
-                s.fields.span(),
-                "unit structs are not supported as contract types",
-            )
-            .to_compile_error(),
+            Fields::Unit => {
+                derive_type_struct_unit(&args.crate_path, vis, ident, attrs, s, gen_spec, &args.lib)
+            }
         },
         Data::Enum(e) => {
             let count_of_variants = e.variants.len();
@@ -435,7 +648,22 @@ pub fn contracttype(metadata: TokenStream, input: TokenStream) -> TokenStream {
                 .filter(|v| v.discriminant.is_some())
                 .count();
             if count_of_int_variants == 0 {
-                derive_type_enum(&args.crate_path, vis, ident, attrs, e, gen_spec, &args.lib)
+                derive_type_enum(
+                    &args.crate_path,
+                    vis,
+                    ident,
+                    generics,
+                    attrs,
+                    e,
+                    gen_spec,
+                    &args.lib,
+                )
+            } else if !generics.params.is_empty() {
+                Error::new(
+                    generics.span(),
+                    "enums with integer variants are unsupported as generic contract types",
+                )
+                .to_compile_error()
             } else if count_of_int_variants == count_of_variants {
                 derive_type_enum_int(&args.crate_path, vis, ident, attrs, e, gen_spec, &args.lib)
             } else {
@@ -449,6 +677,8 @@ pub fn contracttype(metadata: TokenStream, input: TokenStream) -> TokenStream {
         )
         .to_compile_error(),
     };
+    let mut input = input;
+    strip_contracttype_field_attrs(&mut input.data);
     quote! {
         #input
         #derived
@@ -479,6 +709,11 @@ pub fn contracterror(metadata: TokenStream, input: TokenStream) -> TokenStream {
         matches!(input.vis, Visibility::Public(_))
     };
     let derived = match &input.data {
+        Data::Enum(_) if !input.generics.params.is_empty() => Error::new(
+            input.generics.span(),
+            "generics are unsupported on contract errors",
+        )
+        .to_compile_error(),
         Data::Enum(e) => {
             if e.variants.iter().all(|v| v.discriminant.is_some()) {
                 derive_type_error_enum_int(&args.crate_path, ident, attrs, e, gen_spec, &args.lib)
@@ -624,6 +859,70 @@ struct ContractImportArgs {
     file: String,
     #[darling(default)]
     sha256: darling::util::SpannedValue<Option<String>>,
+    #[darling(default)]
+    min_sdk_version: darling::util::SpannedValue<Option<String>>,
+    #[darling(multiple, default)]
+    meta: Vec<darling::util::SpannedValue<ContractImportMetaArgs>>,
+}
+
+#[derive(Debug, FromMeta)]
+struct ContractImportMetaArgs {
+    key: String,
+    #[darling(default)]
+    val: Option<String>,
+}
+
+/// Checks that the meta of the contract satisfies the requirements of the
+/// contractimport args.
+fn verify_contractimport_meta(wasm: &[u8], args: &ContractImportArgs) -> Result<(), Error> {
+    if args.min_sdk_version.is_none() && args.meta.is_empty() {
+        return Ok(());
+    }
+    let meta = ContractMeta::from_wasm(wasm)
+        .map_err(|e| Error::new(Span::call_site(), format!("reading contract meta: {e}")))?;
+
+    if let Some(min) = args.min_sdk_version.as_deref() {
+        let span = args.min_sdk_version.span();
+        let min = semver::Version::parse(min)
+            .map_err(|e| Error::new(span, format!("parsing min_sdk_version: {e}")))?;
+        let Some(version) = meta.sdk_version() else {
+            return Err(Error::new(
+                span,
+                "contract meta does not contain the sdk version",
+            ));
+        };
+        let version = semver::Version::parse(version).map_err(|e| {
+            Error::new(span, format!("parsing contract sdk version {version}: {e}"))
+        })?;
+        if version < min {
+            return Err(Error::new(
+                span,
+                format!("contract sdk version {version} is less than the minimum {min}"),
+            ));
+        }
+    }
+
+    for m in &args.meta {
+        match (meta.get(&m.key), &m.val) {
+            (None, _) => {
+                return Err(Error::new(
+                    m.span(),
+                    format!("contract meta does not contain key {}", m.key),
+                ));
+            }
+            (Some(val), Some(expected)) if val != expected => {
+                return Err(Error::new(
+                    m.span(),
+                    format!(
+                        "contract meta key {} has value {val}, expected {expected}",
+                        m.key
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 #[proc_macro]
 pub fn contractimport(metadata: TokenStream) -> TokenStream {
@@ -649,6 +948,11 @@ pub fn contractimport(metadata: TokenStream) -> TokenStream {
         }
     };
 
+    // Verify meta.
+    if let Err(e) = verify_contractimport_meta(&wasm, &args) {
+        return e.into_compile_error().into();
+    }
+
     // Generate.
     match generate_from_wasm(&wasm, &args.file, args.sha256.as_deref()) {
         Ok(code) => quote! { #code },
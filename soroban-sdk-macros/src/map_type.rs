@@ -8,6 +8,8 @@ use syn::{
     Type, TypePath, TypeTuple,
 };
 
+use crate::generic::instance_name;
+
 // These constants' values must match the definitions of the constants with the
 // same names in soroban_sdk::crypto::bls12_381.
 pub const FP_SERIALIZED_SIZE: u32 = 48;
@@ -58,11 +60,10 @@ pub fn map_type(t: &Type, allow_hash: bool) -> Result<ScSpecTypeDef, Error> {
                     // example, a contract function expecting a `G1Affine` will appear
                     // in the WASM interface as expecting a `BytesN<96>`.
                     //
-                    // Future enhancements might allow the macro to automatically deduce
-                    // and utilize the inner types for types defined using the New Type
-                    // Idiom. For more details, see the tracking issue for supporting
-                    // type aliases:
-                    // https://github.com/stellar/rs-soroban-sdk/issues/1063
+                    // Newtypes and type aliases defined in contracts are represented
+                    // the same way, with `#[contracttype(transparent)]` for newtypes,
+                    // but are resolved by the compiler instead of by name. See the
+                    // `spec_template` module.
                     "Fp" => Ok(ScSpecTypeDef::BytesN(ScSpecTypeBytesN {
                         n: FP_SERIALIZED_SIZE,
                     })),
@@ -168,10 +169,33 @@ pub fn map_type(t: &Type, allow_hash: bool) -> Result<ScSpecTypeDef, Error> {
                                 ))
                             }
                         }
-                        _ => Err(Error::new(
-                            angle_bracketed.span(),
-                            "generics unsupported on user-defined types in contract functions",
-                        ))?,
+                        // Instances of generic contract types are represented in the
+                        // contract's interface as distinct types, named after the
+                        // generic type and its type arguments.
+                        _ => {
+                            let args = args
+                                .iter()
+                                .map(|a| match a {
+                                    GenericArgument::Type(t) => map_type(t, false),
+                                    _ => Err(Error::new(
+                                        a.span(),
+                                        "only type arguments are supported on user-defined types in contract functions",
+                                    )),
+                                })
+                                .collect::<Result<Vec<_>, Error>>()?;
+                            let name = instance_name(ident, &args);
+                            Ok(ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                                name: name.clone().try_into().map_err(|e| {
+                                    Error::new(
+                                        t.span(),
+                                        format!(
+                                            "Udt name {:?} cannot be used in XDR spec: {}",
+                                            name, e
+                                        ),
+                                    )
+                                })?,
+                            }))
+                        }
                     }
                 }
                 _ => Err(Error::new(t.span(), "unsupported type"))?,
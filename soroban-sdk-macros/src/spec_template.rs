@@ -0,0 +1,219 @@
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{ScSpecTypeDef, ScSpecTypeUdt, WriteXdr};
+use syn::{GenericArgument, Ident, Path, PathArguments, Type, TypePath};
+
+use crate::{map_type::map_type, DEFAULT_XDR_RW_LIMITS};
+
+// Name substituted into the spec types referenced in a spec entry to mark
+// where the spec type of a Rust type is resolved by the compiler. The name
+// contains nul bytes so that it cannot collide with the names of types.
+const TYPE_MARKER_PREFIX: &str = "\0soroban_type_";
+
+/// Replaces the user-defined types referenced in the spec type with markers
+/// that are filled in with the spec types resolved by the compiler, and
+/// appends the replaced types to `types`.
+///
+/// User-defined types are the types that are not builtin types, type
+/// parameters, or instances of generic contract types, and include contract
+/// types, transparent contract types, and type aliases. The spec types of them
+/// are resolved using the `SpecTypeDef` trait, and types that do not implement
+/// the trait keep the user-defined type that references them by name.
+pub fn mark_resolved_types(
+    def: &mut ScSpecTypeDef,
+    t: &Type,
+    params: &[Ident],
+    types: &mut Vec<Type>,
+) {
+    match (def, t) {
+        (def @ ScSpecTypeDef::Udt(_), Type::Path(TypePath { qself: None, path })) => {
+            let Some(segment) = path.segments.last() else {
+                return;
+            };
+            if !segment.arguments.is_none() {
+                // Instances of generic contract types.
+                return;
+            }
+            if path.get_ident().is_some_and(|i| params.contains(i)) {
+                return;
+            }
+            *def = type_marker(types.len());
+            types.push(t.clone());
+        }
+        (ScSpecTypeDef::Option(o), Type::Path(p)) => {
+            if let [v] = type_args(p)[..] {
+                mark_resolved_types(&mut o.value_type, v, params, types);
+            }
+        }
+        (ScSpecTypeDef::Result(r), Type::Path(p)) => {
+            if let [ok, err] = type_args(p)[..] {
+                mark_resolved_types(&mut r.ok_type, ok, params, types);
+                mark_resolved_types(&mut r.error_type, err, params, types);
+            }
+        }
+        (ScSpecTypeDef::Vec(v), Type::Path(p)) => {
+            if let [e] = type_args(p)[..] {
+                mark_resolved_types(&mut v.element_type, e, params, types);
+            }
+        }
+        (ScSpecTypeDef::Map(m), Type::Path(p)) => {
+            if let [k, v] = type_args(p)[..] {
+                mark_resolved_types(&mut m.key_type, k, params, types);
+                mark_resolved_types(&mut m.value_type, v, params, types);
+            }
+        }
+        (ScSpecTypeDef::Tuple(tuple), Type::Tuple(t)) => {
+            let mut value_types = tuple.value_types.to_vec();
+            for (def, t) in value_types.iter_mut().zip(&t.elems) {
+                mark_resolved_types(def, t, params, types);
+            }
+            tuple.value_types = value_types.try_into().unwrap();
+        }
+        _ => {}
+    }
+}
+
+fn type_args(p: &TypePath) -> Vec<&Type> {
+    match p.path.segments.last().map(|s| &s.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn type_marker(i: usize) -> ScSpecTypeDef {
+    ScSpecTypeDef::Udt(ScSpecTypeUdt {
+        name: format!("{TYPE_MARKER_PREFIX}{i}\0").try_into().unwrap(),
+    })
+}
+
+/// A spec entry XDR split into segments, separated by holes that are filled
+/// when the spec entry is built at compile time.
+pub struct Template<'a> {
+    segments: Vec<&'a [u8]>,
+    holes: Vec<Hole<'a>>,
+}
+
+#[derive(Clone, Copy)]
+enum Hole<'a> {
+    /// Filled with the arg at the index.
+    Arg(usize),
+    /// Filled with the spec type of the type, resolved by the compiler.
+    Type(&'a Type),
+}
+
+impl<'a> Template<'a> {
+    /// Splits the XDR at the markers. Each arg marker is a hole filled with the
+    /// arg at the index of the marker, and each type marker, set with
+    /// [`mark_resolved_types`], is a hole filled with the spec type of the type
+    /// at the index of the marker.
+    pub fn new(xdr: &'a [u8], arg_markers: &[Vec<u8>], types: &'a [Type]) -> Self {
+        let type_markers = (0..types.len())
+            .map(|i| type_marker(i).to_xdr(DEFAULT_XDR_RW_LIMITS).unwrap())
+            .collect::<Vec<_>>();
+        let markers = arg_markers
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m, Hole::Arg(i)))
+            .chain(
+                type_markers
+                    .iter()
+                    .zip(types)
+                    .map(|(m, t)| (m, Hole::Type(t))),
+            )
+            .collect::<Vec<_>>();
+
+        let mut segments = Vec::new();
+        let mut holes = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        'outer: while i < xdr.len() {
+            for (marker, hole) in &markers {
+                if xdr[i..].starts_with(marker) {
+                    segments.push(&xdr[start..i]);
+                    holes.push(*hole);
+                    i += marker.len();
+                    start = i;
+                    continue 'outer;
+                }
+            }
+            i += 1;
+        }
+        segments.push(&xdr[start..]);
+        Self { segments, holes }
+    }
+
+    /// Returns the tokens of the segments, of type `&[&[u8]]`, and of the
+    /// holes, of type `&[usize]`, for use with the functions in
+    /// `soroban_sdk::spec_generic`. The resolved spec types are included in
+    /// the segments.
+    pub fn to_tokens(&self, path: &Path) -> (TokenStream2, TokenStream2) {
+        let mut segments = Vec::new();
+        let mut holes = Vec::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            segments.push(Literal::byte_string(segment).into_token_stream());
+            match self.holes.get(i) {
+                Some(Hole::Arg(a)) => holes.push(quote! { #a }),
+                Some(Hole::Type(t)) => {
+                    // Types that do not implement SpecTypeDef, such as types
+                    // with manual conversions, are referenced by name.
+                    let by_name = map_type(t, false)
+                        .ok()
+                        .and_then(|def| def.to_xdr(DEFAULT_XDR_RW_LIMITS).ok())
+                        .unwrap_or_default();
+                    let by_name = Literal::byte_string(&by_name);
+                    holes.push(quote! { #path::spec_generic::NO_HOLE });
+                    segments.push(quote! {{
+                        use #path::spec_type::Unresolved as _;
+                        match #path::spec_type::Resolve::<#t>::SPEC_TYPE_DEF_XDR {
+                            Some(xdr) => xdr,
+                            None => #by_name,
+                        }
+                    }});
+                    holes.push(quote! { #path::spec_generic::NO_HOLE });
+                }
+                None => {}
+            }
+        }
+        (quote! { &[#(#segments),*] }, quote! { &[#(#holes),*] })
+    }
+}
+
+/// Returns the tokens of the length of the XDR, and of a const expression of
+/// the XDR as a `[u8; N]`, that has the resolved spec types of the types
+/// filled in.
+pub fn derive_xdr(path: &Path, xdr: &[u8], types: &[Type]) -> (TokenStream2, TokenStream2) {
+    if types.is_empty() {
+        let len = xdr.len();
+        let lit = Literal::byte_string(xdr);
+        return (quote! { #len }, quote! { *#lit });
+    }
+    let (segments, holes) = Template::new(xdr, &[], types).to_tokens(path);
+    let len = quote! { #path::spec_generic::instance_len(#segments, #holes, &[]) };
+    let value = quote! { #path::spec_generic::instance::<{ #len }>(#segments, #holes, &[]) };
+    (len, value)
+}
+
+/// Returns the tokens of the impl of the `SpecTypeDef` trait for a contract
+/// type, that is referenced in specs as a user-defined type with the name.
+pub fn derive_spec_type_def_udt(path: &Path, ident: &Ident, name: &str) -> TokenStream2 {
+    let xdr = ScSpecTypeDef::Udt(ScSpecTypeUdt {
+        name: name.try_into().unwrap_or_default(),
+    })
+    .to_xdr(DEFAULT_XDR_RW_LIMITS)
+    .unwrap();
+    let xdr_lit = Literal::byte_string(&xdr);
+    quote! {
+        impl #path::spec_type::SpecTypeDef for #ident {
+            const SPEC_TYPE_DEF: #path::spec_type::SpecTypeDefXdr =
+                #path::spec_type::SpecTypeDefXdr::from_parts(&[#xdr_lit]);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Access contains an admin and a registry of roles for access control of
+//! contract functions.
+//!
+//! The admin and the roles are stored in instance storage, and are checked by
+//! attributes on the exported functions of a [`contractimpl`]:
+//!
+//! - `#[only_admin]` – Requires the authorization of the admin, see
+//!   [`require_admin`].
+//! - `#[require_auth(arg)]` – Requires the authorization of the [`Address`]
+//!   argument `arg`, see [`Address::require_auth`].
+//! - `#[only_role("role", arg)]` – Requires the [`Address`] argument `arg` to
+//!   have the role, and requires its authorization, see [`require_role`].
+//!
+//! The checks run before the function is called, in the order of the
+//! attributes. Each attribute also adds a line to the docs of the function,
+//! so the access rules appear in the contract spec and in generated clients.
+//!
+//! Roles are intended for a small number of accounts. Every role granted is
+//! stored in instance storage, which is loaded on every invocation of the
+//! contract.
+//!
+//! ### Examples
+//!
+//! ```
+//! use soroban_sdk::{access, contract, contractimpl, symbol_short, Address, Env};
+//!
+//! #[contract]
+//! pub struct Contract;
+//!
+//! #[contractimpl]
+//! impl Contract {
+//!     pub fn __constructor(env: Env, admin: Address) {
+//!         access::set_admin(&env, &admin);
+//!     }
+//!
+//!     #[only_admin]
+//!     pub fn add_minter(env: Env, minter: Address) {
+//!         access::grant_role(&env, &symbol_short!("minter"), &minter);
+//!     }
+//!
+//!     /// Mints an amount.
+//!     #[only_role("minter", minter)]
+//!     pub fn mint(env: Env, minter: Address, amount: i128) -> i128 {
+//!         amount
+//!     }
+//! }
+//!
+//! #[test]
+//! fn test() {
+//! # }
+//! # #[cfg(feature = "testutils")]
+//! # fn main() {
+//!     use soroban_sdk::testutils::Address as _;
+//!
+//!     let env = Env::default();
+//!     env.mock_all_auths();
+//!     let admin = Address::generate(&env);
+//!     let contract_id = env.register(Contract, (&admin,));
+//!     let client = ContractClient::new(&env, &contract_id);
+//!
+//!     let minter = Address::generate(&env);
+//!     client.add_minter(&minter);
+//!     assert_eq!(env.auths()[0].0, admin);
+//!
+//!     assert_eq!(client.mint(&minter, &5), 5);
+//!     assert_eq!(env.auths()[0].0, minter);
+//! }
+//! # #[cfg(not(feature = "testutils"))]
+//! # fn main() { }
+//! ```
+//!
+//! [`contractimpl`]: crate::contractimpl
+
+use crate::{
+    xdr::{ScErrorCode, ScErrorType},
+    Address, Env, Error, Symbol,
+};
+
+fn admin_key() -> Symbol {
+    soroban_sdk_macros::internal_symbol_short!("__admin")
+}
+
+fn role_key(role: &Symbol, account: &Address) -> (Symbol, Symbol, Address) {
+    (
+        soroban_sdk_macros::internal_symbol_short!("__role"),
+        role.clone(),
+        account.clone(),
+    )
+}
+
+/// Sets the admin of the current contract.
+///
+/// Setting the admin does not require any authorization, so it is typically
+/// called from the constructor, or from a function with the `#[only_admin]`
+/// attribute to transfer the admin.
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&admin_key(), admin);
+}
+
+/// Returns the admin of the current contract, if one has been set.
+pub fn admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&admin_key())
+}
+
+/// Requires the authorization of the admin of the current contract, and
+/// returns the admin.
+///
+/// ### Panics
+///
+/// If no admin has been set.
+pub fn require_admin(env: &Env) -> Address {
+    let Some(admin) = admin(env) else {
+        env.panic_with_error(Error::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction,
+        ));
+    };
+    admin.require_auth();
+    admin
+}
+
+/// Grants the role to the account.
+///
+/// Granting a role does not require any authorization, so it is typically
+/// called from a function with the `#[only_admin]` attribute.
+pub fn grant_role(env: &Env, role: &Symbol, account: &Address) {
+    env.storage().instance().set(&role_key(role, account), &());
+}
+
+/// Revokes the role from the account.
+pub fn revoke_role(env: &Env, role: &Symbol, account: &Address) {
+    env.storage().instance().remove(&role_key(role, account));
+}
+
+/// Returns true if the account has the role.
+pub fn has_role(env: &Env, role: &Symbol, account: &Address) -> bool {
+    env.storage().instance().has(&role_key(role, account))
+}
+
+/// Requires the account to have the role, and requires the authorization of
+/// the account.
+///
+/// ### Panics
+///
+/// If the account does not have the role.
+pub fn require_role(env: &Env, role: &Symbol, account: &Address) {
+    if !has_role(env, role, account) {
+        env.panic_with_error(Error::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction,
+        ));
+    }
+    account.require_auth();
+}
@@ -29,7 +29,7 @@ impl<const N: usize> Hash<N> {
     ///
     /// This is intended for test-only, since `Hash` type is only meant to be
     /// constructed via secure manners.
-    #[cfg(test)]
+    #[cfg(any(test, feature = "testutils"))]
     pub(crate) fn from_bytes(bytes: BytesN<N>) -> Self {
         Self(bytes)
     }
@@ -125,7 +125,7 @@
 
 use crate::{
     env::internal::Env as _, unwrap::UnwrapInfallible, Address, Bytes, BytesN, ConstructorArgs,
-    Env, IntoVal,
+    Env, IntoVal, Val, Vec,
 };
 
 /// Deployer provides access to deploying contracts.
@@ -303,10 +303,18 @@ impl DeployerWithAddress {
     #[deprecated(note = "use deploy_v2")]
     pub fn deploy(&self, wasm_hash: impl IntoVal<Env, BytesN<32>>) -> Address {
         let env = &self.env;
+        let wasm_hash: BytesN<32> = wasm_hash.into_val(env);
+        #[cfg(any(test, feature = "testutils"))]
+        {
+            let address = self.deployed_address();
+            if env.deploy_native_wasm(&address, &wasm_hash, &crate::vec![env]) {
+                return address;
+            }
+        }
         let address_obj = env
             .create_contract(
                 self.address.to_object(),
-                wasm_hash.into_val(env).to_object(),
+                wasm_hash.to_object(),
                 self.salt.to_object(),
             )
             .unwrap_infallible();
@@ -332,12 +340,21 @@ impl DeployerWithAddress {
         A: ConstructorArgs,
     {
         let env = &self.env;
+        let wasm_hash: BytesN<32> = wasm_hash.into_val(env);
+        let constructor_args: Vec<Val> = constructor_args.into_val(env);
+        #[cfg(any(test, feature = "testutils"))]
+        {
+            let address = self.deployed_address();
+            if env.deploy_native_wasm(&address, &wasm_hash, &constructor_args) {
+                return address;
+            }
+        }
         let address_obj = env
             .create_contract_with_constructor(
                 self.address.to_object(),
-                wasm_hash.into_val(env).to_object(),
+                wasm_hash.to_object(),
                 self.salt.to_object(),
-                constructor_args.into_val(env).to_object(),
+                constructor_args.to_object(),
             )
             .unwrap_infallible();
         unsafe { Address::unchecked_new(env.clone(), address_obj) }
@@ -372,10 +389,20 @@ impl DeployerWithAsset {
 #[cfg(any(test, feature = "testutils"))]
 #[cfg_attr(feature = "docs", doc(cfg(feature = "testutils")))]
 mod testutils {
+    use std::rc::Rc;
+
     use crate::deploy::Deployer;
-    use crate::Address;
+    use crate::testutils::ContractFunctionSet;
+    use crate::{Address, BytesN};
 
     impl crate::testutils::Deployer for Deployer {
+        fn register_contract_for_wasm_hash<C>(&self, wasm_hash: &BytesN<32>, contract: C)
+        where
+            C: ContractFunctionSet + 'static,
+        {
+            self.env.register_native_wasm(wasm_hash, Rc::new(contract));
+        }
+
         fn get_contract_instance_ttl(&self, contract: &Address) -> u32 {
             self.env
                 .host()
@@ -243,9 +243,91 @@ impl Default for Env {
 #[derive(Clone, Default)]
 struct EnvTestState {
     config: EnvTestConfig,
+    options: Rc<EnvTestOptions>,
     generators: Rc<RefCell<Generators>>,
     auth_snapshot: Rc<RefCell<AuthSnapshot>>,
     snapshot: Option<Rc<LedgerSnapshot>>,
+    native_wasm: Rc<RefCell<NativeWasm>>,
+    native_contracts: Rc<RefCell<NativeContracts>>,
+    failures: Rc<RefCell<Failures>>,
+    calls: Rc<RefCell<std::vec::Vec<CallRecord>>>,
+    measurements: Rc<RefCell<std::vec::Vec<Measurement>>>,
+    profiler: Rc<RefCell<Profiler>>,
+    costs: Rc<RefCell<std::vec::Vec<CostRecord>>>,
+}
+
+/// An invocation made through the [Env] that is in progress.
+#[cfg(any(test, feature = "testutils"))]
+struct InvocationScope {
+    record: usize,
+    start: InvocationCost,
+    top_level: bool,
+    profile_depth: Option<usize>,
+}
+
+/// Native contracts registered as the implementation of Wasm hashes.
+#[cfg(any(test, feature = "testutils"))]
+type NativeWasm = BTreeMap<[u8; 32], Rc<dyn ContractFunctionSet>>;
+
+/// Native contracts registered at contract IDs.
+#[cfg(any(test, feature = "testutils"))]
+type NativeContracts = BTreeMap<[u8; 32], Rc<dyn ContractFunctionSet>>;
+
+/// Dispatches calls from the host to a natively registered contract, passing
+/// the contract an [Env] that shares the test state of the [Env] that
+/// registered it.
+///
+/// The test state is shared so that the native wasm, failures, traces, cost
+/// measurements and profiles are recorded for calls made by native contracts,
+/// in the same way as for calls made by the test. The host holds the test
+/// state through the function set, so the test state must not hold values that
+/// hold an [Env], such as [Address], otherwise the host is kept alive by its
+/// own test state and the [Env] never writes its test snapshot when dropped.
+#[cfg(any(test, feature = "testutils"))]
+struct InternalContractFunctionSet {
+    contract: Rc<dyn ContractFunctionSet>,
+    test_state: EnvTestState,
+}
+
+#[cfg(any(test, feature = "testutils"))]
+impl internal::ContractFunctionSet for InternalContractFunctionSet {
+    fn call(&self, func: &Symbol, env_impl: &internal::EnvImpl, args: &[Val]) -> Option<Val> {
+        let env = Env {
+            env_impl: env_impl.clone(),
+            test_state: self.test_state.clone(),
+        };
+        let func = crate::Symbol::try_from_val(&env, func)
+            .unwrap_infallible()
+            .to_string();
+        // Record panics of the contract for failure reports, then let the
+        // panic continue to the host, which converts it into an error.
+        crate::testutils::failure::install_panic_hook();
+        let call = std::panic::AssertUnwindSafe(|| self.contract.call(&func, env.clone(), args));
+        match std::panic::catch_unwind(call) {
+            Ok(rv) => rv,
+            Err(payload) => {
+                let panic = PanicRecord::new(&env.current_contract_address(), &func, &*payload);
+                self.test_state.failures.borrow_mut().panics.push(panic);
+                std::panic::resume_unwind(payload)
+            }
+        }
+    }
+}
+
+/// Dispatches calls to a native contract whose instance has already been
+/// constructed, ignoring the constructor call made when the contract is
+/// registered into a forked [Env].
+#[cfg(any(test, feature = "testutils"))]
+struct ConstructedContractFunctionSet(Rc<dyn ContractFunctionSet>);
+
+#[cfg(any(test, feature = "testutils"))]
+impl ContractFunctionSet for ConstructedContractFunctionSet {
+    fn call(&self, func: &str, env: Env, args: &[Val]) -> Option<Val> {
+        if func == "__constructor" {
+            return Some(Val::VOID.into());
+        }
+        self.0.call(func, env, args)
+    }
 }
 
 /// Config for changing the default behavior of the Env when used in tests.
@@ -267,6 +349,112 @@ impl Default for EnvTestConfig {
     }
 }
 
+/// Options for changing the default behavior of the Env when used in tests,
+/// in addition to the [EnvTestConfig].
+///
+/// Options are added over time, so the options are constructed from their
+/// default and changed with the `with_` methods.
+///
+/// ```
+/// use soroban_sdk::{
+///     testutils::{EnvTestConfig, EnvTestOptions},
+///     Env,
+/// };
+///
+/// let env = Env::new_with_options(
+///     EnvTestConfig::default(),
+///     EnvTestOptions::default()
+///         .with_print_log_level(None)
+///         .with_capture_trace_in_snapshot(true),
+/// );
+/// ```
+#[cfg(any(test, feature = "testutils"))]
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct EnvTestOptions {
+    /// The minimum level of log entries that are printed to stdout as they are
+    /// logged, so that they appear in the output of failing tests. None
+    /// disables printing. Log entries are recorded regardless, and remain
+    /// available through [`Logs`][crate::testutils::Logs]. Defaults to
+    /// [`Level::Debug`][crate::logs::Level::Debug].
+    pub print_log_level: Option<crate::logs::Level>,
+    /// The number of diagnostic events included in the reports of failed
+    /// invocations, see [`Env::last_failure`]. Defaults to 10.
+    pub failure_report_events: usize,
+    /// Capture the trace of the contract invocations made in the Env, see
+    /// [`Env::trace`], in the test snapshot. The trace is built from
+    /// diagnostic events, which are not stable over time. Defaults to false.
+    pub capture_trace_in_snapshot: bool,
+    /// Record a cost profile of the contract invocations made in the Env, see
+    /// [`CostEstimate::profile`], and write it as folded stacks of CPU
+    /// instructions to `test_profiles/{test-name}.N.folded` when the Env is
+    /// dropped, for rendering with flamegraph tools. Defaults to false.
+    pub capture_profile: bool,
+    /// Capture the resources metered in each top level contract invocation
+    /// made through the Env, see [`CostEstimate::resources`], in the test
+    /// snapshot, so that changes in cost show up as changes to the test
+    /// snapshot. Only the invocations made while the option is set are
+    /// captured. The metered resources change with the version of the
+    /// environment. Defaults to false.
+    pub capture_cost_in_snapshot: bool,
+}
+
+#[cfg(any(test, feature = "testutils"))]
+impl Default for EnvTestOptions {
+    fn default() -> Self {
+        Self {
+            print_log_level: Some(crate::logs::Level::Debug),
+            failure_report_events: 10,
+            capture_trace_in_snapshot: false,
+            capture_profile: false,
+            capture_cost_in_snapshot: false,
+        }
+    }
+}
+
+#[cfg(any(test, feature = "testutils"))]
+impl EnvTestOptions {
+    /// Sets [`EnvTestOptions::print_log_level`].
+    pub fn with_print_log_level(self, print_log_level: Option<crate::logs::Level>) -> Self {
+        Self {
+            print_log_level,
+            ..self
+        }
+    }
+
+    /// Sets [`EnvTestOptions::failure_report_events`].
+    pub fn with_failure_report_events(self, failure_report_events: usize) -> Self {
+        Self {
+            failure_report_events,
+            ..self
+        }
+    }
+
+    /// Sets [`EnvTestOptions::capture_trace_in_snapshot`].
+    pub fn with_capture_trace_in_snapshot(self, capture_trace_in_snapshot: bool) -> Self {
+        Self {
+            capture_trace_in_snapshot,
+            ..self
+        }
+    }
+
+    /// Sets [`EnvTestOptions::capture_profile`].
+    pub fn with_capture_profile(self, capture_profile: bool) -> Self {
+        Self {
+            capture_profile,
+            ..self
+        }
+    }
+
+    /// Sets [`EnvTestOptions::capture_cost_in_snapshot`].
+    pub fn with_capture_cost_in_snapshot(self, capture_cost_in_snapshot: bool) -> Self {
+        Self {
+            capture_cost_in_snapshot,
+            ..self
+        }
+    }
+}
+
 impl Env {
     /// Panic with the given error.
     ///
@@ -376,6 +564,42 @@ impl Env {
     where
         T: TryFromVal<Env, Val>,
     {
+        #[cfg(any(test, feature = "testutils"))]
+        let scope = self.begin_invocation(contract_address, func, &args);
+        #[cfg(any(test, feature = "testutils"))]
+        if scope.top_level {
+            // Print the report of a failed invocation before the panic
+            // continues to the test.
+            let call = std::panic::AssertUnwindSafe(|| {
+                internal::Env::call(
+                    self,
+                    contract_address.to_object(),
+                    func.to_symbol_val(),
+                    args.to_object(),
+                )
+            });
+            let rv = match std::panic::catch_unwind(call) {
+                Ok(rv) => {
+                    self.end_invocation(&scope);
+                    rv.unwrap_infallible()
+                }
+                Err(payload) => {
+                    self.end_invocation(&scope);
+                    let record = FailureRecord::capture_from_events(
+                        self,
+                        contract_address,
+                        &func.to_string(),
+                        &args,
+                    );
+                    std::eprintln!("{}", record.to_report(self));
+                    self.test_state.failures.borrow_mut().last = Some(record);
+                    std::panic::resume_unwind(payload)
+                }
+            };
+            return T::try_from_val(self, &rv)
+                .map_err(|_| ConversionError)
+                .unwrap();
+        }
         let rv = internal::Env::call(
             self,
             contract_address.to_object(),
@@ -383,6 +607,8 @@ impl Env {
             args.to_object(),
         )
         .unwrap_infallible();
+        #[cfg(any(test, feature = "testutils"))]
+        self.end_invocation(&scope);
         T::try_from_val(self, &rv)
             .map_err(|_| ConversionError)
             .unwrap()
@@ -401,6 +627,8 @@ impl Env {
         E: TryFrom<Error>,
         E::Error: Into<InvokeError>,
     {
+        #[cfg(any(test, feature = "testutils"))]
+        let scope = self.begin_invocation(contract_address, func, &args);
         let rv = internal::Env::try_call(
             self,
             contract_address.to_object(),
@@ -408,8 +636,23 @@ impl Env {
             args.to_object(),
         )
         .unwrap_infallible();
+        #[cfg(any(test, feature = "testutils"))]
+        self.end_invocation(&scope);
         match internal::Error::try_from_val(self, &rv) {
-            Ok(err) => Err(E::try_from(err).map_err(Into::into)),
+            Ok(err) => {
+                #[cfg(any(test, feature = "testutils"))]
+                if scope.top_level {
+                    let record = FailureRecord::capture(
+                        self,
+                        contract_address,
+                        &func.to_string(),
+                        &args,
+                        err,
+                    );
+                    self.test_state.failures.borrow_mut().last = Some(record);
+                }
+                Err(E::try_from(err).map_err(Into::into))
+            }
             Err(ConversionError) => Ok(T::try_from_val(self, &rv)),
         }
     }
@@ -458,7 +701,13 @@ impl Env {
 }
 
 #[cfg(any(test, feature = "testutils"))]
-use crate::testutils::cost_estimate::CostEstimate;
+use crate::testutils::cost_estimate::{self, CostEstimate, CostRecord, CostSnapshot, Measurement};
+#[cfg(any(test, feature = "testutils"))]
+use crate::testutils::failure::{FailureRecord, FailureReport, Failures, PanicRecord};
+#[cfg(any(test, feature = "testutils"))]
+use crate::testutils::profile::{ProfileMetric, Profiler};
+#[cfg(any(test, feature = "testutils"))]
+use crate::testutils::trace::{self, CallRecord, InvocationCost, Trace, TraceSnapshot};
 #[cfg(any(test, feature = "testutils"))]
 use crate::{
     auth,
@@ -476,7 +725,7 @@ use internal::ContractInvocationEvent;
 #[cfg(any(test, feature = "testutils"))]
 use soroban_ledger_snapshot::LedgerSnapshot;
 #[cfg(any(test, feature = "testutils"))]
-use std::{path::Path, rc::Rc};
+use std::{collections::BTreeMap, path::Path, rc::Rc};
 #[cfg(any(test, feature = "testutils"))]
 use xdr::{LedgerEntry, LedgerKey, LedgerKeyContractData, SorobanAuthorizationEntry};
 
@@ -498,6 +747,119 @@ impl Env {
         f((*self.test_state.generators).borrow_mut())
     }
 
+    pub(crate) fn test_options(&self) -> &EnvTestOptions {
+        &self.test_state.options
+    }
+
+    pub(crate) fn failures(&self) -> &RefCell<Failures> {
+        &self.test_state.failures
+    }
+
+    /// Records an invocation made through the Env for the trace. If no
+    /// contract is executing, first clears the invocations recorded and the
+    /// failures captured during the previous top level invocation, like the
+    /// host clears its events.
+    fn begin_invocation(
+        &self,
+        contract_address: &Address,
+        func: &crate::Symbol,
+        args: &Vec<Val>,
+    ) -> InvocationScope {
+        let top_level = !self.env_impl.has_frame().unwrap();
+        let mut calls = self.test_state.calls.borrow_mut();
+        let caller = if top_level {
+            let mut failures = self.test_state.failures.borrow_mut();
+            failures.panics.clear();
+            failures.last = None;
+            calls.clear();
+            None
+        } else {
+            Some(self.current_contract_address().to_object())
+        };
+        calls.push(CallRecord {
+            caller,
+            contract: contract_address.to_object(),
+            function: func.to_symbol_val(),
+            args: args.to_object(),
+            cost: None,
+        });
+        let profile_depth = self.test_state.options.capture_profile.then(|| {
+            self.test_state.profiler.borrow_mut().begin(
+                &self.env_impl.budget_cloned(),
+                contract_address,
+                func,
+                top_level,
+            )
+        });
+        InvocationScope {
+            record: calls.len() - 1,
+            // The budget is reset at the start of a top level invocation.
+            start: if top_level {
+                InvocationCost::default()
+            } else {
+                self.budget_consumed()
+            },
+            top_level,
+            profile_depth,
+        }
+    }
+
+    pub(crate) fn measurements(&self) -> &RefCell<std::vec::Vec<Measurement>> {
+        &self.test_state.measurements
+    }
+
+    pub(crate) fn profiler(&self) -> &RefCell<Profiler> {
+        &self.test_state.profiler
+    }
+
+    /// Records the cost of an invocation that has returned, and records the
+    /// resources of a top level invocation for the test snapshot and the
+    /// measurements in progress.
+    fn end_invocation(&self, scope: &InvocationScope) {
+        let end = self.budget_consumed();
+        self.test_state.calls.borrow_mut()[scope.record].cost = Some(InvocationCost {
+            cpu_insns: end.cpu_insns.saturating_sub(scope.start.cpu_insns),
+            mem_bytes: end.mem_bytes.saturating_sub(scope.start.mem_bytes),
+        });
+        if let Some(depth) = scope.profile_depth {
+            self.test_state
+                .profiler
+                .borrow_mut()
+                .end(&self.env_impl.budget_cloned(), depth);
+        }
+        if !scope.top_level {
+            return;
+        }
+        let Some(resources) = self.host().get_last_invocation_resources() else {
+            return;
+        };
+        let mut measurements = self.test_state.measurements.borrow_mut();
+        if !measurements.is_empty() {
+            let fee = cost_estimate::estimate_fee(&resources);
+            for measurement in measurements.iter_mut() {
+                measurement.add(&resources, &fee);
+            }
+        }
+        if !self.test_state.options.capture_cost_in_snapshot {
+            return;
+        }
+        let calls = self.test_state.calls.borrow();
+        let call = &calls[scope.record];
+        self.test_state.costs.borrow_mut().push(CostRecord {
+            contract: call.contract,
+            function: call.function,
+            resources,
+        });
+    }
+
+    fn budget_consumed(&self) -> InvocationCost {
+        let budget = self.env_impl.budget_cloned();
+        InvocationCost {
+            cpu_insns: budget.get_cpu_insns_consumed().unwrap(),
+            mem_bytes: budget.get_mem_bytes_consumed().unwrap(),
+        }
+    }
+
     /// Create an Env with the test config.
     pub fn new_with_config(config: EnvTestConfig) -> Env {
         struct EmptySnapshotSource();
@@ -532,6 +894,18 @@ impl Env {
         self.test_state.config = config;
     }
 
+    /// Create an Env with the test config and options.
+    pub fn new_with_options(config: EnvTestConfig, options: EnvTestOptions) -> Env {
+        let mut env = Self::new_with_config(config);
+        env.set_options(options);
+        env
+    }
+
+    /// Change the test options of an Env.
+    pub fn set_options(&mut self, options: EnvTestOptions) {
+        self.test_state.options = Rc::new(options);
+    }
+
     /// Used by multiple constructors to configure test environments consistently.
     fn new_for_testutils(
         config: EnvTestConfig,
@@ -576,9 +950,17 @@ impl Env {
             env_impl,
             test_state: EnvTestState {
                 config,
+                options: Default::default(),
                 generators: generators.unwrap_or_default(),
                 snapshot,
                 auth_snapshot,
+                native_wasm: Default::default(),
+                native_contracts: Default::default(),
+                failures: Default::default(),
+                calls: Default::default(),
+                measurements: Default::default(),
+                profiler: Default::default(),
+                costs: Default::default(),
             },
         };
 
@@ -612,6 +994,124 @@ impl Env {
         CostEstimate::new(self.clone())
     }
 
+    /// Returns the report of the last top level contract invocation, if it
+    /// failed.
+    ///
+    /// The report is captured for invocations made with
+    /// [`Env::try_invoke_contract`], or with the `try_` functions of a contract
+    /// client, and contains the contract, function, and arguments invoked, the
+    /// error, the panic message and location of natively registered contracts,
+    /// the contract calls in progress when the failure occurred, and the last
+    /// diagnostic events. Invocations that panic on failure, such as the
+    /// functions of a contract client without the `try_` prefix, print the
+    /// report before panicking.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// use soroban_sdk::{contract, contractimpl, Env};
+    ///
+    /// #[contract]
+    /// pub struct Contract;
+    ///
+    /// #[contractimpl]
+    /// impl Contract {
+    ///     pub fn withdraw(amount: i128) -> i128 {
+    ///         if amount < 0 {
+    ///             panic!("negative amount");
+    ///         }
+    ///         amount
+    ///     }
+    /// }
+    ///
+    /// #[test]
+    /// fn test() {
+    /// # }
+    /// # #[cfg(feature = "testutils")]
+    /// # fn main() {
+    ///     let env = Env::default();
+    ///     let contract_id = env.register(Contract, ());
+    ///     let client = ContractClient::new(&env, &contract_id);
+    ///
+    ///     assert!(client.try_withdraw(&-1).is_err());
+    ///
+    ///     let report = env.last_failure().unwrap();
+    ///     assert_eq!(report.function, "withdraw");
+    ///     assert_eq!(report.panic.unwrap().message, "negative amount");
+    /// }
+    /// # #[cfg(not(feature = "testutils"))]
+    /// # fn main() { }
+    /// ```
+    pub fn last_failure(&self) -> Option<FailureReport> {
+        let failures = self.test_state.failures.borrow();
+        failures.last.as_ref().map(|r| r.to_report(self))
+    }
+
+    /// Returns the trace of the last top level contract invocation made in the
+    /// Env.
+    ///
+    /// The trace is a tree of invocations, with the invocation made by the
+    /// test at the top level, and the invocations made by contracts nested
+    /// under the invocation that made them. Each invocation has the caller,
+    /// contract, function, arguments, result or error, cost, and the events it
+    /// emitted. The trace is displayed as a tree with [`std::fmt::Display`].
+    ///
+    /// The trace is built from diagnostic events, and includes invocations made
+    /// by the test with and without the `try_` prefix, by natively registered
+    /// contracts, and by Wasm contracts.
+    ///
+    /// The cost of an invocation is measured only for invocations made through
+    /// the Env, by the test or by natively registered contracts. Invocations
+    /// made by Wasm contracts call the host directly, so they have no cost of
+    /// their own in the trace, and their cost is included in the cost of the
+    /// invocation that made them. Use a natively registered contract to measure
+    /// the cost of each invocation it makes.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// use soroban_sdk::{contract, contractimpl, Address, Env};
+    ///
+    /// #[contract]
+    /// pub struct Contract;
+    ///
+    /// #[contractimpl]
+    /// impl Contract {
+    ///     pub fn add(x: u32, y: u32) -> u32 {
+    ///         x + y
+    ///     }
+    ///
+    ///     pub fn add_twice(env: Env, contract: Address, x: u32) -> u32 {
+    ///         let client = ContractClient::new(&env, &contract);
+    ///         client.add(&client.add(&x, &x), &x)
+    ///     }
+    /// }
+    ///
+    /// #[test]
+    /// fn test() {
+    /// # }
+    /// # #[cfg(feature = "testutils")]
+    /// # fn main() {
+    ///     let env = Env::default();
+    ///     let contract_id = env.register(Contract, ());
+    ///     let client = ContractClient::new(&env, &contract_id);
+    ///
+    ///     assert_eq!(client.add_twice(&contract_id, &2), 6);
+    ///
+    ///     let trace = env.trace();
+    ///     println!("{trace}");
+    ///     let invocation = &trace.0[0];
+    ///     assert_eq!(invocation.caller, None);
+    ///     assert_eq!(invocation.sub_invocations.len(), 2);
+    ///     assert_eq!(invocation.sub_invocations[0].caller, Some(contract_id.clone()));
+    /// }
+    /// # #[cfg(not(feature = "testutils"))]
+    /// # fn main() { }
+    /// ```
+    pub fn trace(&self) -> Trace {
+        Trace::from_xdr(self, &self.to_trace_snapshot())
+    }
+
     /// Register a contract with the [Env] for testing.
     ///
     /// Pass the contract type when the contract is defined in the current crate
@@ -821,41 +1321,25 @@ impl Env {
         contract: T,
         constructor_args: A,
     ) -> Address {
-        struct InternalContractFunctionSet<T: ContractFunctionSet>(pub(crate) T);
-        impl<T: ContractFunctionSet> internal::ContractFunctionSet for InternalContractFunctionSet<T> {
-            fn call(
-                &self,
-                func: &Symbol,
-                env_impl: &internal::EnvImpl,
-                args: &[Val],
-            ) -> Option<Val> {
-                let env = Env {
-                    env_impl: env_impl.clone(),
-                    test_state: Default::default(),
-                };
-                self.0.call(
-                    crate::Symbol::try_from_val(&env, func)
-                        .unwrap_infallible()
-                        .to_string()
-                        .as_str(),
-                    env,
-                    args,
-                )
-            }
-        }
-
         let contract_id = if let Some(contract_id) = contract_id.into() {
             contract_id.clone()
         } else {
             Address::generate(self)
         };
+        let contract: Rc<dyn ContractFunctionSet> = Rc::new(contract);
         self.env_impl
             .register_test_contract_with_constructor(
                 contract_id.to_object(),
-                Rc::new(InternalContractFunctionSet(contract)),
+                Rc::new(InternalContractFunctionSet {
+                    contract: contract.clone(),
+                    test_state: self.test_state.clone(),
+                }),
                 constructor_args.into_val(self).to_object(),
             )
             .unwrap();
+        (*self.test_state.native_contracts)
+            .borrow_mut()
+            .insert(contract_id.contract_id().0 .0, contract);
         contract_id
     }
 
@@ -1370,6 +1854,10 @@ impl Env {
     /// contract error and use the error with `contracterror` attribute
     /// otherwise.
     ///
+    /// To compute the signature payload and auth contexts that the host would
+    /// pass to `__check_auth` for a given invocation, use
+    /// [`CheckAuthPayload`][crate::testutils::CheckAuthPayload].
+    ///
     /// ### Examples
     /// ```
     /// use soroban_sdk::{contract, contracterror, contractimpl, testutils::{Address as _, BytesN as _}, vec, auth::Context, BytesN, Env, Vec, Val};
@@ -1493,6 +1981,111 @@ impl Env {
             .unwrap();
     }
 
+    /// Registers a native contract as the implementation of contracts deployed
+    /// with the Wasm hash.
+    pub(crate) fn register_native_wasm(
+        &self,
+        wasm_hash: &BytesN<32>,
+        contract: Rc<dyn ContractFunctionSet>,
+    ) {
+        (*self.test_state.native_wasm)
+            .borrow_mut()
+            .insert(wasm_hash.to_array(), contract);
+    }
+
+    /// Deploys the native contract registered for the Wasm hash at the
+    /// contract address, calling its constructor with the arguments.
+    ///
+    /// Returns false, and deploys nothing, if no native contract is registered
+    /// for the Wasm hash.
+    pub(crate) fn deploy_native_wasm(
+        &self,
+        contract_address: &Address,
+        wasm_hash: &BytesN<32>,
+        constructor_args: &Vec<Val>,
+    ) -> bool {
+        let contract = (*self.test_state.native_wasm)
+            .borrow()
+            .get(&wasm_hash.to_array())
+            .cloned();
+        let Some(contract) = contract else {
+            return false;
+        };
+
+        // Deploying to an address that already has a contract fails in the
+        // same way that it does for Wasm contracts.
+        let key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+            contract: xdr::ScAddress::Contract(contract_address.contract_id()),
+            key: xdr::ScVal::LedgerKeyContractInstance,
+            durability: xdr::ContractDataDurability::Persistent,
+        }));
+        if self.host().get_ledger_entry(&key).unwrap().is_some() {
+            self.panic_with_error(Error::from_type_and_code(
+                xdr::ScErrorType::Storage,
+                xdr::ScErrorCode::ExistingValue,
+            ));
+        }
+
+        let res = self.env_impl.register_test_contract_with_constructor(
+            contract_address.to_object(),
+            Rc::new(InternalContractFunctionSet {
+                contract: contract.clone(),
+                test_state: self.test_state.clone(),
+            }),
+            constructor_args.to_object(),
+        );
+        internal::reject_err(&self.env_impl, res).unwrap_infallible();
+        (*self.test_state.native_contracts)
+            .borrow_mut()
+            .insert(contract_address.contract_id().0 .0, contract);
+        true
+    }
+
+    /// Creates an [Env] holding a copy of the ledger, generators, and native
+    /// contracts of this [Env].
+    ///
+    /// Invocations in the fork have no effect on this [Env], which makes it
+    /// possible to observe what an invocation would do before performing it.
+    pub(crate) fn fork(&self) -> Env {
+        let ledger = self.to_ledger_snapshot();
+        let generators = (*self.test_state.generators).borrow().clone();
+        let mut env = Env::new_for_testutils(
+            EnvTestConfig {
+                capture_snapshot_at_drop: false,
+            },
+            Rc::new(ledger.clone()),
+            Some(Rc::new(RefCell::new(generators))),
+            ledger.ledger_info(),
+            Some(Rc::new(ledger)),
+        );
+        env.set_options(EnvTestOptions {
+            capture_profile: false,
+            ..(*self.test_state.options).clone()
+        });
+        (*env.test_state.native_wasm)
+            .borrow_mut()
+            .clone_from(&(*self.test_state.native_wasm).borrow());
+        for (contract_id, contract) in (*self.test_state.native_contracts).borrow().iter() {
+            let contract_address = Address::try_from_val(
+                &env,
+                &xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash(*contract_id))),
+            )
+            .unwrap();
+            // The instance of the contract is in the copied ledger, so the
+            // contract is registered without constructing it again.
+            env.env_impl
+                .register_test_contract(
+                    contract_address.to_object(),
+                    Rc::new(InternalContractFunctionSet {
+                        contract: Rc::new(ConstructedContractFunctionSet(contract.clone())),
+                        test_state: env.test_state.clone(),
+                    }),
+                )
+                .unwrap();
+        }
+        env
+    }
+
     /// Run the function as if executed by the given contract ID.
     ///
     /// Used to write or read contract data, or take other actions in tests for
@@ -1545,6 +2138,16 @@ impl Env {
             auth: (*self.test_state.auth_snapshot).borrow().clone(),
             ledger: self.to_ledger_snapshot(),
             events: self.to_events_snapshot(),
+            trace: self
+                .test_state
+                .options
+                .capture_trace_in_snapshot
+                .then(|| self.to_trace_snapshot()),
+            cost: self
+                .test_state
+                .options
+                .capture_cost_in_snapshot
+                .then(|| self.to_cost_snapshot()),
         }
     }
 
@@ -1597,6 +2200,24 @@ impl Env {
         self.to_ledger_snapshot().write_file(p).unwrap();
     }
 
+    /// Create a trace snapshot from the Env's current state.
+    pub(crate) fn to_trace_snapshot(&self) -> TraceSnapshot {
+        let events = self.host().get_events().unwrap().0;
+        trace::trace_snapshot(&events, &self.test_state.calls.borrow(), self)
+    }
+
+    /// Create a cost snapshot from the Env's current state.
+    pub(crate) fn to_cost_snapshot(&self) -> CostSnapshot {
+        CostSnapshot(
+            self.test_state
+                .costs
+                .borrow()
+                .iter()
+                .map(|r| r.to_snapshot(self))
+                .collect(),
+        )
+    }
+
     /// Create an events snapshot from the Env's current state.
     pub(crate) fn to_events_snapshot(&self) -> EventsSnapshot {
         EventsSnapshot(
@@ -1639,6 +2260,9 @@ impl Drop for Env {
         if self.env_impl.can_finish() && self.test_state.config.capture_snapshot_at_drop {
             self.to_test_snapshot_file();
         }
+        if self.env_impl.can_finish() && self.test_state.options.capture_profile {
+            self.to_test_profile_file();
+        }
     }
 }
 
@@ -1652,6 +2276,55 @@ struct LastTestSnapshot {
 #[cfg(any(test, feature = "testutils"))]
 thread_local! {
     static LAST_TEST_SNAPSHOT: RefCell<LastTestSnapshot> = RefCell::new(LastTestSnapshot::default());
+    static LAST_TEST_PROFILE: RefCell<LastTestSnapshot> = RefCell::new(LastTestSnapshot::default());
+}
+
+/// Returns the `{dir}/{test-name}.N.{extension}` path of a file for the
+/// currently executing test, where `N` is incremented for each file written
+/// for the test, along with the name of the test, or None if no test is
+/// executing.
+#[cfg(any(test, feature = "testutils"))]
+fn test_file_path(
+    dir: &str,
+    extension: &str,
+    last: &'static std::thread::LocalKey<RefCell<LastTestSnapshot>>,
+) -> Option<(String, std::path::PathBuf)> {
+    let thread = std::thread::current();
+    let Some(test_name) = thread.name() else {
+        // The stock unit test runner sets a thread name.
+        // If there is no thread name, assume this is not running as
+        // part of a unit test, and do nothing.
+        return None;
+    };
+    if test_name == "main" {
+        // When doc tests are running they're all run with the thread name
+        // main. There's no way to detect which doc test is being run and
+        // there's little value in writing and overwriting a single file for
+        // all doc tests.
+        return None;
+    }
+    let file_number = last.with_borrow_mut(|l| {
+        if test_name == l.name {
+            *l = LastTestSnapshot::default();
+            l.name = test_name.to_owned();
+        }
+        l.number += 1;
+        l.number
+    });
+    // Break up the test name into directories, using :: as the separator.
+    // The :: module separator cannot be written into the filename because
+    // some operating systems (e.g. Windows) do not allow the : character in
+    // filenames.
+    let test_name_path = test_name
+        .split("::")
+        .map(|p| std::path::Path::new(p).to_path_buf())
+        .reduce(|p0, p1| p0.join(p1))
+        .expect("test name to not be empty");
+    let dir = std::path::Path::new(dir);
+    let p = dir
+        .join(&test_name_path)
+        .with_extension(format!("{file_number}.{extension}"));
+    Some((test_name.to_owned(), p))
 }
 
 #[doc(hidden)]
@@ -1685,46 +2358,39 @@ impl Env {
         }
 
         // Determine path to write test snapshots to.
-        let thread = std::thread::current();
-        let Some(test_name) = thread.name() else {
-            // The stock unit test runner sets a thread name.
-            // If there is no thread name, assume this is not running as
-            // part of a unit test, and do nothing.
+        let Some((test_name, p)) = test_file_path("test_snapshots", "json", &LAST_TEST_SNAPSHOT)
+        else {
             return;
         };
-        if test_name == "main" {
-            // When doc tests are running they're all run with the thread name
-            // main. There's no way to detect which doc test is being run and
-            // there's little value in writing and overwriting a single file for
-            // all doc tests.
-            return;
-        }
-        let file_number = LAST_TEST_SNAPSHOT.with_borrow_mut(|l| {
-            if test_name == l.name {
-                *l = LastTestSnapshot::default();
-                l.name = test_name.to_owned();
-            }
-            l.number += 1;
-            l.number
-        });
-        // Break up the test name into directories, using :: as the separator.
-        // The :: module separator cannot be written into the filename because
-        // some operating systems (e.g. Windows) do not allow the : character in
-        // filenames.
-        let test_name_path = test_name
-            .split("::")
-            .map(|p| std::path::Path::new(p).to_path_buf())
-            .reduce(|p0, p1| p0.join(p1))
-            .expect("test name to not be empty");
-        let dir = std::path::Path::new("test_snapshots");
-        let p = dir
-            .join(&test_name_path)
-            .with_extension(format!("{file_number}.json"));
 
         // Write test snapshots to file.
         eprintln!("Writing test snapshot file for test {test_name:?} to {p:?}.");
         snapshot.write_file(p).unwrap();
     }
+
+    /// Create a profile file for the currently executing test.
+    ///
+    /// Writes the profile as folded stacks of CPU instructions to the
+    /// `test_profiles/{test-name}.N.folded` path where `N` is incremented for
+    /// each unique `Env` in the test.
+    ///
+    /// No file will be created if no contract invocations were profiled.
+    ///
+    /// ### Panics
+    ///
+    /// If there is any error writing the file.
+    pub(crate) fn to_test_profile_file(&self) {
+        let profile = self.test_state.profiler.borrow().profile();
+        if profile.stacks.is_empty() {
+            return;
+        }
+        let Some((test_name, p)) = test_file_path("test_profiles", "folded", &LAST_TEST_PROFILE)
+        else {
+            return;
+        };
+        eprintln!("Writing test profile file for test {test_name:?} to {p:?}.");
+        profile.write_folded(p, ProfileMetric::CpuInsns).unwrap();
+    }
 }
 
 #[doc(hidden)]
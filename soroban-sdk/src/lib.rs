@@ -258,6 +258,14 @@ pub use soroban_sdk_macros::contracterror;
 /// contract.
 /// - Types for all contract types defined in the contract.
 ///
+/// Optional arguments verify the imported file, failing compilation if it does
+/// not match:
+/// - `sha256 = "<hex>"` – The SHA-256 hash of the file.
+/// - `min_sdk_version = "<version>"` – The minimum version of the SDK that
+///   built the contract, as recorded in the contract's meta.
+/// - `meta(key = "<key>")` – A meta entry the contract must contain. May be
+///   given multiple times. Add `val = "<val>"` to also require its value.
+///
 /// ### Examples
 ///
 /// ```ignore
@@ -348,6 +356,18 @@ pub use soroban_sdk_macros::contract;
 /// Functions that are publicly accessible in the implementation are invocable
 /// by other contracts, or directly by transactions, when deployed.
 ///
+/// Exported functions can be restricted with the access control attributes
+/// `#[only_admin]`, `#[require_auth(arg)]`, and `#[only_role("role", arg)]`.
+/// See the [`access`] module for details and an example.
+///
+/// Exported functions can also be guarded against reentrant calls, and against
+/// calls while the contract is paused, with the attributes `#[nonreentrant]`
+/// and `#[when_not_paused]`. See the [`security`] module for details.
+///
+/// On an implementation of a trait defined with [`contracttrait`], use
+/// `#[contractimpl(contracttrait)]` to also export the default functions of the
+/// trait that the implementation does not override.
+///
 /// ### Examples
 ///
 /// Define a contract with one function, `hello`, and call it from within a test
@@ -384,6 +404,97 @@ pub use soroban_sdk_macros::contract;
 /// ```
 pub use soroban_sdk_macros::contractimpl;
 
+/// Defines a trait whose default functions are exported by the contracts that
+/// implement it.
+///
+/// Contracts implement the trait with `#[contractimpl(contracttrait)]`. The
+/// default functions of the trait that the implementation does not override
+/// are exported as functions of the contract, with spec entries, and are
+/// added to the client of the contract. Functions the implementation
+/// overrides are exported as written in the implementation. This allows
+/// reusable functionality, such as ownership or a token interface, to be
+/// defined once, in any crate, and composed into contracts.
+///
+/// The default functions may use the access control attributes of
+/// [`contractimpl`], such as `#[only_admin]`. The attributes apply to the
+/// default function when it is exported, and are not inherited by functions
+/// that override it.
+///
+/// A macro with the same name as the trait is generated alongside the trait,
+/// and is imported with the trait. The types in the signatures of the default
+/// functions are resolved where the trait is defined, except for generic types
+/// other than `Vec`, `Map`, `BytesN`, `Hash`, `Option` and `Result`, which
+/// must be in scope where the trait is implemented. Traits with generics are
+/// not supported.
+///
+/// ### Examples
+///
+/// ```
+/// use soroban_sdk::{access, contract, contractimpl, contracttrait, Address, Env};
+///
+/// #[contracttrait]
+/// pub trait Ownable {
+///     /// Returns the owner of the contract.
+///     fn owner(env: Env) -> Address {
+///         access::admin(&env).unwrap()
+///     }
+///
+///     /// Transfers the ownership of the contract.
+///     #[only_admin]
+///     fn transfer_ownership(env: Env, new_owner: Address) {
+///         access::set_admin(&env, &new_owner);
+///     }
+/// }
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[contractimpl]
+/// impl Contract {
+///     pub fn __constructor(env: Env, owner: Address) {
+///         access::set_admin(&env, &owner);
+///     }
+/// }
+///
+/// #[contractimpl(contracttrait)]
+/// impl Ownable for Contract {}
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # #[cfg(feature = "testutils")]
+/// # fn main() {
+///     use soroban_sdk::testutils::Address as _;
+///
+///     let env = Env::default();
+///     env.mock_all_auths();
+///     let owner = Address::generate(&env);
+///     let contract_id = env.register(Contract, (&owner,));
+///     let client = ContractClient::new(&env, &contract_id);
+///
+///     assert_eq!(client.owner(), owner);
+///     let new_owner = Address::generate(&env);
+///     client.transfer_ownership(&new_owner);
+///     assert_eq!(client.owner(), new_owner);
+/// }
+/// # #[cfg(not(feature = "testutils"))]
+/// # fn main() { }
+/// ```
+pub use soroban_sdk_macros::contracttrait;
+
+/// Exports the default functions of a [`contracttrait`] that an
+/// implementation does not override. Invoked by the macro generated with the
+/// trait.
+#[doc(hidden)]
+pub use soroban_sdk_macros::contractimpl_trait_default_fns;
+
+/// Exports the functions for upgrading a contract and migrating its data.
+///
+/// Apply to the implementation of [`upgrade::Upgradeable`] for a contract to
+/// export `version`, `upgrade`, and `migrate` functions from the contract. See
+/// the [`upgrade`] module for details and an example.
+pub use soroban_sdk_macros::contractupgradeable;
+
 /// Adds a serialized SCMetaEntry::SCMetaV0 to the WASM contracts custom section
 /// under the section name 'contractmetav0'. Contract developers can use this to
 /// append metadata to their contract.
@@ -433,15 +544,63 @@ pub use soroban_sdk_macros::contractmeta;
 /// - Enums with tuple-like variants with a maximum of one tuple field are
 /// supported. The tuple field must be of a type that is also convertible to and
 /// from `Val`.
-/// - Enums with struct-like variants are not supported.
+/// - Enums with struct-like variants are supported. The fields are stored as a
+///   map, the same as the fields of a struct, and are included in the contract
+///   spec as a struct named after the enum and the variant, e.g.
+///   `ActionTransfer` for the `Transfer` variant of an `Action` enum.
 /// - Structs are supported. All fields must be of a type that is also
 /// convertible to and from `Val`.
+/// - Unit structs are supported, and are stored as void. They are included in
+///   the contract spec as structs with no fields, with the first line of their
+///   doc set to a marker that tells them apart from structs with no fields that
+///   are stored as empty maps and vecs.
+/// - Enums with tuple-like variants with no fields, e.g. `Kind::None()`, are
+///   supported, and are stored the same as unit variants.
 /// - All variant names, field names, and type names must be 10-characters or
 /// less in length.
 ///
 /// Includes the type in the contract spec so that clients can generate bindings
 /// for the type.
 ///
+/// ### Field Attributes
+///
+/// Fields of structs with named fields support attributes that control how
+/// the fields are stored:
+/// - `#[contracttype(rename = "name")]` stores the field, and includes it in
+///   the contract spec, with the name instead of the name of the field. Useful
+///   for keeping stored names short, and for renaming fields without changing
+///   how existing values are stored.
+/// - `#[contracttype(default)]` sets the field to its [`Default`] value when
+///   it is missing from a stored value. Useful for adding fields to types that
+///   have values stored before the fields were added.
+/// - `#[contracttype(skip)]` does not store the field, or include it in the
+///   contract spec, and sets the field to its [`Default`] value when converted
+///   from a stored value.
+///
+/// ```
+/// use soroban_sdk::contracttype;
+///
+/// #[contracttype]
+/// pub struct Account {
+///     #[contracttype(rename = "bal")]
+///     pub balance: i128,
+///     #[contracttype(default)]
+///     pub frozen: bool,
+///     #[contracttype(skip)]
+///     pub cached: u32,
+/// }
+/// ```
+///
+/// The fields of tuple structs are stored by position and do not support
+/// field attributes. Field attributes on them fail to compile:
+///
+/// ```compile_fail
+/// use soroban_sdk::contracttype;
+///
+/// #[contracttype]
+/// pub struct Pair(#[contracttype(skip)] pub u32, pub u32);
+/// ```
+///
 /// ### Examples
 ///
 /// Defining a contract type that is a struct and use it in a contract.
@@ -534,6 +693,14 @@ pub use soroban_sdk_macros::contractmeta;
 ///     Dark,
 /// }
 ///
+/// /// A struct-like enum is stored as a two-element vector containing the name
+/// /// of the enum variant as a Symbol, then a map of the fields.
+/// #[contracttype]
+/// #[derive(Clone, Debug, Eq, PartialEq)]
+/// pub enum Mix {
+///     Blend { a: Shade, b: Shade },
+/// }
+///
 /// /// An integer enum is stored as its integer value.
 /// #[contracttype]
 /// #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -580,6 +747,99 @@ pub use soroban_sdk_macros::contractmeta;
 /// # #[cfg(not(feature = "testutils"))]
 /// # fn main() { }
 /// ```
+///
+/// Defining a generic contract type and using instances of it in a contract.
+///
+/// Generic structs and enums are supported with type parameters. Each
+/// instance of a generic type used in a contract function, such as
+/// `Entry<Address>`, is exported in the contract spec as its own type, with a
+/// name made from the type name and its type arguments, such as
+/// `Entry_Address`. Generic types are convertible to and from [`Val`], but not
+/// to and from XDR.
+///
+/// ```
+/// #![no_std]
+/// use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+///
+/// #[contracttype]
+/// #[derive(Clone, Debug, Eq, PartialEq)]
+/// pub struct Entry<T> {
+///     pub key: u32,
+///     pub value: T,
+/// }
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[contractimpl]
+/// impl Contract {
+///     pub fn entry(key: u32, value: Address) -> Entry<Address> {
+///         Entry { key, value }
+///     }
+/// }
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # #[cfg(feature = "testutils")]
+/// # fn main() {
+/// #   use soroban_sdk::testutils::Address as _;
+///     let env = Env::default();
+///     let contract_id = env.register(Contract, ());
+///     let client = ContractClient::new(&env, &contract_id);
+///
+///     let value = Address::generate(&env);
+///     assert_eq!(client.entry(&1, &value), Entry { key: 1, value });
+/// }
+/// # #[cfg(not(feature = "testutils"))]
+/// # fn main() { }
+/// ```
+///
+/// Defining a transparent contract type and a type alias.
+///
+/// Tuple structs with a single field can be marked `transparent`, so that
+/// they are distinct types in Rust but are converted to and from [`Val`] as
+/// their field, and appear in the contract spec as the type of their field.
+/// Transparent types are not included in the contract spec as types of their
+/// own. Type aliases used in contract functions and contract types also
+/// appear in the contract spec as the type they alias.
+///
+/// ```
+/// #![no_std]
+/// use soroban_sdk::{contract, contractimpl, contracttype, Env};
+///
+/// // Appears in the contract spec as a `u64`.
+/// #[contracttype(transparent)]
+/// #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// pub struct UserId(pub u64);
+///
+/// // Appears in the contract spec as a `u32`.
+/// pub type Points = u32;
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[contractimpl]
+/// impl Contract {
+///     pub fn next(id: UserId, points: Points) -> (UserId, Points) {
+///         (UserId(id.0 + 1), points)
+///     }
+/// }
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # #[cfg(feature = "testutils")]
+/// # fn main() {
+///     let env = Env::default();
+///     let contract_id = env.register(Contract, ());
+///     let client = ContractClient::new(&env, &contract_id);
+///
+///     assert_eq!(client.next(&UserId(1), &5), (UserId(2), 5));
+/// }
+/// # #[cfg(not(feature = "testutils"))]
+/// # fn main() { }
+/// ```
 pub use soroban_sdk_macros::contracttype;
 
 /// Generates a type that helps build function args for a contract trait.
@@ -733,6 +993,12 @@ macro_rules! assert_with_error {
 #[doc(hidden)]
 pub mod unwrap;
 
+#[doc(hidden)]
+pub mod spec_generic;
+
+#[doc(hidden)]
+pub mod spec_type;
+
 mod env;
 
 mod address;
@@ -779,6 +1045,7 @@ pub mod data {
     #[deprecated(note = "use storage::Storage")]
     pub use super::storage::Storage as Data;
 }
+pub mod access;
 pub mod auth;
 mod bytes;
 pub mod crypto;
@@ -792,8 +1059,10 @@ pub mod ledger;
 pub mod logs;
 mod map;
 pub mod prng;
+pub mod security;
 pub mod storage;
 pub mod token;
+pub mod upgrade;
 mod vec;
 pub use address::Address;
 pub use bytes::{Bytes, BytesN};
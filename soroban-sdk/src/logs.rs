@@ -1,9 +1,11 @@
 //! Logging contains types for logging debug events.
 //!
-//! See [`log`][crate::log] for how to conveniently log debug events.
+//! See [`log`][crate::log] for how to conveniently log debug events, and
+//! [`debug`][crate::debug], [`info`][crate::info], [`warn`][crate::warn], and
+//! [`error`][crate::error] for how to log events with a level and named fields.
 use core::fmt::Debug;
 
-use crate::{env::internal::EnvBase, Env, Val};
+use crate::{env::internal::EnvBase, Env, Symbol, Val};
 
 /// Log a debug event.
 ///
@@ -85,6 +87,132 @@ macro_rules! log {
     };
 }
 
+/// Log a debug event at a level, with named fields.
+///
+/// Used by [`debug`][crate::debug], [`info`][crate::info],
+/// [`warn`][crate::warn], and [`error`][crate::error].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_record {
+    ($env:expr, $level:expr, $msg:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        if cfg!(debug_assertions) {
+            $env.logs().add_record($level, $msg, &[
+                <_ as $crate::IntoVal<$crate::Env, $crate::Val>>::into_val(&$level.to_symbol(), $env),
+                $(
+                    <_ as $crate::IntoVal<$crate::Env, $crate::Val>>::into_val(
+                        &$crate::Symbol::new($env, stringify!($name)),
+                        $env,
+                    ),
+                    <_ as $crate::IntoVal<$crate::Env, $crate::Val>>::into_val(&$value, $env),
+                )*
+            ]);
+        }
+    };
+}
+
+/// Log a debug event at [`Level::Debug`], with named fields.
+///
+/// Takes a [Env], a literal string, and an optional trailing sequence of
+/// fields written `name = value`, where the value may be any value that is
+/// convertible to [`Val`]. Field names must be valid [`Symbol`]s.
+///
+/// Like [`log`][crate::log], the events are structured diagnostic events, and
+/// are only enabled in builds that have `debug-assertions` enabled.
+///
+/// ### Examples
+///
+/// ```
+/// use soroban_sdk::{debug, Env};
+///
+/// let env = Env::default();
+///
+/// let amount = 5;
+/// debug!(&env, "transfer", amount = amount, fee = 1);
+/// ```
+///
+/// Assert on logs in tests:
+///
+/// ```
+/// # #[cfg(feature = "testutils")]
+/// # {
+/// use soroban_sdk::{info, logs::Level, Env};
+///
+/// let env = Env::default();
+///
+/// info!(&env, "transfer", amount = 5);
+///
+/// use soroban_sdk::testutils::Logs;
+/// let record = env.logs().records().last().unwrap().clone();
+/// assert_eq!(record.level, Level::Info);
+/// assert_eq!(record.message, "transfer");
+/// assert_eq!(record.field::<i32>(&env, "amount"), Some(5));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! debug {
+    ($env:expr, $($args:tt)*) => {
+        $crate::__log_record!($env, $crate::logs::Level::Debug, $($args)*)
+    };
+}
+
+/// Log a debug event at [`Level::Info`], with named fields.
+///
+/// See [`debug`][crate::debug] for details.
+#[macro_export]
+macro_rules! info {
+    ($env:expr, $($args:tt)*) => {
+        $crate::__log_record!($env, $crate::logs::Level::Info, $($args)*)
+    };
+}
+
+/// Log a debug event at [`Level::Warn`], with named fields.
+///
+/// See [`debug`][crate::debug] for details.
+#[macro_export]
+macro_rules! warn {
+    ($env:expr, $($args:tt)*) => {
+        $crate::__log_record!($env, $crate::logs::Level::Warn, $($args)*)
+    };
+}
+
+/// Log a debug event at [`Level::Error`], with named fields.
+///
+/// See [`debug`][crate::debug] for details.
+#[macro_export]
+macro_rules! error {
+    ($env:expr, $($args:tt)*) => {
+        $crate::__log_record!($env, $crate::logs::Level::Error, $($args)*)
+    };
+}
+
+/// Level of a log entry.
+///
+/// Entries logged with [`log`][crate::log] are at [`Level::Debug`].
+///
+/// Entries logged with a level are marked by the [symbol of the
+/// level][Level::to_symbol] as their first argument. The host logs every entry
+/// under the same event topic, so symbols starting with `__` are reserved as
+/// the first argument of an entry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// Returns the symbol that marks a log entry as logged at the level.
+    pub fn to_symbol(self) -> Symbol {
+        match self {
+            Level::Debug => soroban_sdk_macros::internal_symbol_short!("__debug"),
+            Level::Info => soroban_sdk_macros::internal_symbol_short!("__info"),
+            Level::Warn => soroban_sdk_macros::internal_symbol_short!("__warn"),
+            Level::Error => soroban_sdk_macros::internal_symbol_short!("__error"),
+        }
+    }
+}
+
 /// Logs logs debug events.
 ///
 /// See [`log`][crate::log] for how to conveniently log debug events.
@@ -122,15 +250,33 @@ impl Logs {
     /// See [`log`][crate::log] for how to conveniently log debug events.
     #[inline(always)]
     pub fn add(&self, msg: &'static str, args: &[Val]) {
+        self.add_record(Level::Debug, msg, args);
+    }
+
+    /// Log a debug event at a level.
+    ///
+    /// The arguments of entries logged with [`debug`][crate::debug],
+    /// [`info`][crate::info], [`warn`][crate::warn], and
+    /// [`error`][crate::error] are the symbol of the level followed by the
+    /// name and value of each field.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn add_record(&self, level: Level, msg: &'static str, args: &[Val]) {
         if cfg!(debug_assertions) {
             let env = self.env();
             env.log_from_slice(msg, args).unwrap();
 
             #[cfg(any(test, feature = "testutils"))]
+            if env
+                .test_options()
+                .print_log_level
+                .is_some_and(|min| level >= min)
             {
                 use crate::testutils::Logs;
                 std::println!("{}", self.all().last().unwrap());
             }
+            #[cfg(not(any(test, feature = "testutils")))]
+            let _ = level;
         }
     }
 }
@@ -164,6 +310,30 @@ impl testutils::Logs for Logs {
             .collect::<std::vec::Vec<_>>()
     }
 
+    fn records(&self) -> std::vec::Vec<testutils::LogRecord> {
+        use crate::xdr::{ContractEventBody, ContractEventType, ScAddress, ScVal};
+        use crate::{Address, TryFromVal};
+        let env = self.env();
+        let log_topics = [ScVal::Symbol("log".try_into().unwrap())];
+        env.host()
+            .get_diagnostic_events()
+            .unwrap()
+            .0
+            .into_iter()
+            .filter_map(|e| match (&e.event.type_, &e.event.body) {
+                (ContractEventType::Diagnostic, ContractEventBody::V0(ce))
+                    if ce.topics.as_slice() == log_topics =>
+                {
+                    let contract = e.event.contract_id.as_ref().map(|id| {
+                        Address::try_from_val(env, &ScAddress::Contract(id.clone())).unwrap()
+                    });
+                    Some(testutils::LogRecord::from_data(contract, &ce.data))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     fn print(&self) {
         std::println!("{}", self.all().join("\n"))
     }
@@ -0,0 +1,179 @@
+//! Security contains guards against reentrant calls, and a switch to pause
+//! contract functions.
+//!
+//! The guards are applied with attributes on the exported functions of a
+//! [`contractimpl`]:
+//!
+//! - `#[nonreentrant]` – Fails with [`SecurityError::Reentrant`] if called
+//!   while a nonreentrant function of the contract is executing, see
+//!   [`ReentrancyGuard`].
+//! - `#[when_not_paused]` – Fails with [`SecurityError::Paused`] if called
+//!   while the contract is paused, see [`Pausable`].
+//!
+//! The guards run before the function is called, in the order of the
+//! attributes, together with the attributes of the [`access`][crate::access]
+//! module. Each attribute also adds a line to the docs of the function, so the
+//! guards appear in the contract spec and in generated clients.
+//!
+//! The Soroban environment rejects calls from a contract back into a contract
+//! that is already executing, with the exception of a contract authorizing its
+//! own address with [`Address::require_auth`][crate::Address::require_auth],
+//! which calls `__check_auth` on the contract. The reentrancy guard rejects
+//! any reentrant call regardless of how it is made.
+//!
+//! ### Examples
+//!
+//! ```
+//! use soroban_sdk::{access, contract, contractimpl, security::Pausable, Address, Env};
+//!
+//! #[contract]
+//! pub struct Contract;
+//!
+//! #[contractimpl]
+//! impl Contract {
+//!     pub fn __constructor(env: Env, admin: Address) {
+//!         access::set_admin(&env, &admin);
+//!     }
+//!
+//!     #[only_admin]
+//!     pub fn pause(env: Env) {
+//!         Pausable::pause(&env);
+//!     }
+//!
+//!     #[nonreentrant]
+//!     #[when_not_paused]
+//!     pub fn withdraw(env: Env, amount: i128) -> i128 {
+//!         amount
+//!     }
+//! }
+//!
+//! #[test]
+//! fn test() {
+//! # }
+//! # #[cfg(feature = "testutils")]
+//! # fn main() {
+//!     use soroban_sdk::{security::SecurityError, testutils::Address as _};
+//!
+//!     let env = Env::default();
+//!     env.mock_all_auths();
+//!     let contract_id = env.register(Contract, (Address::generate(&env),));
+//!     let client = ContractClient::new(&env, &contract_id);
+//!
+//!     assert_eq!(client.withdraw(&5), 5);
+//!     client.pause();
+//!     assert_eq!(
+//!         client.try_withdraw(&5),
+//!         Err(Ok(SecurityError::Paused.into()))
+//!     );
+//! }
+//! # #[cfg(not(feature = "testutils"))]
+//! # fn main() { }
+//! ```
+//!
+//! [`contractimpl`]: crate::contractimpl
+
+use crate::{contracterror, Env, Symbol};
+
+/// Errors of the guards in the [`security`][self] module.
+///
+/// The codes are chosen to be distinct from the codes that contracts commonly
+/// use for their own errors.
+#[contracterror(crate_path = "crate", export = false)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SecurityError {
+    /// A nonreentrant function was called while a nonreentrant function of the
+    /// contract was executing.
+    Reentrant = 1_000_000,
+    /// A function that requires the contract not to be paused was called
+    /// while the contract was paused.
+    Paused = 1_000_001,
+}
+
+fn entered_key() -> Symbol {
+    soroban_sdk_macros::internal_symbol_short!("__entered")
+}
+
+fn paused_key() -> Symbol {
+    soroban_sdk_macros::internal_symbol_short!("__paused")
+}
+
+/// Guards the functions of the current contract against reentrant calls.
+///
+/// The guard is a flag in temporary storage that is set while a nonreentrant
+/// function executes.
+pub struct ReentrancyGuard;
+
+impl ReentrancyGuard {
+    /// Marks a nonreentrant function of the current contract as executing.
+    ///
+    /// ### Panics
+    ///
+    /// With [`SecurityError::Reentrant`] if a nonreentrant function of the
+    /// current contract is already executing.
+    pub fn enter(env: &Env) {
+        if Self::is_entered(env) {
+            env.panic_with_error(SecurityError::Reentrant);
+        }
+        env.storage().temporary().set(&entered_key(), &());
+    }
+
+    /// Marks the nonreentrant function of the current contract as no longer
+    /// executing.
+    pub fn exit(env: &Env) {
+        env.storage().temporary().remove(&entered_key());
+    }
+
+    /// Returns true if a nonreentrant function of the current contract is
+    /// executing.
+    pub fn is_entered(env: &Env) -> bool {
+        env.storage().temporary().has(&entered_key())
+    }
+}
+
+/// Pauses the functions of the current contract that require the contract not
+/// to be paused.
+///
+/// The paused state is a flag in instance storage. Pausing and unpausing do
+/// not require any authorization, so they are typically called from functions
+/// with the `#[only_admin]` attribute.
+pub struct Pausable;
+
+impl Pausable {
+    /// Pauses the current contract.
+    pub fn pause(env: &Env) {
+        env.storage().instance().set(&paused_key(), &());
+    }
+
+    /// Unpauses the current contract.
+    pub fn unpause(env: &Env) {
+        env.storage().instance().remove(&paused_key());
+    }
+
+    /// Returns true if the current contract is paused.
+    pub fn is_paused(env: &Env) -> bool {
+        env.storage().instance().has(&paused_key())
+    }
+
+    /// Requires the current contract not to be paused.
+    ///
+    /// ### Panics
+    ///
+    /// With [`SecurityError::Paused`] if the current contract is paused.
+    pub fn require_not_paused(env: &Env) {
+        if Self::is_paused(env) {
+            env.panic_with_error(SecurityError::Paused);
+        }
+    }
+}
+
+#[cfg(any(test, feature = "testutils"))]
+#[cfg_attr(feature = "docs", doc(cfg(feature = "testutils")))]
+impl crate::testutils::ReentrancyGuard for ReentrancyGuard {
+    fn with_entered<T>(env: &Env, contract_id: &crate::Address, f: impl FnOnce() -> T) -> T {
+        env.as_contract(contract_id, || Self::enter(env));
+        let result = f();
+        env.as_contract(contract_id, || Self::exit(env));
+        result
+    }
+}
@@ -0,0 +1,68 @@
+//! Helpers used by generated code to build spec entries from templates.
+//!
+//! A spec entry that cannot be generated entirely by the macros is generated
+//! as a template, a list of XDR segments separated by holes, and is built at
+//! compile time. Each hole is filled with an arg, or is left empty if it is
+//! [`NO_HOLE`].
+//!
+//! The spec entry of a generic contract type is a template that has holes that
+//! are filled with either the XDR of the name of the instance (hole `0`), or
+//! the XDR of the spec type of the type argument at the index of the hole
+//! minus one.
+
+/// Hole that is not filled with an arg, used to separate segments.
+pub const NO_HOLE: usize = usize::MAX;
+
+/// Returns the length of the spec entry produced by filling the holes of the
+/// template with the args.
+pub const fn instance_len(template: &[&[u8]], holes: &[usize], args: &[&[u8]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < template.len() {
+        len += template[i].len();
+        i += 1;
+    }
+    let mut i = 0;
+    while i < holes.len() {
+        if holes[i] != NO_HOLE {
+            len += args[holes[i]].len();
+        }
+        i += 1;
+    }
+    len
+}
+
+/// Returns the spec entry produced by filling the holes of the template with
+/// the args.
+///
+/// ### Panics
+///
+/// If `N` is not the value returned by [`instance_len`] for the same inputs.
+pub const fn instance<const N: usize>(
+    template: &[&[u8]],
+    holes: &[usize],
+    args: &[&[u8]],
+) -> [u8; N] {
+    let mut output = [0u8; N];
+    let mut output_i = 0;
+    let mut template_i = 0;
+    while template_i < template.len() {
+        output_i = copy(&mut output, output_i, template[template_i]);
+        if template_i < holes.len() && holes[template_i] != NO_HOLE {
+            output_i = copy(&mut output, output_i, args[holes[template_i]]);
+        }
+        template_i += 1;
+    }
+    assert!(output_i == N, "spec instance length mismatch");
+    output
+}
+
+const fn copy<const N: usize>(output: &mut [u8; N], mut output_i: usize, input: &[u8]) -> usize {
+    let mut input_i = 0;
+    while input_i < input.len() {
+        output[output_i] = input[input_i];
+        output_i += 1;
+        input_i += 1;
+    }
+    output_i
+}
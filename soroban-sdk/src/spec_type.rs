@@ -0,0 +1,266 @@
+//! Spec types of Rust types, resolved by the compiler.
+//!
+//! The contract macros map builtin types to spec types by name. Other types
+//! referenced in contract functions and contract types, such as contract
+//! types, transparent contract types, and type aliases, cannot be mapped by
+//! name, and the spec types of them are instead resolved by the compiler using
+//! the [`SpecTypeDef`] trait. Types that do not implement the trait, such as
+//! types that implement the conversions to and from [`Val`] manually, are
+//! referenced by name as user-defined types.
+
+use crate::xdr::ScSpecType;
+use crate::{
+    crypto::bls12_381::{Fp, Fp2, Fr, G1Affine, G2Affine},
+    Address, Bytes, BytesN, Duration, Error, Map, MuxedAddress, String, Symbol, Timepoint, Val,
+    Vec, I256, U256,
+};
+
+/// Maximum length of the XDR of a spec type.
+pub const SPEC_TYPE_DEF_MAX_LEN: usize = 1024;
+
+/// XDR of a spec type, with a fixed capacity so that it can be built in
+/// constant expressions from the XDR of other spec types.
+pub struct SpecTypeDefXdr {
+    bytes: [u8; SPEC_TYPE_DEF_MAX_LEN],
+    len: usize,
+}
+
+impl SpecTypeDefXdr {
+    /// Returns the XDR formed by concatenating the parts.
+    ///
+    /// ### Panics
+    ///
+    /// If the parts are longer than [`SPEC_TYPE_DEF_MAX_LEN`].
+    pub const fn from_parts(parts: &[&[u8]]) -> Self {
+        let mut bytes = [0u8; SPEC_TYPE_DEF_MAX_LEN];
+        let mut len = 0;
+        let mut parts_i = 0;
+        while parts_i < parts.len() {
+            let part = parts[parts_i];
+            assert!(
+                len + part.len() <= SPEC_TYPE_DEF_MAX_LEN,
+                "spec type too long"
+            );
+            let mut part_i = 0;
+            while part_i < part.len() {
+                bytes[len] = part[part_i];
+                len += 1;
+                part_i += 1;
+            }
+            parts_i += 1;
+        }
+        Self { bytes, len }
+    }
+
+    /// Returns the XDR of a spec type that has no parameters.
+    pub const fn from_type(t: ScSpecType) -> Self {
+        Self::from_parts(&[&(t as i32).to_be_bytes()])
+    }
+
+    pub const fn as_slice(&self) -> &[u8] {
+        self.bytes.split_at(self.len).0
+    }
+}
+
+/// Implemented by types that are referenced in contract specs by a spec type.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used in a contract spec",
+    note = "types used in contract functions and contract types must be builtin types, or types defined with #[contracttype] or #[contracterror]"
+)]
+pub trait SpecTypeDef {
+    /// The XDR of the spec type.
+    const SPEC_TYPE_DEF: SpecTypeDefXdr;
+    /// The XDR of the spec type as a slice.
+    const SPEC_TYPE_DEF_XDR: &'static [u8] = Self::SPEC_TYPE_DEF.as_slice();
+}
+
+/// Resolves the spec type of a type that implements [`SpecTypeDef`], and
+/// falls back to [`Unresolved`] for other types.
+///
+/// Types that implement the conversions to and from [`Val`] manually do not
+/// implement [`SpecTypeDef`], and the contract macros reference them in specs
+/// by name, as user-defined types.
+#[doc(hidden)]
+pub struct Resolve<T: ?Sized>(core::marker::PhantomData<T>);
+
+impl<T: SpecTypeDef> Resolve<T> {
+    pub const SPEC_TYPE_DEF_XDR: Option<&'static [u8]> = Some(T::SPEC_TYPE_DEF_XDR);
+}
+
+#[doc(hidden)]
+pub trait Unresolved {
+    const SPEC_TYPE_DEF_XDR: Option<&'static [u8]> = None;
+}
+
+impl<T: ?Sized> Unresolved for Resolve<T> {}
+
+macro_rules! impl_spec_type_def {
+    ($($ty:ty => $spec_type:ident),* $(,)?) => {
+        $(
+            impl SpecTypeDef for $ty {
+                const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_type(ScSpecType::$spec_type);
+            }
+        )*
+    };
+}
+
+impl_spec_type_def!(
+    Val => Val,
+    bool => Bool,
+    Error => Error,
+    u32 => U32,
+    i32 => I32,
+    u64 => U64,
+    i64 => I64,
+    Timepoint => Timepoint,
+    Duration => Duration,
+    u128 => U128,
+    i128 => I128,
+    U256 => U256,
+    I256 => I256,
+    Bytes => Bytes,
+    String => String,
+    Symbol => Symbol,
+    Address => Address,
+    MuxedAddress => MuxedAddress,
+    Fr => U256,
+);
+
+impl<const N: usize> SpecTypeDef for BytesN<N> {
+    const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+        &(ScSpecType::BytesN as i32).to_be_bytes(),
+        &(N as u32).to_be_bytes(),
+    ]);
+}
+
+macro_rules! impl_spec_type_def_bytes_n {
+    ($($ty:ty => $n:expr),* $(,)?) => {
+        $(
+            impl SpecTypeDef for $ty {
+                const SPEC_TYPE_DEF: SpecTypeDefXdr = <BytesN<{ $n }> as SpecTypeDef>::SPEC_TYPE_DEF;
+            }
+        )*
+    };
+}
+
+impl_spec_type_def_bytes_n!(
+    Fp => crate::crypto::bls12_381::FP_SERIALIZED_SIZE,
+    Fp2 => crate::crypto::bls12_381::FP2_SERIALIZED_SIZE,
+    G1Affine => crate::crypto::bls12_381::G1_SERIALIZED_SIZE,
+    G2Affine => crate::crypto::bls12_381::G2_SERIALIZED_SIZE,
+);
+
+impl<T: SpecTypeDef> SpecTypeDef for Option<T> {
+    const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+        &(ScSpecType::Option as i32).to_be_bytes(),
+        T::SPEC_TYPE_DEF_XDR,
+    ]);
+}
+
+impl<T: SpecTypeDef, E: SpecTypeDef> SpecTypeDef for Result<T, E> {
+    const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+        &(ScSpecType::Result as i32).to_be_bytes(),
+        T::SPEC_TYPE_DEF_XDR,
+        E::SPEC_TYPE_DEF_XDR,
+    ]);
+}
+
+impl<T: SpecTypeDef> SpecTypeDef for Vec<T> {
+    const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+        &(ScSpecType::Vec as i32).to_be_bytes(),
+        T::SPEC_TYPE_DEF_XDR,
+    ]);
+}
+
+impl<K: SpecTypeDef, V: SpecTypeDef> SpecTypeDef for Map<K, V> {
+    const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+        &(ScSpecType::Map as i32).to_be_bytes(),
+        K::SPEC_TYPE_DEF_XDR,
+        V::SPEC_TYPE_DEF_XDR,
+    ]);
+}
+
+macro_rules! impl_spec_type_def_tuple {
+    ($count:literal $(, $typ:ident)*) => {
+        impl<$($typ: SpecTypeDef),*> SpecTypeDef for ($($typ,)*) {
+            const SPEC_TYPE_DEF: SpecTypeDefXdr = SpecTypeDefXdr::from_parts(&[
+                &(ScSpecType::Tuple as i32).to_be_bytes(),
+                &($count as u32).to_be_bytes(),
+                $($typ::SPEC_TYPE_DEF_XDR,)*
+            ]);
+        }
+    };
+}
+
+impl_spec_type_def_tuple!(0);
+impl_spec_type_def_tuple!(1, T0);
+impl_spec_type_def_tuple!(2, T0, T1);
+impl_spec_type_def_tuple!(3, T0, T1, T2);
+impl_spec_type_def_tuple!(4, T0, T1, T2, T3);
+impl_spec_type_def_tuple!(5, T0, T1, T2, T3, T4);
+impl_spec_type_def_tuple!(6, T0, T1, T2, T3, T4, T5);
+impl_spec_type_def_tuple!(7, T0, T1, T2, T3, T4, T5, T6);
+impl_spec_type_def_tuple!(8, T0, T1, T2, T3, T4, T5, T6, T7);
+impl_spec_type_def_tuple!(9, T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_spec_type_def_tuple!(10, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_spec_type_def_tuple!(11, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_spec_type_def_tuple!(12, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xdr::{
+        Limits, ScSpecTypeBytesN, ScSpecTypeDef, ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeResult,
+        ScSpecTypeTuple, ScSpecTypeVec, WriteXdr,
+    };
+
+    fn xdr(t: ScSpecTypeDef) -> std::vec::Vec<u8> {
+        t.to_xdr(Limits::none()).unwrap()
+    }
+
+    #[test]
+    fn test_builtin() {
+        assert_eq!(u32::SPEC_TYPE_DEF_XDR, xdr(ScSpecTypeDef::U32));
+        assert_eq!(Address::SPEC_TYPE_DEF_XDR, xdr(ScSpecTypeDef::Address));
+        assert_eq!(
+            <BytesN<32>>::SPEC_TYPE_DEF_XDR,
+            xdr(ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 32 }))
+        );
+        assert_eq!(
+            G1Affine::SPEC_TYPE_DEF_XDR,
+            xdr(ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 96 }))
+        );
+    }
+
+    #[test]
+    fn test_composite() {
+        assert_eq!(
+            <Option<Vec<Map<Symbol, Result<i128, Error>>>>>::SPEC_TYPE_DEF_XDR,
+            xdr(ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                value_type: Box::new(ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                    element_type: Box::new(ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+                        key_type: Box::new(ScSpecTypeDef::Symbol),
+                        value_type: Box::new(ScSpecTypeDef::Result(Box::new(ScSpecTypeResult {
+                            ok_type: Box::new(ScSpecTypeDef::I128),
+                            error_type: Box::new(ScSpecTypeDef::Error),
+                        }))),
+                    }))),
+                }))),
+            })))
+        );
+        assert_eq!(
+            <(u32, (), Bytes)>::SPEC_TYPE_DEF_XDR,
+            xdr(ScSpecTypeDef::Tuple(Box::new(ScSpecTypeTuple {
+                value_types: [
+                    ScSpecTypeDef::U32,
+                    ScSpecTypeDef::Tuple(Box::new(ScSpecTypeTuple {
+                        value_types: [].try_into().unwrap(),
+                    })),
+                    ScSpecTypeDef::Bytes,
+                ]
+                .try_into()
+                .unwrap(),
+            })))
+        );
+    }
+}
@@ -5,26 +5,41 @@ mod auth;
 mod bytes_alloc_vec;
 mod bytes_buffer;
 mod cmp_across_env_in_tests;
+mod contract_access;
 mod contract_add_i32;
 mod contract_assert;
 mod contract_custom_account_impl;
+mod contract_custom_account_payload;
+mod contract_deploy_native;
 mod contract_docs;
 mod contract_duration;
 mod contract_fn;
 mod contract_invoke;
 mod contract_invoke_arg_count;
 mod contract_overlapping_type_fn_names;
+mod contract_security;
+mod contract_sign_with;
 mod contract_snapshot;
 mod contract_store;
 mod contract_timepoint;
+mod contract_trait;
 mod contract_udt_enum;
 mod contract_udt_enum_error;
+mod contract_udt_enum_named;
 mod contract_udt_enum_option;
+mod contract_udt_generic;
+mod contract_udt_manual;
 mod contract_udt_option;
 mod contract_udt_struct;
+mod contract_udt_struct_field_attrs;
 mod contract_udt_struct_tuple;
+mod contract_udt_transparent;
+mod contract_udt_unit;
+mod contract_upgradeable;
 mod contractimport;
 mod contractimport_with_error;
+mod contractimport_with_event;
+mod contractimport_with_meta;
 mod cost_estimate;
 mod crypto_bls12_381;
 mod crypto_ed25519;
@@ -33,11 +48,15 @@ mod crypto_secp256k1;
 mod crypto_secp256r1;
 mod crypto_sha256;
 mod env;
+mod failure_report;
+mod logs;
 mod max_ttl;
 mod muxed_address;
 mod prng;
+mod profile;
 mod proptest_scval_cmp;
 mod proptest_val_cmp;
 mod storage_testutils;
 mod token_client;
 mod token_spec;
+mod trace;
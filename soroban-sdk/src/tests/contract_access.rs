@@ -0,0 +1,211 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    access, contract, contractimpl, symbol_short,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    xdr::{ScErrorCode, ScErrorType},
+    Address, Env, Error, IntoVal, Symbol,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{Limits, ReadXdr, ScSpecEntry};
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn __constructor(env: Env, admin: Address) {
+        access::set_admin(&env, &admin);
+    }
+
+    /// Grants the minter role.
+    #[only_admin]
+    pub fn add_minter(env: Env, minter: Address) {
+        access::grant_role(&env, &symbol_short!("minter"), &minter);
+    }
+
+    #[only_admin]
+    pub fn remove_minter(env: Env, minter: Address) {
+        access::revoke_role(&env, &symbol_short!("minter"), &minter);
+    }
+
+    #[only_role("minter", minter)]
+    pub fn mint(minter: Address, amount: i128) -> i128 {
+        let _ = minter;
+        amount
+    }
+
+    #[require_auth(from)]
+    pub fn transfer(from: Address, to: Address, amount: i128) -> (Address, i128) {
+        let _ = from;
+        (to, amount)
+    }
+}
+
+#[contract]
+pub struct NoAdminContract;
+
+#[contractimpl]
+impl NoAdminContract {
+    #[only_admin]
+    pub fn exec() {}
+}
+
+fn auth(
+    env: &Env,
+    address: &Address,
+    contract_id: &Address,
+    fn_name: &str,
+    args: soroban_sdk::Vec<soroban_sdk::Val>,
+) -> (Address, AuthorizedInvocation) {
+    (
+        address.clone(),
+        AuthorizedInvocation {
+            function: AuthorizedFunction::Contract((
+                contract_id.clone(),
+                Symbol::new(env, fn_name),
+                args,
+            )),
+            sub_invocations: std::vec![],
+        },
+    )
+}
+
+#[test]
+fn test_only_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Contract, (&admin,));
+    let client = ContractClient::new(&env, &contract_id);
+
+    let minter = Address::generate(&env);
+    client.add_minter(&minter);
+    assert_eq!(
+        env.auths(),
+        std::vec![auth(
+            &env,
+            &admin,
+            &contract_id,
+            "add_minter",
+            (&minter,).into_val(&env)
+        )]
+    );
+    env.as_contract(&contract_id, || {
+        assert_eq!(access::admin(&env), Some(admin.clone()));
+        assert!(access::has_role(&env, &symbol_short!("minter"), &minter));
+    });
+}
+
+#[test]
+fn test_only_admin_without_auth() {
+    let env = Env::default();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Contract, (&admin,));
+    let client = ContractClient::new(&env, &contract_id);
+
+    let minter = Address::generate(&env);
+    assert!(client.try_add_minter(&minter).is_err());
+}
+
+#[test]
+fn test_only_admin_without_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(NoAdminContract, ());
+    let client = NoAdminContractClient::new(&env, &contract_id);
+
+    assert_eq!(
+        client.try_exec(),
+        Err(Ok(Error::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction
+        )))
+    );
+}
+
+#[test]
+fn test_only_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Contract, (&admin,));
+    let client = ContractClient::new(&env, &contract_id);
+
+    let minter = Address::generate(&env);
+    assert_eq!(
+        client.try_mint(&minter, &5),
+        Err(Ok(Error::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction
+        )))
+    );
+
+    client.add_minter(&minter);
+    assert_eq!(client.mint(&minter, &5), 5);
+    assert_eq!(
+        env.auths(),
+        std::vec![auth(
+            &env,
+            &minter,
+            &contract_id,
+            "mint",
+            (&minter, 5i128).into_val(&env)
+        )]
+    );
+
+    client.remove_minter(&minter);
+    assert!(client.try_mint(&minter, &5).is_err());
+}
+
+#[test]
+fn test_require_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let contract_id = env.register(Contract, (&admin,));
+    let client = ContractClient::new(&env, &contract_id);
+
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    assert_eq!(client.transfer(&from, &to, &3), (to.clone(), 3));
+    assert_eq!(
+        env.auths(),
+        std::vec![auth(
+            &env,
+            &from,
+            &contract_id,
+            "transfer",
+            (&from, &to, 3i128).into_val(&env)
+        )]
+    );
+}
+
+#[test]
+fn test_spec_docs() {
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_ADD_MINTER, Limits::none()).unwrap();
+    let ScSpecEntry::FunctionV0(f) = entry else {
+        panic!("not a function");
+    };
+    assert_eq!(
+        f.doc.to_utf8_string_lossy(),
+        "Grants the minter role.\n\nRequires the authorization of the admin."
+    );
+
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_MINT, Limits::none()).unwrap();
+    let ScSpecEntry::FunctionV0(f) = entry else {
+        panic!("not a function");
+    };
+    assert_eq!(
+        f.doc.to_utf8_string_lossy(),
+        "Requires `minter` to have the `minter` role, and the authorization of `minter`."
+    );
+
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_TRANSFER, Limits::none()).unwrap();
+    let ScSpecEntry::FunctionV0(f) = entry else {
+        panic!("not a function");
+    };
+    assert_eq!(
+        f.doc.to_utf8_string_lossy(),
+        "Requires the authorization of `from`."
+    );
+}
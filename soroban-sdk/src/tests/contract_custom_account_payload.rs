@@ -0,0 +1,151 @@
+use crate::{self as soroban_sdk, symbol_short};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    contract, contracterror, contractimpl,
+    crypto::Hash,
+    testutils::{CheckAuthPayload, MockAuthInvoke},
+    Address, BytesN, Env, IntoVal, Vec,
+};
+
+#[contract]
+pub struct Account;
+
+#[contracterror]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Error {
+    UnexpectedContext = 1,
+}
+
+#[contractimpl]
+impl Account {
+    pub fn __constructor(env: Env, public_key: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("pk"), &public_key);
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for Account {
+    type Signature = BytesN<64>;
+    type Error = Error;
+
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: Self::Signature,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), Self::Error> {
+        for context in auth_contexts.iter() {
+            match context {
+                Context::Contract(c) if c.fn_name == symbol_short!("transfer") => {}
+                _ => return Err(Error::UnexpectedContext),
+            }
+        }
+        let public_key: BytesN<32> = env.storage().instance().get(&symbol_short!("pk")).unwrap();
+        env.crypto()
+            .ed25519_verify(&public_key, &signature_payload.into(), &signature);
+        Ok(())
+    }
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn transfer(from: Address, amount: i128) -> i128 {
+        from.require_auth();
+        amount
+    }
+}
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[1; 32])
+}
+
+fn sign(e: &Env, payload: &CheckAuthPayload) -> BytesN<64> {
+    let signature = signing_key().sign(&payload.signature_payload().to_array());
+    BytesN::from_array(e, &signature.to_bytes())
+}
+
+#[test]
+fn test_check_auth_with_payload() {
+    let e = Env::default();
+    let public_key = BytesN::from_array(&e, &signing_key().verifying_key().to_bytes());
+    let account_id = e.register(Account, (public_key,));
+    let contract_id = e.register(Contract, ());
+
+    let payload = CheckAuthPayload::new(
+        &account_id,
+        &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "transfer",
+            args: (&account_id, 10_i128).into_val(&e),
+            sub_invokes: &[],
+        },
+    );
+    assert_eq!(payload.auth_contexts().len(), 1);
+
+    let signature = sign(&e, &payload);
+    assert_eq!(
+        payload.try_invoke_check_auth::<Error>(signature.into_val(&e)),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_check_auth_with_payload_unexpected_context() {
+    let e = Env::default();
+    let public_key = BytesN::from_array(&e, &signing_key().verifying_key().to_bytes());
+    let account_id = e.register(Account, (public_key,));
+    let contract_id = e.register(Contract, ());
+
+    let payload = CheckAuthPayload::new(
+        &account_id,
+        &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "transfer",
+            args: (&account_id, 10_i128).into_val(&e),
+            sub_invokes: &[MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "burn",
+                args: ().into_val(&e),
+                sub_invokes: &[],
+            }],
+        },
+    );
+    assert_eq!(payload.auth_contexts().len(), 2);
+
+    let signature = sign(&e, &payload);
+    assert_eq!(
+        payload.try_invoke_check_auth::<Error>(signature.into_val(&e)),
+        Err(Ok(Error::UnexpectedContext))
+    );
+}
+
+#[test]
+fn test_payload_matches_host_in_enforcing_mode() {
+    let e = Env::default();
+    let public_key = BytesN::from_array(&e, &signing_key().verifying_key().to_bytes());
+    let account_id = e.register(Account, (public_key,));
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let payload = CheckAuthPayload::new(
+        &account_id,
+        &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "transfer",
+            args: (&account_id, 10_i128).into_val(&e),
+            sub_invokes: &[],
+        },
+    );
+    let signature = sign(&e, &payload);
+
+    let amount = client
+        .set_auths(&[payload.to_auth_entry(signature)])
+        .transfer(&account_id, &10);
+    assert_eq!(amount, 10);
+}
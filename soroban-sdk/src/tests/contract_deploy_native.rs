@@ -0,0 +1,136 @@
+use crate::{self as soroban_sdk, symbol_short};
+use soroban_sdk::{contract, contractimpl, testutils::Deployer as _, Address, BytesN, Env};
+
+#[contract]
+pub struct Counter;
+
+#[contractimpl]
+impl Counter {
+    pub fn __constructor(env: Env, start: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("count"), &start);
+    }
+
+    pub fn increment(env: Env) -> u32 {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("count"))
+            .unwrap();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("count"), &(count + 1));
+        count + 1
+    }
+}
+
+#[contract]
+pub struct Factory;
+
+#[contractimpl]
+impl Factory {
+    pub fn deploy(env: Env, wasm_hash: BytesN<32>, salt: BytesN<32>, start: u32) -> Address {
+        env.deployer()
+            .with_current_contract(salt)
+            .deploy_v2(wasm_hash, (start,))
+    }
+
+    pub fn deployed_address(env: Env, salt: BytesN<32>) -> Address {
+        env.deployer()
+            .with_current_contract(salt)
+            .deployed_address()
+    }
+}
+
+#[test]
+fn test_deploy_native_contract_for_wasm_hash() {
+    let e = Env::default();
+    let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+    e.deployer()
+        .register_contract_for_wasm_hash(&wasm_hash, Counter);
+
+    let factory = FactoryClient::new(&e, &e.register(Factory, ()));
+    let salt = BytesN::from_array(&e, &[2; 32]);
+
+    let expected_address = factory.deployed_address(&salt);
+    let counter_address = factory.deploy(&wasm_hash, &salt, &5);
+    assert_eq!(counter_address, expected_address);
+
+    let counter = CounterClient::new(&e, &counter_address);
+    assert_eq!(counter.increment(), 6);
+    assert_eq!(counter.increment(), 7);
+}
+
+#[test]
+fn test_deploy_native_contract_for_wasm_hash_multiple_instances() {
+    let e = Env::default();
+    let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+    e.deployer()
+        .register_contract_for_wasm_hash(&wasm_hash, Counter);
+
+    let factory = FactoryClient::new(&e, &e.register(Factory, ()));
+
+    let counter_1 = factory.deploy(&wasm_hash, &BytesN::from_array(&e, &[1; 32]), &10);
+    let counter_2 = factory.deploy(&wasm_hash, &BytesN::from_array(&e, &[2; 32]), &20);
+    assert_ne!(counter_1, counter_2);
+
+    assert_eq!(CounterClient::new(&e, &counter_1).increment(), 11);
+    assert_eq!(CounterClient::new(&e, &counter_2).increment(), 21);
+}
+
+#[test]
+fn test_deploy_native_contract_for_wasm_hash_twice_fails() {
+    let e = Env::default();
+    let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+    e.deployer()
+        .register_contract_for_wasm_hash(&wasm_hash, Counter);
+
+    let factory = FactoryClient::new(&e, &e.register(Factory, ()));
+    let salt = BytesN::from_array(&e, &[2; 32]);
+
+    factory.deploy(&wasm_hash, &salt, &5);
+    assert!(factory.try_deploy(&wasm_hash, &salt, &5).is_err());
+}
+
+#[test]
+fn test_deploy_unregistered_wasm_hash_fails() {
+    let e = Env::default();
+    let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+    e.deployer()
+        .register_contract_for_wasm_hash(&wasm_hash, Counter);
+
+    let factory = FactoryClient::new(&e, &e.register(Factory, ()));
+    let salt = BytesN::from_array(&e, &[2; 32]);
+
+    // The Wasm for the hash was never uploaded, so deploying fails.
+    let other_wasm_hash = BytesN::from_array(&e, &[3; 32]);
+    assert!(factory.try_deploy(&other_wasm_hash, &salt, &5).is_err());
+}
+
+/// Test that the test snapshot file is written when the Env is dropped after
+/// native contracts have used the test state they share with the Env, to
+/// deploy and invoke other native contracts.
+#[test]
+fn test_deploy_native_contract_snapshot_file() {
+    let p = std::path::Path::new("test_snapshots")
+        .join("tests")
+        .join("contract_deploy_native")
+        .join("test_deploy_native_contract_snapshot_file");
+    let p1 = p.with_extension("1.json");
+    let _ = std::fs::remove_file(&p1);
+    {
+        let e = Env::default();
+        let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+        e.deployer()
+            .register_contract_for_wasm_hash(&wasm_hash, Counter);
+        let factory = FactoryClient::new(&e, &e.register(Factory, ()));
+        let salt = BytesN::from_array(&e, &[2; 32]);
+        let counter = CounterClient::new(&e, &factory.deploy(&wasm_hash, &salt, &5));
+        assert_eq!(counter.increment(), 6);
+        assert!(factory.try_deploy(&wasm_hash, &salt, &5).is_err());
+        assert!(!p1.exists());
+    } // Env dropped, written to p1.
+    assert!(p1.exists());
+    let _ = std::fs::remove_file(&p1);
+}
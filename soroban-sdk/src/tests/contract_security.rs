@@ -0,0 +1,87 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl,
+    security::{Pausable, ReentrancyGuard, SecurityError},
+    testutils::ReentrancyGuard as _,
+    Env,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{Limits, ReadXdr, ScSpecEntry};
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn pause(env: Env) {
+        Pausable::pause(&env);
+    }
+
+    pub fn unpause(env: Env) {
+        Pausable::unpause(&env);
+    }
+
+    /// Withdraws an amount.
+    #[nonreentrant]
+    #[when_not_paused]
+    pub fn withdraw(env: Env, amount: i128) -> i128 {
+        assert!(ReentrancyGuard::is_entered(&env));
+        amount
+    }
+
+    pub fn is_entered(env: Env) -> bool {
+        ReentrancyGuard::is_entered(&env)
+    }
+}
+
+#[test]
+fn test_nonreentrant() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.withdraw(&5), 5);
+    // The guard is released when the function returns.
+    assert!(!client.is_entered());
+    assert_eq!(client.withdraw(&6), 6);
+
+    let res = ReentrancyGuard::with_entered(&env, &contract_id, || client.try_withdraw(&5));
+    assert_eq!(res, Err(Ok(SecurityError::Reentrant.into())));
+
+    // Functions without the attribute are unaffected.
+    assert!(ReentrancyGuard::with_entered(&env, &contract_id, || {
+        client.is_entered()
+    }));
+    assert!(!client.is_entered());
+}
+
+#[test]
+fn test_when_not_paused() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    client.pause();
+    assert_eq!(
+        client.try_withdraw(&5),
+        Err(Ok(SecurityError::Paused.into()))
+    );
+    env.as_contract(&contract_id, || assert!(Pausable::is_paused(&env)));
+
+    client.unpause();
+    assert_eq!(client.withdraw(&5), 5);
+}
+
+#[test]
+fn test_spec_docs() {
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_WITHDRAW, Limits::none()).unwrap();
+    let ScSpecEntry::FunctionV0(f) = entry else {
+        panic!("not a function");
+    };
+    assert_eq!(
+        f.doc.to_utf8_string_lossy(),
+        "Withdraws an amount.\n\n\
+        Fails if called while a nonreentrant function of the contract is executing.\n\
+        Fails if called while the contract is paused."
+    );
+}
@@ -0,0 +1,231 @@
+use crate::{self as soroban_sdk, symbol_short};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    auth::{Context, CustomAccountInterface},
+    contract, contracterror, contractimpl,
+    crypto::Hash,
+    testutils::{secp256r1, Address as _, AuthSigner, CustomAccount, Ed25519Account},
+    Address, BytesN, Env, Vec,
+};
+
+#[contract]
+pub struct Account;
+
+#[contracterror]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Error {
+    UnexpectedContext = 1,
+}
+
+#[contractimpl]
+impl Account {
+    pub fn __constructor(env: Env, public_key: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("pk"), &public_key);
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for Account {
+    type Signature = BytesN<64>;
+    type Error = Error;
+
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: Self::Signature,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), Self::Error> {
+        for context in auth_contexts.iter() {
+            match context {
+                Context::Contract(c)
+                    if c.fn_name == symbol_short!("transfer")
+                        || c.fn_name == symbol_short!("burn") => {}
+                _ => return Err(Error::UnexpectedContext),
+            }
+        }
+        let public_key: BytesN<32> = env.storage().instance().get(&symbol_short!("pk")).unwrap();
+        env.crypto()
+            .ed25519_verify(&public_key, &signature_payload.into(), &signature);
+        Ok(())
+    }
+}
+
+mod p256_account {
+    use crate as soroban_sdk;
+    use soroban_sdk::{
+        auth::Context, contract, contractimpl, crypto::Hash, symbol_short, BytesN, Env, Vec,
+    };
+
+    #[contract]
+    pub struct P256Account;
+
+    #[contractimpl]
+    impl P256Account {
+        pub fn __constructor(env: Env, public_key: BytesN<65>) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("pk"), &public_key);
+        }
+
+        #[allow(non_snake_case)]
+        pub fn __check_auth(
+            env: Env,
+            signature_payload: Hash<32>,
+            signature: BytesN<64>,
+            _auth_contexts: Vec<Context>,
+        ) {
+            let public_key: BytesN<65> =
+                env.storage().instance().get(&symbol_short!("pk")).unwrap();
+            env.crypto()
+                .secp256r1_verify(&public_key, &signature_payload, &signature);
+        }
+    }
+}
+use p256_account::P256Account;
+
+#[contract]
+pub struct Token;
+
+#[contractimpl]
+impl Token {
+    pub fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let burned: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("burned"))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("burned"), &(burned + amount));
+    }
+
+    pub fn burned(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("burned"))
+            .unwrap_or(0)
+    }
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn transfer(env: Env, token: Address, from: Address, amount: i128) {
+        from.require_auth();
+        TokenClient::new(&env, &token).burn(&from, &amount);
+    }
+}
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[1; 32])
+}
+
+#[test]
+fn test_sign_with_ed25519_account() {
+    let e = Env::default();
+    let token_id = e.register(Token, ());
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let account = Ed25519Account::new(&e, signing_key());
+    client
+        .sign_with(&[&account])
+        .transfer(&token_id, &account.address(), &10);
+
+    // The invocation ran once, the recording of its auths had no effect.
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 10);
+}
+
+#[test]
+fn test_sign_with_custom_account() {
+    let e = Env::default();
+    let public_key = BytesN::from_array(&e, &signing_key().verifying_key().to_bytes());
+    let account_id = e.register(Account, (public_key,));
+    let token_id = e.register(Token, ());
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let account = CustomAccount::new(&account_id, |payload| {
+        assert_eq!(payload.auth_contexts().len(), 2);
+        let signature = signing_key().sign(&payload.signature_payload().to_array());
+        BytesN::<64>::from_array(&e, &signature.to_bytes())
+    });
+    client
+        .sign_with(&[&account])
+        .transfer(&token_id, &account_id, &10);
+    client
+        .sign_with(&[&account])
+        .transfer(&token_id, &account_id, &5);
+
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 15);
+}
+
+#[test]
+fn test_sign_with_wrong_key_fails() {
+    let e = Env::default();
+    let public_key = BytesN::from_array(&e, &signing_key().verifying_key().to_bytes());
+    let account_id = e.register(Account, (public_key,));
+    let token_id = e.register(Token, ());
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let account = CustomAccount::new(&account_id, |payload| {
+        let signature =
+            SigningKey::from_bytes(&[2; 32]).sign(&payload.signature_payload().to_array());
+        BytesN::<64>::from_array(&e, &signature.to_bytes())
+    });
+    assert!(client
+        .sign_with(&[&account])
+        .try_transfer(&token_id, &account_id, &10)
+        .is_err());
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 0);
+}
+
+#[test]
+fn test_sign_with_missing_signer_fails() {
+    let e = Env::default();
+    let token_id = e.register(Token, ());
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let account = Ed25519Account::new(&e, signing_key());
+    let other = Address::generate(&e);
+    assert!(client
+        .sign_with(&[&account])
+        .try_transfer(&token_id, &other, &10)
+        .is_err());
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 0);
+}
+
+#[test]
+fn test_sign_with_secp256r1_custom_account() {
+    let e = Env::default();
+    let signing_key = p256::ecdsa::SigningKey::from_slice(&[1; 32]).unwrap();
+    let public_key = secp256r1::public_key(&e, signing_key.verifying_key());
+    let account_id = e.register(P256Account, (public_key,));
+    let token_id = e.register(Token, ());
+    let contract_id = e.register(Contract, ());
+    let client = ContractClient::new(&e, &contract_id);
+
+    let account = CustomAccount::with_key(&account_id, signing_key);
+    client
+        .sign_with(&[&account])
+        .transfer(&token_id, &account_id, &10);
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 10);
+
+    // A signature of another key does not verify.
+    let other = CustomAccount::with_key(
+        &account_id,
+        p256::ecdsa::SigningKey::from_slice(&[2; 32]).unwrap(),
+    );
+    assert!(client
+        .sign_with(&[&other])
+        .try_transfer(&token_id, &account_id, &10)
+        .is_err());
+    assert_eq!(TokenClient::new(&e, &token_id).burned(), 10);
+}
@@ -0,0 +1,111 @@
+use crate as soroban_sdk;
+use soroban_sdk::{contract, contractimpl, Env};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{Limits, ReadXdr, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef};
+
+mod counter {
+    use crate as soroban_sdk;
+    use soroban_sdk::{contracttrait, symbol_short, Env};
+
+    #[contracttrait]
+    pub trait Counter {
+        /// Increments the count.
+        fn increment(env: Env) -> u32 {
+            let count = Self::count(env.clone()) + Self::step();
+            env.storage()
+                .instance()
+                .set(&symbol_short!("count"), &count);
+            count
+        }
+
+        /// Returns the count.
+        fn count(env: Env) -> u32 {
+            env.storage()
+                .instance()
+                .get(&symbol_short!("count"))
+                .unwrap_or(0)
+        }
+
+        fn step() -> u32;
+    }
+}
+
+// A contract trait with the same name as the trait above, in another module.
+mod other {
+    use crate as soroban_sdk;
+    use soroban_sdk::{contract, contractimpl, contracttrait, Env};
+
+    #[contracttrait]
+    pub trait Counter {
+        fn count(env: Env) -> u32 {
+            let _ = env;
+            7
+        }
+    }
+
+    #[contract]
+    pub struct OtherContract;
+
+    #[contractimpl(contracttrait)]
+    impl Counter for OtherContract {}
+}
+
+use counter::Counter;
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl(contracttrait)]
+impl Counter for Contract {
+    fn step() -> u32 {
+        2
+    }
+
+    fn count(env: Env) -> u32 {
+        let _ = env;
+        10
+    }
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    // The default function is exported, and calls the overriding functions.
+    assert_eq!(client.increment(), 12);
+    // The overriding function is exported as written in the implementation.
+    assert_eq!(client.count(), 10);
+    assert_eq!(client.step(), 2);
+}
+
+#[test]
+fn test_same_name_in_other_module() {
+    let env = Env::default();
+    let contract_id = env.register(other::OtherContract, ());
+    let client = other::OtherContractClient::new(&env, &contract_id);
+    assert_eq!(client.count(), 7);
+}
+
+#[test]
+fn test_spec() {
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_INCREMENT, Limits::none()).unwrap();
+    let expect = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: "Increments the count.".try_into().unwrap(),
+        name: "increment".try_into().unwrap(),
+        inputs: [].try_into().unwrap(),
+        outputs: [ScSpecTypeDef::U32].try_into().unwrap(),
+    });
+    assert_eq!(entry, expect);
+
+    // The spec of an overriding function comes from the implementation.
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_COUNT, Limits::none()).unwrap();
+    let expect = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: "".try_into().unwrap(),
+        name: "count".try_into().unwrap(),
+        inputs: [].try_into().unwrap(),
+        outputs: [ScSpecTypeDef::U32].try_into().unwrap(),
+    });
+    assert_eq!(entry, expect);
+}
@@ -0,0 +1,134 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec, Env, IntoVal, Map, Symbol, TryFromVal,
+    Val, Vec,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecTypeDef, ScSpecTypeUdt, ScSpecUdtStructFieldV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+    ScSpecUdtUnionV0,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    Noop,
+    Transfer { to: u32, amount: i128 },
+    Burn(i128),
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn exec(a: Action) -> Action {
+        a
+    }
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    let a = Action::Transfer { to: 1, amount: 2 };
+    assert_eq!(client.exec(&a), a);
+    assert_eq!(client.exec(&Action::Noop), Action::Noop);
+    assert_eq!(client.exec(&Action::Burn(3)), Action::Burn(3));
+}
+
+#[test]
+fn test_encoding() {
+    let env = Env::default();
+
+    // A variant with named fields is stored as the name of the variant followed
+    // by a map of the fields, the same as a struct.
+    let a = Action::Transfer { to: 1, amount: 2 };
+    let val: Val = a.clone().into_val(&env);
+    let vec = Vec::<Val>::try_from_val(&env, &val).unwrap();
+    assert_eq!(vec.len(), 2);
+    assert_eq!(
+        Symbol::try_from_val(&env, &vec.get_unchecked(0)).unwrap(),
+        symbol_short!("Transfer")
+    );
+    let fields = Map::<Symbol, Val>::try_from_val(&env, &vec.get_unchecked(1)).unwrap();
+    assert_eq!(
+        fields.keys(),
+        vec![&env, symbol_short!("amount"), symbol_short!("to")]
+    );
+    assert_eq!(Action::try_from_val(&env, &val), Ok(a));
+
+    // Values following the map fail to convert.
+    let val: Val = (symbol_short!("Transfer"), fields, 4u32).into_val(&env);
+    assert!(Action::try_from_val(&env, &val).is_err());
+}
+
+#[test]
+fn test_xdr() {
+    let env = Env::default();
+    let a = Action::Transfer { to: 1, amount: 2 };
+    let scval: stellar_xdr::ScVal = (&a).try_into().unwrap();
+    assert_eq!(Action::try_from_val(&env, &scval), Ok(a.clone()));
+
+    let val: Val = a.into_val(&env);
+    let from_val = stellar_xdr::ScVal::try_from_val(&env, &val).unwrap();
+    assert_eq!(from_val, scval);
+}
+
+#[test]
+fn test_spec() {
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_ACTION, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Action".try_into().unwrap(),
+        cases: std::vec![
+            ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                doc: "".try_into().unwrap(),
+                name: "Noop".try_into().unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: "Transfer".try_into().unwrap(),
+                type_: std::vec![ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                    name: "ActionTransfer".try_into().unwrap(),
+                })]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: "Burn".try_into().unwrap(),
+                type_: std::vec![ScSpecTypeDef::I128].try_into().unwrap(),
+            }),
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entry, expect);
+
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_ACTIONTRANSFER, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "@soroban_enum_variant_fields".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "ActionTransfer".try_into().unwrap(),
+        fields: std::vec![
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "amount".try_into().unwrap(),
+                type_: ScSpecTypeDef::I128,
+            },
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "to".try_into().unwrap(),
+                type_: ScSpecTypeDef::U32,
+            },
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entry, expect);
+}
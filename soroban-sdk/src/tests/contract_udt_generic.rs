@@ -0,0 +1,210 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, spec_generic, testutils::Address as _, vec, Address, Env,
+    IntoVal, TryFromVal, Val, Vec,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef,
+    ScSpecTypeOption, ScSpecTypeUdt, ScSpecTypeVec, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+    ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0,
+    StringM, WriteXdr,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Page<T: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone> {
+    pub items: Vec<T>,
+    pub next: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Checkpoint<V> {
+    Empty,
+    Value(u32, V),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wrapper<T>(pub T);
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn page(items: Vec<Address>) -> Page<Address> {
+        Page { items, next: None }
+    }
+
+    pub fn checkpoint(c: Checkpoint<i128>) -> Checkpoint<i128> {
+        c
+    }
+
+    pub fn nested(p: Page<Checkpoint<u32>>) -> Wrapper<Page<Checkpoint<u32>>> {
+        Wrapper(p)
+    }
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    assert_eq!(
+        client.page(&vec![&env, a.clone(), b.clone()]),
+        Page {
+            items: vec![&env, a, b],
+            next: None
+        }
+    );
+
+    assert_eq!(client.checkpoint(&Checkpoint::Empty), Checkpoint::Empty);
+    assert_eq!(
+        client.checkpoint(&Checkpoint::Value(1, -5)),
+        Checkpoint::Value(1, -5)
+    );
+
+    let p = Page {
+        items: vec![&env, Checkpoint::Value(2, 3), Checkpoint::Empty],
+        next: Some(4),
+    };
+    assert_eq!(client.nested(&p), Wrapper(p));
+}
+
+#[test]
+fn test_conversions() {
+    let env = Env::default();
+    let page = Page {
+        items: vec![&env, 1u32, 2u32],
+        next: Some(3),
+    };
+    let val: Val = page.clone().into_val(&env);
+    assert_eq!(Page::<u32>::try_from_val(&env, &val), Ok(page));
+
+    let checkpoint = Checkpoint::Value(1, 2u32);
+    let val: Val = checkpoint.clone().into_val(&env);
+    assert_eq!(Checkpoint::<u32>::try_from_val(&env, &val), Ok(checkpoint));
+    // A value of one instantiation does not convert into another.
+    assert!(Checkpoint::<Address>::try_from_val(&env, &val).is_err());
+}
+
+fn instance_spec(template: &[&[u8]], holes: &[usize], args: &[&[u8]]) -> ScSpecEntry {
+    let len = spec_generic::instance_len(template, holes, args);
+    // Use a buffer large enough for the instances in these tests.
+    assert!(len <= 512);
+    let buf: [u8; 512] = {
+        let mut buf = [0u8; 512];
+        let mut i = 0;
+        for (t, h) in template.iter().zip(holes.iter().map(Some).chain([None])) {
+            buf[i..i + t.len()].copy_from_slice(t);
+            i += t.len();
+            if let Some(h) = h {
+                buf[i..i + args[*h].len()].copy_from_slice(args[*h]);
+                i += args[*h].len();
+            }
+        }
+        buf
+    };
+    ScSpecEntry::from_xdr(&buf[..len], Limits::none()).unwrap()
+}
+
+fn udt(name: &str) -> ScSpecTypeDef {
+    ScSpecTypeDef::Udt(ScSpecTypeUdt {
+        name: name.try_into().unwrap(),
+    })
+}
+
+#[test]
+fn test_spec_fn() {
+    let entry = ScSpecEntry::from_xdr(__SPEC_XDR_FN_NESTED, Limits::none()).unwrap();
+    let expect = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: "".try_into().unwrap(),
+        name: "nested".try_into().unwrap(),
+        inputs: std::vec![ScSpecFunctionInputV0 {
+            doc: "".try_into().unwrap(),
+            name: "p".try_into().unwrap(),
+            type_: udt("Page_Checkpoint_u32"),
+        }]
+        .try_into()
+        .unwrap(),
+        outputs: std::vec![udt("Wrapper_Page_Checkpoint_u32")]
+            .try_into()
+            .unwrap(),
+    });
+    assert_eq!(entry, expect);
+}
+
+#[test]
+fn test_spec_instance() {
+    let name = StringM::<60>::try_from("Page_Address")
+        .unwrap()
+        .to_xdr(Limits::none())
+        .unwrap();
+    let arg = ScSpecTypeDef::Address.to_xdr(Limits::none()).unwrap();
+    let entry = instance_spec(
+        Page::<Address>::SPEC_XDR_TEMPLATE,
+        Page::<Address>::SPEC_XDR_TEMPLATE_HOLES,
+        &[&name, &arg],
+    );
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Page_Address".try_into().unwrap(),
+        fields: std::vec![
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "items".try_into().unwrap(),
+                type_: ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                    element_type: Box::new(ScSpecTypeDef::Address),
+                })),
+            },
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "next".try_into().unwrap(),
+                type_: ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                    value_type: Box::new(ScSpecTypeDef::U32),
+                })),
+            },
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entry, expect);
+
+    let name = StringM::<60>::try_from("Checkpoint_i128")
+        .unwrap()
+        .to_xdr(Limits::none())
+        .unwrap();
+    let arg = ScSpecTypeDef::I128.to_xdr(Limits::none()).unwrap();
+    let entry = instance_spec(
+        Checkpoint::<i128>::SPEC_XDR_TEMPLATE,
+        Checkpoint::<i128>::SPEC_XDR_TEMPLATE_HOLES,
+        &[&name, &arg],
+    );
+    let expect = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Checkpoint_i128".try_into().unwrap(),
+        cases: std::vec![
+            ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                doc: "".try_into().unwrap(),
+                name: "Empty".try_into().unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: "Value".try_into().unwrap(),
+                type_: std::vec![ScSpecTypeDef::U32, ScSpecTypeDef::I128]
+                    .try_into()
+                    .unwrap(),
+            }),
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entry, expect);
+}
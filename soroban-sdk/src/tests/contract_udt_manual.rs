@@ -0,0 +1,81 @@
+use crate as soroban_sdk;
+use soroban_sdk::{contract, contractimpl, ConversionError, Env, IntoVal, TryFromVal, Val};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef,
+    ScSpecTypeOption, ScSpecTypeUdt,
+};
+
+// A type that implements the conversions to and from Val manually, and is not
+// a contract type.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Celsius(u32);
+
+impl TryFromVal<Env, Val> for Celsius {
+    type Error = ConversionError;
+
+    fn try_from_val(env: &Env, v: &Val) -> Result<Self, Self::Error> {
+        Ok(Celsius(u32::try_from_val(env, v)?))
+    }
+}
+
+impl TryFromVal<Env, Celsius> for Val {
+    type Error = ConversionError;
+
+    fn try_from_val(env: &Env, v: &Celsius) -> Result<Self, Self::Error> {
+        Ok(v.0.into_val(env))
+    }
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn warmer(temp: Celsius, by: Option<Celsius>) -> Celsius {
+        Celsius(temp.0 + by.map_or(1, |b| b.0))
+    }
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    assert_eq!(client.warmer(&Celsius(20), &Some(Celsius(2))), Celsius(22));
+    assert_eq!(client.warmer(&Celsius(20), &None), Celsius(21));
+}
+
+#[test]
+fn test_spec() {
+    let celsius = || {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: "Celsius".try_into().unwrap(),
+        })
+    };
+
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_FN_WARMER, Limits::none()).unwrap();
+    let expect = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: "".try_into().unwrap(),
+        name: "warmer".try_into().unwrap(),
+        inputs: std::vec![
+            ScSpecFunctionInputV0 {
+                doc: "".try_into().unwrap(),
+                name: "temp".try_into().unwrap(),
+                type_: celsius(),
+            },
+            ScSpecFunctionInputV0 {
+                doc: "".try_into().unwrap(),
+                name: "by".try_into().unwrap(),
+                type_: ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                    value_type: Box::new(celsius()),
+                })),
+            },
+        ]
+        .try_into()
+        .unwrap(),
+        outputs: std::vec![celsius()].try_into().unwrap(),
+    });
+    assert_eq!(entries, expect);
+}
@@ -0,0 +1,202 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, map, symbol_short, ConversionError, Env, IntoVal, Map,
+    Symbol, TryFromVal, Val,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecTypeDef, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountV1 {
+    pub balance: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountV2 {
+    pub balance: i128,
+    #[contracttype(default)]
+    pub frozen: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Account {
+    #[contracttype(rename = "bal")]
+    pub balance: i128,
+    #[contracttype(rename = "lim", default)]
+    pub limit: Option<i128>,
+    #[contracttype(skip)]
+    pub cached: u32,
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn set_v1(env: Env, account: AccountV1) {
+        env.storage()
+            .persistent()
+            .set(&symbol_short!("account"), &account);
+    }
+
+    pub fn get_v2(env: Env) -> AccountV2 {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("account"))
+            .unwrap()
+    }
+
+    pub fn account(account: Account) -> Account {
+        account
+    }
+}
+
+#[test]
+fn test_rename_and_skip() {
+    let env = Env::default();
+    let account = Account {
+        balance: 5,
+        limit: Some(10),
+        cached: 3,
+    };
+    let val: Val = account.into_val(&env);
+    let m = Map::<Symbol, Val>::try_from_val(&env, &val).unwrap();
+    assert_eq!(
+        m.keys(),
+        soroban_sdk::vec![&env, symbol_short!("bal"), symbol_short!("lim")]
+    );
+
+    let account = Account::try_from_val(&env, &val).unwrap();
+    assert_eq!(
+        account,
+        Account {
+            balance: 5,
+            limit: Some(10),
+            cached: 0,
+        }
+    );
+}
+
+#[test]
+fn test_default() {
+    let env = Env::default();
+
+    let val: Val = map![&env, (symbol_short!("balance"), 5i128)].into_val(&env);
+    assert_eq!(
+        AccountV2::try_from_val(&env, &val),
+        Ok(AccountV2 {
+            balance: 5,
+            frozen: false,
+        })
+    );
+
+    let m: Map<Symbol, Val> = map![
+        &env,
+        (symbol_short!("balance"), 5i128.into_val(&env)),
+        (symbol_short!("frozen"), true.into_val(&env)),
+    ];
+    let val: Val = m.into_val(&env);
+    assert_eq!(
+        AccountV2::try_from_val(&env, &val),
+        Ok(AccountV2 {
+            balance: 5,
+            frozen: true,
+        })
+    );
+
+    // Fields that are not defaulted are required.
+    let val: Val = map![&env, (symbol_short!("frozen"), true)].into_val(&env);
+    assert_eq!(AccountV2::try_from_val(&env, &val), Err(ConversionError));
+
+    // Unknown fields are an error.
+    let m: Map<Symbol, Val> = map![
+        &env,
+        (symbol_short!("balance"), 5i128.into_val(&env)),
+        (symbol_short!("other"), true.into_val(&env)),
+    ];
+    let val: Val = m.into_val(&env);
+    assert_eq!(AccountV2::try_from_val(&env, &val), Err(ConversionError));
+}
+
+#[test]
+fn test_default_xdr() {
+    let env = Env::default();
+    let account = AccountV2 {
+        balance: 5,
+        frozen: true,
+    };
+    let scval: stellar_xdr::ScVal = (&account).try_into().unwrap();
+    assert_eq!(AccountV2::try_from_val(&env, &scval), Ok(account));
+
+    let scval: stellar_xdr::ScVal = (&AccountV1 { balance: 5 }).try_into().unwrap();
+    assert_eq!(
+        AccountV2::try_from_val(&env, &scval),
+        Ok(AccountV2 {
+            balance: 5,
+            frozen: false,
+        })
+    );
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    // Values stored before a field with a default was added can be read.
+    client.set_v1(&AccountV1 { balance: 7 });
+    assert_eq!(
+        client.get_v2(),
+        AccountV2 {
+            balance: 7,
+            frozen: false,
+        }
+    );
+
+    let account = Account {
+        balance: 1,
+        limit: None,
+        cached: 9,
+    };
+    assert_eq!(
+        client.account(&account),
+        Account {
+            balance: 1,
+            limit: None,
+            cached: 0,
+        }
+    );
+}
+
+#[test]
+fn test_spec() {
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_ACCOUNT, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Account".try_into().unwrap(),
+        fields: std::vec![
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "bal".try_into().unwrap(),
+                type_: ScSpecTypeDef::I128,
+            },
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "lim".try_into().unwrap(),
+                type_: ScSpecTypeDef::Option(Box::new(stellar_xdr::ScSpecTypeOption {
+                    value_type: Box::new(ScSpecTypeDef::I128),
+                })),
+            },
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entries, expect);
+}
@@ -0,0 +1,122 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, testutils::Address as _, Address, Env, IntoVal,
+    TryFromVal, TryIntoVal, Val, Vec,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef,
+    ScSpecTypeOption, ScSpecTypeUdt, ScSpecTypeVec, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[contracttype(transparent)]
+pub struct UserId(pub u64);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype(transparent)]
+pub struct Users(pub Vec<UserId>);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct User {
+    pub id: UserId,
+    pub owner: Owner,
+}
+
+pub type Owner = Address;
+
+pub type MaybeUser = Option<User>;
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn first(users: Users, owner: Owner) -> MaybeUser {
+        users.0.first().map(|id| User { id, owner })
+    }
+}
+
+#[test]
+fn test_conversion() {
+    let env = Env::default();
+    let id = UserId(5);
+    let val: Val = id.into_val(&env);
+    let inner: u64 = val.try_into_val(&env).unwrap();
+    assert_eq!(inner, 5);
+    assert_eq!(UserId::try_from_val(&env, &val), Ok(id));
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let users = Users(Vec::from_array(&env, [UserId(3), UserId(4)]));
+    assert_eq!(
+        client.first(&users, &owner),
+        Some(User {
+            id: UserId(3),
+            owner: owner.clone(),
+        })
+    );
+    assert_eq!(client.first(&Users(Vec::new(&env)), &owner), None);
+}
+
+#[test]
+fn test_spec() {
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_FN_FIRST, Limits::none()).unwrap();
+    let expect = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: "".try_into().unwrap(),
+        name: "first".try_into().unwrap(),
+        inputs: std::vec![
+            ScSpecFunctionInputV0 {
+                doc: "".try_into().unwrap(),
+                name: "users".try_into().unwrap(),
+                type_: ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                    element_type: Box::new(ScSpecTypeDef::U64),
+                })),
+            },
+            ScSpecFunctionInputV0 {
+                doc: "".try_into().unwrap(),
+                name: "owner".try_into().unwrap(),
+                type_: ScSpecTypeDef::Address,
+            },
+        ]
+        .try_into()
+        .unwrap(),
+        outputs: std::vec![ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+            value_type: Box::new(ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                name: "User".try_into().unwrap(),
+            })),
+        }))]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entries, expect);
+
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_USER, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "User".try_into().unwrap(),
+        fields: std::vec![
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "id".try_into().unwrap(),
+                type_: ScSpecTypeDef::U64,
+            },
+            ScSpecUdtStructFieldV0 {
+                doc: "".try_into().unwrap(),
+                name: "owner".try_into().unwrap(),
+                type_: ScSpecTypeDef::Address,
+            },
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entries, expect);
+}
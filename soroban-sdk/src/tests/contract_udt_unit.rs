@@ -0,0 +1,218 @@
+use crate as soroban_sdk;
+use soroban_sdk::{
+    contract, contractimpl, contracttype, map, symbol_short, vec, ConversionError, Env, IntoVal,
+    Map, Symbol, TryFromVal, Val, Vec,
+};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Limits, ReadXdr, ScSpecEntry, ScSpecTypeDef, ScSpecTypeUdt, ScSpecUdtStructV0,
+    ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0,
+    ScVal,
+};
+
+mod udt {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/test_udt.wasm");
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Marker;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Empty {}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EmptyTuple();
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Kind {
+    Unit,
+    None(),
+    Empty {},
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn exec(marker: Marker, kind: Kind) -> (Marker, Kind) {
+        (marker, kind)
+    }
+}
+
+#[test]
+fn test_conversion() {
+    let env = Env::default();
+
+    let val: Val = Marker.into_val(&env);
+    assert!(val.is_void());
+    assert_eq!(Marker::try_from_val(&env, &val), Ok(Marker));
+    let val: Val = 1u32.into_val(&env);
+    assert_eq!(Marker::try_from_val(&env, &val), Err(ConversionError));
+
+    let scval: ScVal = Marker.try_into().unwrap();
+    assert_eq!(scval, ScVal::Void);
+    assert_eq!(Marker::try_from_val(&env, &scval), Ok(Marker));
+
+    // Empty tuple variants are stored the same as unit variants.
+    let val: Val = Kind::None().into_val(&env);
+    let v = Vec::<Val>::try_from_val(&env, &val).unwrap();
+    assert_eq!(v, vec![&env, symbol_short!("None").into_val(&env)]);
+    assert_eq!(Kind::try_from_val(&env, &val), Ok(Kind::None()));
+
+    let scval: ScVal = Kind::None().try_into().unwrap();
+    assert_eq!(Kind::try_from_val(&env, &scval), Ok(Kind::None()));
+
+    // Empty variants with braces are stored as the name of the variant
+    // followed by an empty map.
+    let val: Val = Kind::Empty {}.into_val(&env);
+    let v = Vec::<Val>::try_from_val(&env, &val).unwrap();
+    assert_eq!(v.len(), 2);
+    assert_eq!(
+        Map::<Symbol, Val>::try_from_val(&env, &v.get_unchecked(1)),
+        Ok(map![&env])
+    );
+    assert_eq!(Kind::try_from_val(&env, &val), Ok(Kind::Empty {}));
+
+    let scval: ScVal = Kind::Empty {}.try_into().unwrap();
+    assert_eq!(Kind::try_from_val(&env, &scval), Ok(Kind::Empty {}));
+
+    // Structs with empty braces and parentheses are stored as an empty map and
+    // vec.
+    let val: Val = Empty {}.into_val(&env);
+    assert_eq!(Map::<Symbol, Val>::try_from_val(&env, &val), Ok(map![&env]));
+    assert_eq!(Empty::try_from_val(&env, &val), Ok(Empty {}));
+    let val: Val = EmptyTuple().into_val(&env);
+    assert_eq!(Vec::<Val>::try_from_val(&env, &val), Ok(vec![&env]));
+    assert_eq!(EmptyTuple::try_from_val(&env, &val), Ok(EmptyTuple()));
+}
+
+#[test]
+fn test_functional() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+    assert_eq!(client.exec(&Marker, &Kind::None()), (Marker, Kind::None()));
+    assert_eq!(client.exec(&Marker, &Kind::Unit), (Marker, Kind::Unit));
+}
+
+#[test]
+fn test_contractimport() {
+    let env = Env::default();
+    let contract_id = env.register(udt::WASM, ());
+    let client = udt::Client::new(&env, &contract_id);
+    assert_eq!(client.add(&udt::UdtEnum::UdtF(), &udt::UdtEnum::UdtA), 0);
+
+    let val: Val = udt::UdtUnit.into_val(&env);
+    assert!(val.is_void());
+}
+
+#[test]
+fn test_contractimport_roundtrip() {
+    // The unit struct regenerated from the spec by contractimport is stored
+    // the same as the unit struct it was exported from.
+    let env = Env::default();
+    let val: Val = Marker.into_val(&env);
+    let imported = udt::UdtUnit::try_from_val(&env, &val).unwrap();
+    let val: Val = imported.into_val(&env);
+    assert_eq!(Marker::try_from_val(&env, &val), Ok(Marker));
+
+    let scval: ScVal = Marker.try_into().unwrap();
+    let imported = udt::UdtUnit::try_from_val(&env, &scval).unwrap();
+    assert_eq!(ScVal::try_from(imported), Ok(scval));
+}
+
+#[test]
+fn test_contractimport_roundtrip_empty() {
+    // Structs with empty braces and parentheses regenerated from the spec by
+    // contractimport are stored the same as the structs they were exported
+    // from.
+    let env = Env::default();
+    let val: Val = Empty {}.into_val(&env);
+    let imported = udt::UdtEmpty::try_from_val(&env, &val).unwrap();
+    assert_eq!(imported, udt::UdtEmpty {});
+    let val: Val = imported.into_val(&env);
+    assert_eq!(Empty::try_from_val(&env, &val), Ok(Empty {}));
+    assert!(udt::UdtEmptyTuple::try_from_val(&env, &val).is_err());
+
+    let val: Val = EmptyTuple().into_val(&env);
+    let imported = udt::UdtEmptyTuple::try_from_val(&env, &val).unwrap();
+    assert_eq!(imported, udt::UdtEmptyTuple());
+    let val: Val = imported.into_val(&env);
+    assert_eq!(EmptyTuple::try_from_val(&env, &val), Ok(EmptyTuple()));
+    assert!(udt::UdtEmpty::try_from_val(&env, &val).is_err());
+
+    let scval: ScVal = Empty {}.try_into().unwrap();
+    let imported = udt::UdtEmpty::try_from_val(&env, &scval).unwrap();
+    assert_eq!(ScVal::try_from(imported), Ok(scval));
+    let scval: ScVal = EmptyTuple().try_into().unwrap();
+    let imported = udt::UdtEmptyTuple::try_from_val(&env, &scval).unwrap();
+    assert_eq!(ScVal::try_from(imported), Ok(scval));
+}
+
+#[test]
+fn test_spec() {
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_MARKER, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "@soroban_unit_struct".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Marker".try_into().unwrap(),
+        fields: [].try_into().unwrap(),
+    });
+    assert_eq!(entries, expect);
+
+    // Structs with empty braces have no marker, and tuple structs with no
+    // fields are marked.
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_EMPTY, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Empty".try_into().unwrap(),
+        fields: [].try_into().unwrap(),
+    });
+    assert_eq!(entries, expect);
+
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_EMPTYTUPLE, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: "@soroban_tuple_struct".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "EmptyTuple".try_into().unwrap(),
+        fields: [].try_into().unwrap(),
+    });
+    assert_eq!(entries, expect);
+
+    let entries = ScSpecEntry::from_xdr(__SPEC_XDR_TYPE_KIND, Limits::none()).unwrap();
+    let expect = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+        doc: "".try_into().unwrap(),
+        lib: "".try_into().unwrap(),
+        name: "Kind".try_into().unwrap(),
+        cases: std::vec![
+            ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                doc: "".try_into().unwrap(),
+                name: "Unit".try_into().unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: "None".try_into().unwrap(),
+                type_: [].try_into().unwrap(),
+            }),
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: "Empty".try_into().unwrap(),
+                type_: [ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                    name: "KindEmpty".try_into().unwrap(),
+                })]
+                .try_into()
+                .unwrap(),
+            }),
+        ]
+        .try_into()
+        .unwrap(),
+    });
+    assert_eq!(entries, expect);
+}
@@ -0,0 +1,150 @@
+use crate::{self as soroban_sdk, symbol_short};
+use soroban_sdk::{
+    contract, contractimpl, contractupgradeable,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    upgrade::{self, Upgradeable},
+    Address, Env, IntoVal, Vec,
+};
+
+mod addcontract {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/test_add_u64.wasm");
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+    }
+
+    pub fn steps(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("steps"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+}
+
+#[contractupgradeable]
+impl Upgradeable for Contract {
+    const VERSION: u32 = 3;
+
+    fn authorize_upgrade(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+        admin.require_auth();
+    }
+
+    fn migrate(env: &Env, from_version: u32) {
+        let mut steps = Self::steps(env.clone());
+        steps.push_back(from_version);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("steps"), &steps);
+    }
+}
+
+#[test]
+fn test_migrate_runs_each_step_once() {
+    let e = Env::default();
+    let contract_id = e.register(Contract, (Address::generate(&e),));
+    let client = ContractClient::new(&e, &contract_id);
+
+    // The constructor does not initialize the version, so the data is at
+    // version 1.
+    assert_eq!(client.version(), 1);
+
+    assert_eq!(client.migrate(), 3);
+    assert_eq!(client.version(), 3);
+    assert_eq!(client.steps(), Vec::from_array(&e, [1, 2]));
+
+    assert_eq!(client.migrate(), 3);
+    assert_eq!(client.steps(), Vec::from_array(&e, [1, 2]));
+}
+
+#[test]
+fn test_init_skips_migrations() {
+    let e = Env::default();
+    let contract_id = e.register(Contract, (Address::generate(&e),));
+    let client = ContractClient::new(&e, &contract_id);
+
+    e.as_contract(&contract_id, || upgrade::init::<Contract>(&e));
+
+    assert_eq!(client.version(), 3);
+    assert_eq!(client.migrate(), 3);
+    assert_eq!(client.steps(), Vec::new(&e));
+}
+
+#[test]
+fn test_migrate_from_newer_version_fails() {
+    let e = Env::default();
+    let contract_id = e.register(Contract, (Address::generate(&e),));
+    let client = ContractClient::new(&e, &contract_id);
+
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("__version"), &4_u32)
+    });
+
+    assert!(client.try_migrate().is_err());
+    assert_eq!(client.version(), 4);
+}
+
+#[test]
+fn test_upgrade_requires_authorization() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let contract_id = e.register(Contract, (admin,));
+    let client = ContractClient::new(&e, &contract_id);
+
+    let wasm_hash = e.deployer().upload_contract_wasm(addcontract::WASM);
+    assert!(client.try_upgrade(&wasm_hash).is_err());
+    // The contract fails because the admin did not authorize the upgrade.
+    let panic = e.last_failure().unwrap().panic.unwrap();
+    assert_eq!(panic.function, "upgrade");
+    assert!(panic
+        .message
+        .starts_with("HostError: Error(Auth, InvalidAction)"));
+
+    // The contract still runs the previous code.
+    assert_eq!(client.version(), 1);
+}
+
+#[test]
+fn test_upgrade_authorized() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let contract_id = e.register(Contract, (admin.clone(),));
+    let client = ContractClient::new(&e, &contract_id);
+
+    let wasm_hash = e.deployer().upload_contract_wasm(addcontract::WASM);
+    client.upgrade(&wasm_hash);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin,
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    contract_id.clone(),
+                    symbol_short!("upgrade"),
+                    (wasm_hash.clone(),).into_val(&e),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+
+    // The contract now runs the new code.
+    let client = addcontract::Client::new(&e, &contract_id);
+    assert_eq!(client.add(&2, &3), 5);
+}
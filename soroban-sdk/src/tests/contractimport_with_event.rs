@@ -0,0 +1,51 @@
+use crate as soroban_sdk;
+use soroban_sdk::{symbol_short, testutils::Events, Env};
+
+mod eventcontract {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/test_events.wasm");
+}
+
+#[test]
+fn test_try_from_event() {
+    let e = Env::default();
+    let contract_id = e.register(eventcontract::WASM, ());
+    let client = eventcontract::Client::new(&e, &contract_id);
+
+    client.hello();
+
+    let events = e.events().all();
+    let (_, topics, data) = events.get_unchecked(0);
+    assert_eq!(
+        eventcontract::Greeting::try_from_event(&e, &topics, &data),
+        Ok(eventcontract::Greeting {
+            topic: symbol_short!("topic2"),
+            message: symbol_short!("hello"),
+        })
+    );
+
+    // The second event has a different prefix topic.
+    let (_, topics, data) = events.get_unchecked(1);
+    assert!(eventcontract::Greeting::try_from_event(&e, &topics, &data).is_err());
+}
+
+#[test]
+fn test_publish() {
+    let e = Env::default();
+    let contract_id = e.register(eventcontract::WASM, ());
+
+    let event = eventcontract::Greeting {
+        topic: symbol_short!("topic3"),
+        message: symbol_short!("hi"),
+    };
+    e.as_contract(&contract_id, || event.publish(&e));
+
+    let events = e.events().all();
+    assert_eq!(events.len(), 1);
+    let (id, topics, data) = events.get_unchecked(0);
+    assert_eq!(id, contract_id);
+    assert_eq!(
+        eventcontract::Greeting::try_from_event(&e, &topics, &data),
+        Ok(event)
+    );
+}
@@ -0,0 +1,22 @@
+use crate as soroban_sdk;
+use soroban_sdk::{symbol_short, Env};
+
+mod contract_data {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(
+        file = "test_wasms/test_contract_data.wasm",
+        min_sdk_version = "22.0.0",
+        meta(key = "rsver", val = "1.81.0"),
+        meta(key = "rssdkver"),
+    );
+}
+
+#[test]
+fn test_functional() {
+    let e = Env::default();
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    client.put(&symbol_short!("k"), &symbol_short!("v"));
+    assert_eq!(client.get(&symbol_short!("k")), Some(symbol_short!("v")));
+}
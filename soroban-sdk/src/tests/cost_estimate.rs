@@ -1,8 +1,11 @@
 use crate as soroban_sdk;
 use expect_test::expect;
-use soroban_sdk::Env;
+use soroban_sdk::{
+    testutils::{cost_estimate::FeeConfig, EnvTestConfig, EnvTestOptions, Snapshot},
+    Env,
+};
 use soroban_sdk_macros::symbol_short;
-use stellar_xdr::curr::ContractCostType;
+use stellar_xdr::curr::{ContractCostType, ContractDataDurability};
 
 mod contract_data {
     use crate as soroban_sdk;
@@ -265,3 +268,229 @@ fn test_cost_estimate_budget() {
         .as_str(),
     );
 }
+
+#[test]
+fn test_cost_estimate_measure() {
+    let e = Env::default();
+
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    // A measurement of a single invocation is the same as the estimate of the
+    // last invocation.
+    let measurement = e
+        .cost_estimate()
+        .measure(|| client.put(&symbol_short!("k1"), &symbol_short!("v1")));
+    assert_eq!(measurement.invocations, 1);
+    assert_eq!(measurement.resources, e.cost_estimate().resources());
+    assert_eq!(measurement.fee, e.cost_estimate().fee());
+
+    // A measurement of multiple invocations is the sum of the estimates of
+    // each invocation.
+    let measurement = e.cost_estimate().measure(|| {
+        client.put(&symbol_short!("k2"), &symbol_short!("v2"));
+        let put = e.cost_estimate().resources();
+        let get = e
+            .cost_estimate()
+            .measure(|| client.get(&symbol_short!("k2")));
+        assert_eq!(get.value, Some(symbol_short!("v2")));
+        assert_eq!(get.invocations, 1);
+        (put, get.resources)
+    });
+    let (put, get) = measurement.value;
+    assert_eq!(measurement.invocations, 2);
+    assert_eq!(
+        measurement.resources.instructions,
+        put.instructions + get.instructions
+    );
+    assert_eq!(
+        measurement.resources.mem_bytes,
+        put.mem_bytes + get.mem_bytes
+    );
+    assert_eq!(measurement.resources.write_entries, 1);
+    assert_eq!(measurement.resources.memory_read_entries, 6);
+
+    // Invocations made outside of the measurement are not measured.
+    let measurement = e.cost_estimate().measure(|| ());
+    assert_eq!(measurement.invocations, 0);
+    assert_eq!(measurement.resources.instructions, 0);
+    assert_eq!(measurement.fee.total, 0);
+}
+
+#[test]
+fn test_cost_estimate_assert_below() {
+    let e = Env::default();
+
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    let measurement = e
+        .cost_estimate()
+        .measure(|| client.put(&symbol_short!("k1"), &symbol_short!("v1")));
+    crate::assert_cpu_below!(measurement, 1_000_000);
+    crate::assert_mem_below!(
+        measurement,
+        2_000_000,
+        "put used {:?}",
+        measurement.resources
+    );
+    crate::assert_fee_below!(measurement, 100_000);
+}
+
+#[test]
+#[should_panic(expected = "cpu instructions 242479 is not below the limit 100000: put")]
+fn test_cost_estimate_assert_cpu_below_fails() {
+    let e = Env::default();
+
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    let measurement = e
+        .cost_estimate()
+        .measure(|| client.put(&symbol_short!("k1"), &symbol_short!("v1")));
+    crate::assert_cpu_below!(measurement, 100_000, "put");
+}
+
+#[test]
+fn test_cost_estimate_fee_with() {
+    let e = Env::default();
+
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    client.put(&symbol_short!("k1"), &symbol_short!("v1"));
+
+    // The default fee estimate uses the pubnet configuration.
+    assert_eq!(
+        e.cost_estimate().fee_with(&FeeConfig::pubnet()),
+        e.cost_estimate().fee()
+    );
+    assert_eq!(FeeConfig::default(), FeeConfig::pubnet());
+
+    // Fee changes can be modelled with a custom configuration.
+    let pubnet = FeeConfig::pubnet();
+    let config = FeeConfig {
+        fee_per_instruction_increment: pubnet.fee_per_instruction_increment * 2,
+        ..pubnet.clone()
+    };
+    let fee = e.cost_estimate().fee_with(&config);
+    let pubnet_fee = e.cost_estimate().fee();
+    // The instruction fee is rounded up per increment of 10000 instructions.
+    assert_eq!(fee.instructions, (242479 * 50 + 9999) / 10000);
+    assert_eq!(
+        fee.total,
+        pubnet_fee.total - pubnet_fee.instructions + fee.instructions
+    );
+
+    // The fee per entry write also applies to the writes of the extended
+    // TTLs, which are part of the rent.
+    let config = FeeConfig {
+        fee_per_write_entry: 0,
+        ..pubnet
+    };
+    let fee = e.cost_estimate().fee_with(&config);
+    assert_eq!(fee.write_entries, 0);
+    assert_eq!(
+        fee.persistent_entry_rent,
+        pubnet_fee.persistent_entry_rent - pubnet.fee_per_write_entry
+    );
+
+    expect![[r#"
+        Resource                               amount            fee
+        instructions                           242479            607
+        disk_read_entries                           0           6250
+        write_entries                               1          10000
+        disk_read_bytes                             0              0
+        write_bytes                                80            274
+        contract_events_size_bytes                  0              0
+        persistent_rent_ledger_bytes           327600          11991
+        temporary_rent_ledger_bytes                 0              0
+        Total                                                  29122
+    "#]]
+    .assert_eq(
+        e.cost_estimate()
+            .fee_breakdown_with(&FeeConfig::pubnet())
+            .to_string()
+            .as_str(),
+    );
+}
+
+#[test]
+fn test_fee_config_rent_fee() {
+    let config = FeeConfig::pubnet();
+
+    let persistent = config.rent_fee(ContractDataDurability::Persistent, 100, 100_000);
+    let temporary = config.rent_fee(ContractDataDurability::Temporary, 100, 100_000);
+    expect!["65889"].assert_eq(&persistent.to_string());
+    expect!["38027"].assert_eq(&temporary.to_string());
+
+    // Rent grows with the size of the entry and the length of the extension.
+    assert!(config.rent_fee(ContractDataDurability::Persistent, 1000, 100_000) > persistent);
+    assert!(config.rent_fee(ContractDataDurability::Persistent, 100, 200_000) > persistent);
+
+    // And with the rent fee of the network.
+    let config = FeeConfig {
+        fee_per_rent_1kb: config.fee_per_rent_1kb * 2,
+        ..config
+    };
+    assert!(config.rent_fee(ContractDataDurability::Persistent, 100, 100_000) > persistent);
+}
+
+#[test]
+fn test_cost_in_snapshot() {
+    let e = Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_capture_cost_in_snapshot(true),
+    );
+
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+
+    client.put(&symbol_short!("k1"), &symbol_short!("v1"));
+    let put = e.cost_estimate().resources();
+    client.get(&symbol_short!("k1"));
+    let get = e.cost_estimate().resources();
+
+    // The resources of each top level invocation are captured in order.
+    let snapshot = e.to_snapshot();
+    let cost = snapshot.cost.clone().unwrap();
+    assert_eq!(cost.0.len(), 2);
+    assert_eq!(cost.0[0].contract, (&contract_id).into());
+    assert_eq!(cost.0[0].function.to_utf8_string_lossy(), "put");
+    assert_eq!(cost.0[0].instructions, put.instructions);
+    assert_eq!(cost.0[0].mem_bytes, put.mem_bytes);
+    assert_eq!(cost.0[0].write_entries, put.write_entries);
+    assert_eq!(cost.0[0].write_bytes, put.write_bytes);
+    assert_eq!(
+        cost.0[0].persistent_rent_ledger_bytes,
+        put.persistent_rent_ledger_bytes
+    );
+    assert_eq!(cost.0[1].function.to_utf8_string_lossy(), "get");
+    assert_eq!(cost.0[1].instructions, get.instructions);
+    assert_eq!(cost.0[1].write_entries, 0);
+
+    // The cost section survives a roundtrip through the snapshot file format.
+    let mut json = std::vec::Vec::new();
+    snapshot.write(&mut json).unwrap();
+    let read = Snapshot::read(json.as_slice()).unwrap();
+    assert_eq!(read.cost, snapshot.cost);
+
+    // The cost section is omitted by default, and no costs are recorded.
+    let mut e = Env::default();
+    let contract_id = e.register(contract_data::WASM, ());
+    let client = contract_data::Client::new(&e, &contract_id);
+    client.put(&symbol_short!("k1"), &symbol_short!("v1"));
+    let snapshot = e.to_snapshot();
+    assert_eq!(snapshot.cost, None);
+    assert!(e.to_cost_snapshot().0.is_empty());
+    let mut json = std::vec::Vec::new();
+    snapshot.write(&mut json).unwrap();
+    assert!(!String::from_utf8(json).unwrap().contains("\"cost\""));
+
+    // Only the invocations made once the option is set are captured.
+    e.set_options(EnvTestOptions::default().with_capture_cost_in_snapshot(true));
+    contract_data::Client::new(&e, &contract_id).get(&symbol_short!("k1"));
+    let cost = e.to_snapshot().cost.unwrap();
+    assert_eq!(cost.0.len(), 1);
+    assert_eq!(cost.0[0].function.to_utf8_string_lossy(), "get");
+}
@@ -149,6 +149,29 @@ fn test_snapshot_file() {
     let _ = std::fs::remove_file(&p2);
 }
 
+/// Test that the test snapshot file is written after contract invocations,
+/// including failed invocations, which the Env records for tests.
+#[test]
+fn test_snapshot_file_after_invocations() {
+    let p = std::path::Path::new("test_snapshots")
+        .join("tests")
+        .join("env")
+        .join("test_snapshot_file_after_invocations");
+    let p1 = p.with_extension("1.json");
+    let _ = std::fs::remove_file(&p1);
+    {
+        let e = Env::default();
+        let c = e.register(Contract, ());
+        let client = ContractClient::new(&e, &c);
+        client.test();
+        assert!(client.try_need_auth(&Address::generate(&e)).is_err());
+        assert!(e.last_failure().is_some());
+        assert!(!p1.exists());
+    } // Env dropped, written to p1.
+    assert!(p1.exists());
+    let _ = std::fs::remove_file(&p1);
+}
+
 /// Test that the test snapshot file is not written when disabled.
 #[test]
 fn test_snapshot_file_disabled() {
@@ -0,0 +1,168 @@
+use crate::{
+    self as soroban_sdk, contract, contracterror, contractimpl,
+    env::{EnvTestConfig, EnvTestOptions},
+    testutils::failure::CallFrame,
+    xdr::{ScErrorCode, ScErrorType, ScVal},
+    Address, Env, Error,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Insufficient = 1,
+}
+
+mod inner {
+    use super::ContractError;
+    use crate as soroban_sdk;
+    use soroban_sdk::{contract, contractimpl, Env};
+
+    #[contract]
+    pub struct Inner;
+
+    #[contractimpl]
+    impl Inner {
+        pub fn withdraw(env: Env, amount: i128) -> i128 {
+            if amount > 10 {
+                env.panic_with_error(ContractError::Insufficient);
+            }
+            if amount < 0 {
+                panic!("negative amount");
+            }
+            amount
+        }
+    }
+}
+use inner::{Inner, InnerClient};
+
+#[contract]
+pub struct Outer;
+
+#[contractimpl]
+impl Outer {
+    pub fn withdraw(env: Env, inner: Address, amount: i128) -> i128 {
+        InnerClient::new(&env, &inner).withdraw(&amount)
+    }
+
+    pub fn withdraw_or_panic(env: Env, inner: Address, amount: i128) -> i128 {
+        let _ = InnerClient::new(&env, &inner).try_withdraw(&amount);
+        panic!("inner failed");
+    }
+}
+
+#[test]
+fn test_native_panic() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let client = InnerClient::new(&env, &inner_id);
+
+    assert!(client.try_withdraw(&-1).is_err());
+
+    let report = env.last_failure().unwrap();
+    assert_eq!(report.contract, inner_id);
+    assert_eq!(report.function, "withdraw");
+    assert_eq!(report.args, [ScVal::from(-1i128)]);
+    // Errors other than contract errors are narrowed to a single error for
+    // the invoker.
+    assert_eq!(
+        report.error,
+        Error::from_type_and_code(ScErrorType::Context, ScErrorCode::InvalidAction)
+    );
+    let panic = report.panic.clone().unwrap();
+    assert_eq!(panic.contract, inner_id);
+    assert_eq!(panic.function, "withdraw");
+    assert_eq!(panic.message, "negative amount");
+    assert!(panic.location.unwrap().contains("failure_report.rs"));
+    assert_eq!(
+        report.call_stack,
+        [CallFrame {
+            contract: inner_id.clone(),
+            function: "withdraw".into(),
+        }]
+    );
+    assert!(!report.events.is_empty() && report.events.len() <= 10);
+
+    let printed = report.to_string();
+    assert!(printed.contains("panic: 'negative amount' at "));
+
+    // A successful invocation clears the report.
+    assert_eq!(client.withdraw(&1), 1);
+    assert_eq!(env.last_failure(), None);
+}
+
+#[test]
+fn test_cross_contract_call_stack() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let outer_id = env.register(Outer, ());
+    let client = OuterClient::new(&env, &outer_id);
+
+    assert!(client.try_withdraw(&inner_id, &11).is_err());
+
+    let report = env.last_failure().unwrap();
+    assert_eq!(report.contract, outer_id);
+    assert_eq!(report.error, ContractError::Insufficient.into());
+    assert_eq!(
+        report.call_stack,
+        [
+            CallFrame {
+                contract: outer_id.clone(),
+                function: "withdraw".into(),
+            },
+            CallFrame {
+                contract: inner_id.clone(),
+                function: "withdraw".into(),
+            },
+        ]
+    );
+    assert_eq!(report.panic.unwrap().contract, inner_id);
+}
+
+#[test]
+fn test_handled_failure_is_not_reported() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let outer_id = env.register(Outer, ());
+    let client = OuterClient::new(&env, &outer_id);
+
+    assert!(client.try_withdraw_or_panic(&inner_id, &-1).is_err());
+
+    let report = env.last_failure().unwrap();
+    assert_eq!(
+        report.call_stack,
+        [CallFrame {
+            contract: outer_id.clone(),
+            function: "withdraw_or_panic".into(),
+        }]
+    );
+    let panic = report.panic.unwrap();
+    assert_eq!(panic.contract, outer_id);
+    assert_eq!(panic.message, "inner failed");
+}
+
+#[test]
+fn test_report_captured_on_panic() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let client = InnerClient::new(&env, &inner_id);
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| client.withdraw(&11)));
+    assert!(res.is_err());
+
+    let report = env.last_failure().unwrap();
+    assert_eq!(report.error, ContractError::Insufficient.into());
+    assert_eq!(report.args, [ScVal::from(11i128)]);
+}
+
+#[test]
+fn test_failure_report_events_config() {
+    let env = Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_failure_report_events(2),
+    );
+    let inner_id = env.register(Inner, ());
+    let client = InnerClient::new(&env, &inner_id);
+
+    assert!(client.try_withdraw(&-1).is_err());
+    assert_eq!(env.last_failure().unwrap().events.len(), 2);
+}
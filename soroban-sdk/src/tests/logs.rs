@@ -0,0 +1,143 @@
+use crate::{
+    self as soroban_sdk, contract, contractimpl, debug,
+    env::{EnvTestConfig, EnvTestOptions},
+    error, info, log,
+    logs::Level,
+    symbol_short,
+    testutils::{LogRecord, Logs as _},
+    warn,
+    xdr::ScVal,
+    Env, Symbol,
+};
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn transfer(env: Env, amount: i128) {
+        debug!(&env, "start");
+        info!(&env, "transfer", amount = amount, to = symbol_short!("bob"));
+        warn!(&env, "low balance", balance = 1u32);
+        error!(&env, "failed", code = 7u32,);
+        log!(&env, "plain", 1u32, symbol_short!("arg"));
+    }
+}
+
+#[test]
+fn test_records() {
+    let env = Env::default();
+    let contract_id = env.register(Contract, ());
+    let client = ContractClient::new(&env, &contract_id);
+
+    client.transfer(&5);
+
+    let records = env.logs().records();
+    assert_eq!(
+        records
+            .iter()
+            .map(|r| (r.level, r.message.as_str()))
+            .collect::<std::vec::Vec<_>>(),
+        [
+            (Level::Debug, "start"),
+            (Level::Info, "transfer"),
+            (Level::Warn, "low balance"),
+            (Level::Error, "failed"),
+            (Level::Debug, "plain"),
+        ]
+    );
+    assert!(records
+        .iter()
+        .all(|r| r.contract == Some(contract_id.clone())));
+
+    let transfer = &records[1];
+    assert_eq!(transfer.field::<i128>(&env, "amount"), Some(5));
+    assert_eq!(
+        transfer.field::<Symbol>(&env, "to"),
+        Some(symbol_short!("bob"))
+    );
+    assert_eq!(transfer.field::<i128>(&env, "missing"), None);
+    assert_eq!(transfer.field::<u32>(&env, "amount"), None);
+
+    // The arguments of plain log entries are named by their position.
+    assert_eq!(
+        records[4].fields,
+        [
+            ("0".into(), ScVal::U32(1)),
+            ("1".into(), ScVal::Symbol("arg".try_into().unwrap())),
+        ]
+    );
+}
+
+#[test]
+fn test_records_outside_contract() {
+    let env = Env::default();
+
+    info!(&env, "outside", n = 1u32);
+
+    assert_eq!(
+        env.logs().records(),
+        [LogRecord {
+            level: Level::Info,
+            contract: None,
+            message: "outside".into(),
+            fields: [("n".into(), ScVal::U32(1))].into(),
+        }]
+    );
+}
+
+#[test]
+fn test_records_plain_log_of_level_name() {
+    let env = Env::default();
+
+    log!(&env, "m", symbol_short!("warn"));
+    log!(
+        &env,
+        "m",
+        symbol_short!("error"),
+        symbol_short!("code"),
+        7u32
+    );
+
+    assert_eq!(
+        env.logs().records(),
+        [
+            LogRecord {
+                level: Level::Debug,
+                contract: None,
+                message: "m".into(),
+                fields: [("0".into(), ScVal::Symbol("warn".try_into().unwrap()))].into(),
+            },
+            LogRecord {
+                level: Level::Debug,
+                contract: None,
+                message: "m".into(),
+                fields: [
+                    ("0".into(), ScVal::Symbol("error".try_into().unwrap())),
+                    ("1".into(), ScVal::Symbol("code".try_into().unwrap())),
+                    ("2".into(), ScVal::U32(7)),
+                ]
+                .into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_print_log_level_does_not_affect_records() {
+    let env = Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_print_log_level(Some(Level::Error)),
+    );
+    let contract_id = env.register(Contract, ());
+    ContractClient::new(&env, &contract_id).transfer(&5);
+    assert_eq!(env.logs().records().len(), 5);
+
+    let env = Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_print_log_level(None),
+    );
+    let contract_id = env.register(Contract, ());
+    ContractClient::new(&env, &contract_id).transfer(&5);
+    assert_eq!(env.logs().records().len(), 5);
+}
@@ -0,0 +1,237 @@
+use crate::{
+    self as soroban_sdk, contract, contractimpl,
+    env::{EnvTestConfig, EnvTestOptions},
+    testutils::{
+        profile::{Profile, ProfileMetric},
+        AuthSigner, Ed25519Account,
+    },
+    Address, Env,
+};
+
+mod addcontract {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/test_add_u64.wasm");
+}
+
+mod inner {
+    use crate as soroban_sdk;
+    use soroban_sdk::{contract, contractimpl, Bytes, Env};
+
+    #[contract]
+    pub struct Inner;
+
+    #[contractimpl]
+    impl Inner {
+        pub fn hash(env: Env, len: u32) -> u32 {
+            let data = Bytes::from_slice(&env, &std::vec![0u8; len as usize]);
+            env.crypto().sha256(&data);
+            len
+        }
+
+        pub fn fail(len: u32) -> u32 {
+            panic!("failed after {len}")
+        }
+    }
+}
+use inner::{Inner, InnerClient};
+
+#[contract]
+pub struct Outer;
+
+#[contractimpl]
+impl Outer {
+    pub fn hash_twice(env: Env, inner: Address, len: u32) -> u32 {
+        let client = InnerClient::new(&env, &inner);
+        client.hash(&len) + client.hash(&len)
+    }
+
+    pub fn try_fail(env: Env, inner: Address) -> bool {
+        let client = InnerClient::new(&env, &inner);
+        client.try_fail(&1).is_err()
+    }
+
+    pub fn authorized(from: Address) {
+        from.require_auth();
+    }
+}
+
+fn profiled_env() -> Env {
+    Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_capture_profile(true),
+    )
+}
+
+fn cpu(profile: &Profile, frames: &[&str], cost_type: &str) -> u64 {
+    profile
+        .stacks
+        .iter()
+        .filter(|s| s.frames == frames && s.cost_type == cost_type)
+        .map(|s| s.cpu_insns)
+        .sum()
+}
+
+#[test]
+fn test_profile_disabled() {
+    let e = Env::default();
+    let inner_id = e.register(Inner, ());
+    InnerClient::new(&e, &inner_id).hash(&10);
+
+    assert_eq!(e.cost_estimate().profile(), Profile::default());
+}
+
+#[test]
+fn test_profile_nested() {
+    let e = profiled_env();
+    let inner_id = e.register(Inner, ());
+    let outer_id = e.register(Outer, ());
+    let client = OuterClient::new(&e, &outer_id);
+
+    assert_eq!(client.hash_twice(&inner_id, &100), 200);
+    let budget = e.cost_estimate().budget();
+
+    let profile = e.cost_estimate().profile();
+    let outer = std::format!("{outer_id:?}.hash_twice");
+    let inner = std::format!("{inner_id:?}.hash");
+
+    // The hashing is attributed to the inner invocations, which are merged.
+    let hash = cpu(&profile, &[&outer, &inner], "ComputeSha256Hash");
+    assert!(hash > cpu(&profile, &[&outer], "ComputeSha256Hash"));
+    assert_eq!(
+        hash + cpu(&profile, &[&outer], "ComputeSha256Hash"),
+        budget
+            .tracker(soroban_sdk::xdr::ContractCostType::ComputeSha256Hash)
+            .cpu
+    );
+
+    // The cost of the invocation is the sum of the cost of its stacks, which
+    // excludes the cost of converting the returned value for the test.
+    let total: u64 = profile.stacks.iter().map(|s| s.cpu_insns).sum();
+    assert!(total > 0);
+    assert!(total <= budget.cpu_instruction_cost());
+
+    // Every stack starts at the top level invocation.
+    assert!(profile.stacks.iter().all(|s| s.frames[0] == outer));
+
+    let folded = profile.to_folded(ProfileMetric::CpuInsns);
+    assert!(folded.contains(&std::format!("{outer};{inner};ComputeSha256Hash {hash}\n")));
+    assert_eq!(folded.lines().count(), profile.stacks.len());
+}
+
+#[test]
+fn test_profile_accumulates_invocations() {
+    let e = profiled_env();
+    let inner_id = e.register(Inner, ());
+    let client = InnerClient::new(&e, &inner_id);
+    let inner = std::format!("{inner_id:?}.hash");
+
+    client.hash(&100);
+    let once = cpu(&e.cost_estimate().profile(), &[&inner], "ComputeSha256Hash");
+    client.hash(&100);
+    let twice = cpu(&e.cost_estimate().profile(), &[&inner], "ComputeSha256Hash");
+    assert_eq!(twice, once * 2);
+}
+
+#[test]
+fn test_profile_failed_invocation() {
+    let e = profiled_env();
+    let inner_id = e.register(Inner, ());
+    let outer_id = e.register(Outer, ());
+
+    assert!(OuterClient::new(&e, &outer_id).try_fail(&inner_id));
+
+    // The failed invocation is ended with the invocation that made it.
+    let profile = e.cost_estimate().profile();
+    let outer = std::format!("{outer_id:?}.try_fail");
+    let inner = std::format!("{inner_id:?}.fail");
+    assert!(profile.stacks.iter().all(|s| s.frames[0] == outer));
+    assert!(profile
+        .stacks
+        .iter()
+        .any(|s| s.frames == [outer.clone(), inner.clone()]));
+
+    // Later invocations start from the top level.
+    InnerClient::new(&e, &inner_id).hash(&1);
+    let profile = e.cost_estimate().profile();
+    let hash = std::format!("{inner_id:?}.hash");
+    assert!(profile.stacks.iter().any(|s| s.frames == [hash.clone()]));
+}
+
+#[test]
+fn test_profile_wasm() {
+    let e = profiled_env();
+    let contract_id = e.register(addcontract::WASM, ());
+    let client = addcontract::Client::new(&e, &contract_id);
+
+    assert_eq!(client.add(&1, &2), 3);
+
+    let profile = e.cost_estimate().profile();
+    let add = std::format!("{contract_id:?}.add");
+    assert!(cpu(&profile, &[&add], "InstantiateWasmInstructions") > 0);
+    assert!(cpu(&profile, &[&add], "WasmInsnExec") > 0);
+
+    let json = serde_json::to_string(&profile).unwrap();
+    let roundtrip: Profile = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtrip, profile);
+}
+
+/// Test that the test profile file is written when the Env is dropped.
+#[test]
+fn test_profile_file() {
+    let p = std::path::Path::new("test_profiles")
+        .join("tests")
+        .join("profile")
+        .join("test_profile_file");
+    let p1 = p.with_extension("1.folded");
+    let p2 = p.with_extension("2.folded");
+    let _ = std::fs::remove_file(&p1);
+    let _ = std::fs::remove_file(&p2);
+    {
+        let _ = profiled_env(); // When dropped won't be written because empty.
+    }
+    {
+        let e = Env::default(); // When dropped won't be written because disabled.
+        let inner_id = e.register(Inner, ());
+        InnerClient::new(&e, &inner_id).hash(&1);
+    }
+    assert!(!p1.exists());
+    let inner;
+    {
+        let e = profiled_env();
+        let inner_id = e.register(Inner, ());
+        InnerClient::new(&e, &inner_id).hash(&1);
+        inner = std::format!("{inner_id:?}.hash");
+        assert!(!p1.exists());
+    } // Env dropped, written to p1.
+    let folded = std::fs::read_to_string(&p1).unwrap();
+    assert!(folded
+        .lines()
+        .all(|l| l.starts_with(&std::format!("{inner};"))));
+    assert!(!p2.exists());
+    let _ = std::fs::remove_file(&p1);
+}
+
+/// Test that signing auths, which records the auths in a fork of the Env,
+/// writes no profile file for the fork.
+#[test]
+fn test_profile_file_sign_with() {
+    let p = std::path::Path::new("test_profiles")
+        .join("tests")
+        .join("profile")
+        .join("test_profile_file_sign_with");
+    let p1 = p.with_extension("1.folded");
+    let p2 = p.with_extension("2.folded");
+    let _ = std::fs::remove_file(&p1);
+    let _ = std::fs::remove_file(&p2);
+    {
+        let e = profiled_env();
+        let outer_id = e.register(Outer, ());
+        let account = Ed25519Account::new(&e, ed25519_dalek::SigningKey::from_bytes(&[1; 32]));
+        OuterClient::new(&e, &outer_id)
+            .sign_with(&[&account])
+            .authorized(&account.address());
+    } // Env dropped, written to p1.
+    assert!(p1.exists());
+    assert!(!p2.exists());
+    let _ = std::fs::remove_file(&p1);
+}
@@ -0,0 +1,237 @@
+use crate::{
+    self as soroban_sdk, contract, contracterror, contractimpl,
+    env::{EnvTestConfig, EnvTestOptions},
+    symbol_short, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
+};
+
+mod addcontract {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(file = "../target/wasm32v1-none/release/test_add_u64.wasm");
+}
+
+mod importcontract {
+    use crate as soroban_sdk;
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32v1-none/release/test_import_contract.wasm"
+    );
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Overflow = 1,
+}
+
+mod inner {
+    use super::ContractError;
+    use crate as soroban_sdk;
+    use soroban_sdk::{contract, contractimpl, symbol_short, Env, Vec};
+
+    #[contract]
+    pub struct Inner;
+
+    #[contractimpl]
+    impl Inner {
+        pub fn add(env: Env, x: u32, y: u32) -> u32 {
+            env.events().publish((symbol_short!("add"),), x);
+            x.checked_add(y)
+                .unwrap_or_else(|| env.panic_with_error(ContractError::Overflow))
+        }
+
+        pub fn sum(values: Vec<u32>) -> u32 {
+            values.iter().sum()
+        }
+    }
+}
+use inner::{Inner, InnerClient};
+
+#[contract]
+pub struct Outer;
+
+#[contractimpl]
+impl Outer {
+    pub fn add_twice(env: Env, inner: Address, x: u32) -> u32 {
+        let client = InnerClient::new(&env, &inner);
+        env.events().publish((symbol_short!("outer"),), x);
+        client.add(&client.add(&x, &x), &x)
+    }
+
+    pub fn try_add(env: Env, inner: Address, x: u32) -> u32 {
+        let client = InnerClient::new(&env, &inner);
+        client.try_add(&x, &u32::MAX).unwrap_or(Ok(0)).unwrap()
+    }
+}
+
+#[test]
+fn test_trace() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let outer_id = env.register(Outer, ());
+    let client = OuterClient::new(&env, &outer_id);
+
+    assert_eq!(client.add_twice(&inner_id, &2), 6);
+
+    let trace = env.trace();
+    assert_eq!(trace.0.len(), 1);
+    let outer = &trace.0[0];
+    assert_eq!(outer.caller, None);
+    assert_eq!(outer.contract, outer_id);
+    assert_eq!(outer.function, Symbol::new(&env, "add_twice"));
+    assert_eq!(outer.args, (inner_id.clone(), 2u32).into_val(&env));
+    assert_eq!(
+        u32::try_from_val(&env, &outer.result.unwrap().unwrap()),
+        Ok(6)
+    );
+    assert_eq!(
+        outer.events,
+        Vec::from_array(
+            &env,
+            [(
+                outer_id.clone(),
+                (symbol_short!("outer"),).into_val(&env),
+                2u32.into_val(&env)
+            )]
+        )
+    );
+
+    assert_eq!(outer.sub_invocations.len(), 2);
+    for (inner, (args, result)) in outer
+        .sub_invocations
+        .iter()
+        .zip([((2u32, 2u32), 4u32), ((4, 2), 6)])
+    {
+        assert_eq!(inner.caller, Some(outer_id.clone()));
+        assert_eq!(inner.contract, inner_id);
+        assert_eq!(inner.function, symbol_short!("add"));
+        let expected_args: Vec<Val> = args.into_val(&env);
+        assert_eq!(inner.args, expected_args);
+        assert_eq!(
+            u32::try_from_val(&env, &inner.result.unwrap().unwrap()),
+            Ok(result)
+        );
+        assert_eq!(inner.events.len(), 1);
+        assert!(inner.sub_invocations.is_empty());
+    }
+
+    // The cost of an invocation includes the cost of the invocations it made.
+    let outer_cost = outer.cost.unwrap();
+    let inner_cost = outer.sub_invocations[0].cost.unwrap();
+    assert!(inner_cost.cpu_insns > 0);
+    assert!(outer_cost.cpu_insns > 2 * inner_cost.cpu_insns);
+    assert!(outer_cost.mem_bytes > 2 * inner_cost.mem_bytes);
+}
+
+#[test]
+fn test_trace_failed_invocation() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let outer_id = env.register(Outer, ());
+    let client = OuterClient::new(&env, &outer_id);
+
+    assert_eq!(client.try_add(&inner_id, &1), 0);
+
+    let trace = env.trace();
+    assert_eq!(trace.0.len(), 1);
+    let handled = &trace.0[0].sub_invocations[0];
+    assert_eq!(
+        handled.result.unwrap().err(),
+        Some(ContractError::Overflow.into())
+    );
+    assert!(trace.0[0].result.unwrap().is_ok());
+
+    assert!(client.try_add_twice(&inner_id, &u32::MAX).is_err());
+
+    // The trace is of the last top level invocation.
+    let trace = env.trace();
+    assert_eq!(trace.0.len(), 1);
+    let failed = &trace.0[0];
+    assert_eq!(failed.function, Symbol::new(&env, "add_twice"));
+    assert_eq!(
+        failed.result.unwrap().err(),
+        Some(ContractError::Overflow.into())
+    );
+    assert_eq!(
+        failed.sub_invocations[0].result.unwrap().err(),
+        Some(ContractError::Overflow.into())
+    );
+    // The cost of an invocation that panicked is not measured.
+    assert!(failed.cost.is_some());
+    assert_eq!(failed.sub_invocations[0].cost, None);
+}
+
+#[test]
+fn test_trace_single_vec_arg() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let client = InnerClient::new(&env, &inner_id);
+
+    let values = Vec::from_array(&env, [1u32, 2, 3]);
+    assert_eq!(client.sum(&values), 6);
+
+    let trace = env.trace();
+    let expected_args: Vec<Val> = (values,).into_val(&env);
+    assert_eq!(trace.0[0].args, expected_args);
+}
+
+#[test]
+fn test_trace_wasm() {
+    let env = Env::default();
+    let add_id = env.register(addcontract::WASM, ());
+    let import_id = env.register(importcontract::WASM, ());
+    let client = importcontract::Client::new(&env, &import_id);
+
+    assert_eq!(client.add_with(&add_id, &1, &2), 3);
+
+    let trace = env.trace();
+    let import = &trace.0[0];
+    assert_eq!(import.contract, import_id);
+    assert!(import.cost.is_some());
+    let add = &import.sub_invocations[0];
+    assert_eq!(add.caller, Some(import_id.clone()));
+    assert_eq!(add.contract, add_id);
+    assert_eq!(add.args, (1u64, 2u64).into_val(&env));
+    assert_eq!(
+        u64::try_from_val(&env, &add.result.unwrap().unwrap()),
+        Ok(3)
+    );
+    // Invocations made by Wasm contracts are not measured.
+    assert_eq!(add.cost, None);
+}
+
+#[test]
+fn test_trace_display() {
+    let env = Env::default();
+    let inner_id = env.register(Inner, ());
+    let outer_id = env.register(Outer, ());
+    let client = OuterClient::new(&env, &outer_id);
+
+    client.add_twice(&inner_id, &2);
+
+    let printed = env.trace().to_string();
+    let lines = printed.lines().collect::<std::vec::Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with(&format!("{outer_id:?}.add_twice(")));
+    assert!(lines[0].contains("-> U32(6) [cpu: "));
+    assert!(lines[1].starts_with("├─ event [Symbol(ScSymbol(StringM(outer)))] U32(2)"));
+    assert!(lines[2].starts_with(&format!("├─ {inner_id:?}.add([U32(2), U32(2)]) -> U32(4)")));
+    assert!(lines[3].starts_with("│  └─ event "));
+    assert!(lines[4].starts_with(&format!("└─ {inner_id:?}.add([U32(4), U32(2)]) -> U32(6)")));
+    assert!(lines[5].starts_with("   └─ event "));
+}
+
+#[test]
+fn test_trace_in_snapshot() {
+    let env = Env::new_with_options(
+        EnvTestConfig::default(),
+        EnvTestOptions::default().with_capture_trace_in_snapshot(true),
+    );
+    let inner_id = env.register(Inner, ());
+    InnerClient::new(&env, &inner_id).add(&1, &2);
+
+    let trace = env.to_snapshot().trace.unwrap();
+    assert_eq!(trace.0.len(), 1);
+    assert_eq!(trace.0[0].function.to_utf8_string_lossy(), "add");
+
+    let env = Env::default();
+    assert_eq!(env.to_snapshot().trace, None);
+}
@@ -8,22 +8,34 @@ pub mod arbitrary;
 mod sign;
 use std::rc::Rc;
 
-pub use sign::ed25519;
+pub use sign::{bls12_381, ed25519, secp256k1, secp256r1};
 
 mod mock_auth;
 pub use mock_auth::{
     AuthorizedFunction, AuthorizedInvocation, MockAuth, MockAuthContract, MockAuthInvoke,
 };
+
+mod check_auth;
+pub use check_auth::CheckAuthPayload;
+
+mod auth_signer;
+pub use auth_signer::{signed_auths, AuthSigner, CustomAccount, Ed25519Account};
 use soroban_env_host::TryIntoVal;
 
 pub mod storage;
 
 pub mod cost_estimate;
 
+pub mod failure;
+
+pub mod trace;
+
+pub mod profile;
+
 use crate::{xdr, ConstructorArgs, Env, Val, Vec};
 use soroban_ledger_snapshot::LedgerSnapshot;
 
-pub use crate::env::EnvTestConfig;
+pub use crate::env::{EnvTestConfig, EnvTestOptions};
 
 pub trait Register {
     fn register<'i, I, A>(self, env: &Env, id: I, args: A) -> crate::Address
@@ -62,6 +74,10 @@ pub struct Snapshot {
     pub auth: AuthSnapshot,
     pub ledger: LedgerSnapshot,
     pub events: EventsSnapshot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<trace::TraceSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<cost_estimate::CostSnapshot>,
 }
 
 impl Snapshot {
@@ -414,10 +430,94 @@ pub trait Events {
 pub trait Logs {
     /// Returns all diagnostic events that have been logged.
     fn all(&self) -> std::vec::Vec<String>;
+    /// Returns all entries that have been logged, as records with the level,
+    /// contract, message, and fields of each entry.
+    fn records(&self) -> std::vec::Vec<LogRecord>;
     /// Prints all diagnostic events to stdout.
     fn print(&self);
 }
 
+/// An entry that has been logged, see [`Logs::records`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogRecord {
+    /// The level of the entry. Entries logged with [`log`][crate::log] are at
+    /// [`Level::Debug`][crate::logs::Level::Debug].
+    pub level: crate::logs::Level,
+    /// The contract that logged the entry, or None if it was logged outside of
+    /// a contract.
+    pub contract: Option<crate::Address>,
+    /// The message of the entry.
+    pub message: String,
+    /// The names and values of the fields of the entry. The arguments of
+    /// entries logged with [`log`][crate::log] are named by their position,
+    /// starting at `"0"`.
+    pub fields: std::vec::Vec<(String, xdr::ScVal)>,
+}
+
+impl LogRecord {
+    /// Returns the value of the field with the name, converted to `T`.
+    ///
+    /// Returns None if the entry has no field with the name, or if the value
+    /// is not convertible to `T`.
+    pub fn field<T: crate::TryFromVal<Env, Val>>(&self, env: &Env, name: &str) -> Option<T> {
+        let (_, value) = self.fields.iter().find(|(n, _)| n == name)?;
+        let value = <Val as crate::TryFromVal<Env, xdr::ScVal>>::try_from_val(env, value).ok()?;
+        T::try_from_val(env, &value).ok()
+    }
+
+    pub(crate) fn from_data(contract: Option<crate::Address>, data: &xdr::ScVal) -> Self {
+        use crate::logs::Level;
+        use xdr::ScVal;
+
+        let to_string = |v: &ScVal| match v {
+            ScVal::String(s) => s.to_utf8_string_lossy(),
+            ScVal::Symbol(s) => s.to_utf8_string_lossy(),
+            v => format!("{v:?}"),
+        };
+        let (message, args) = match data {
+            ScVal::Vec(Some(v)) if !v.is_empty() => (to_string(&v[0]), &v[1..]),
+            v => (to_string(v), &[][..]),
+        };
+
+        // Entries logged with a level are marked by the symbol of the level as
+        // their first argument, followed by pairs of field names and values.
+        let level = match args.first() {
+            Some(ScVal::Symbol(s)) => match s.to_utf8_string_lossy().as_str() {
+                "__debug" => Some(Level::Debug),
+                "__info" => Some(Level::Info),
+                "__warn" => Some(Level::Warn),
+                "__error" => Some(Level::Error),
+                _ => None,
+            },
+            _ => None,
+        };
+        let (level, fields) = match level {
+            Some(level) => {
+                let fields = args[1..]
+                    .chunks(2)
+                    .map(|c| (to_string(&c[0]), c.get(1).cloned().unwrap_or(ScVal::Void)))
+                    .collect();
+                (level, fields)
+            }
+            None => {
+                let fields = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (i.to_string(), v.clone()))
+                    .collect();
+                (Level::Debug, fields)
+            }
+        };
+
+        LogRecord {
+            level,
+            contract,
+            message,
+            fields,
+        }
+    }
+}
+
 /// Test utilities for [`BytesN`][crate::BytesN].
 pub trait BytesN<const N: usize> {
     // Generate a BytesN filled with random bytes.
@@ -445,6 +545,15 @@ pub trait Address {
     fn generate(env: &Env) -> crate::Address;
 }
 
+/// Test utilities for [`ReentrancyGuard`][crate::security::ReentrancyGuard].
+pub trait ReentrancyGuard {
+    /// Calls `f` with the contract marked as executing a nonreentrant
+    /// function, so that calls in `f` to nonreentrant functions of the
+    /// contract fail as reentrant calls do, with
+    /// [`SecurityError::Reentrant`][crate::security::SecurityError::Reentrant].
+    fn with_entered<T>(env: &Env, contract_id: &crate::Address, f: impl FnOnce() -> T) -> T;
+}
+
 pub trait MuxedAddress {
     /// Create a new MuxedAddress with arbitrary `Address` and id parts.
     ///
@@ -466,6 +575,68 @@ pub trait MuxedAddress {
 }
 
 pub trait Deployer {
+    /// Registers a natively compiled contract as the implementation of
+    /// contracts deployed with the given Wasm hash.
+    ///
+    /// When a contract deploys a contract with the Wasm hash, using
+    /// [`deploy_v2`][crate::deploy::DeployerWithAddress::deploy_v2], the
+    /// native contract is registered at the deployed address instead of the
+    /// Wasm being instantiated. This allows factory contracts to be tested
+    /// with the native implementation of the contracts they deploy, which
+    /// supports debugging and code coverage, and does not require the Wasm to
+    /// have been built or uploaded.
+    ///
+    /// The deployed address is the same as it would be for the Wasm contract.
+    /// Note that the executable of the deployed contract is the placeholder
+    /// used for all natively registered contracts rather than the Wasm hash,
+    /// and that authorization of the deployer is not required.
+    ///
+    /// ### Examples
+    /// ```
+    /// use soroban_sdk::{contract, contractimpl, testutils::Deployer as _, Address, BytesN, Env};
+    ///
+    /// #[contract]
+    /// pub struct Counter;
+    ///
+    /// #[contractimpl]
+    /// impl Counter {
+    ///     pub fn __constructor(env: Env, start: u32) {
+    ///         env.storage().instance().set(&(), &start);
+    ///     }
+    /// }
+    ///
+    /// #[contract]
+    /// pub struct Factory;
+    ///
+    /// #[contractimpl]
+    /// impl Factory {
+    ///     pub fn deploy(env: Env, wasm_hash: BytesN<32>) -> Address {
+    ///         env.deployer()
+    ///             .with_current_contract([0; 32])
+    ///             .deploy_v2(wasm_hash, (5_u32,))
+    ///     }
+    /// }
+    ///
+    /// #[test]
+    /// fn test() {
+    /// # }
+    /// # fn main() {
+    ///     let env = Env::default();
+    ///     let wasm_hash = BytesN::from_array(&env, &[1; 32]);
+    ///     env.deployer().register_contract_for_wasm_hash(&wasm_hash, Counter);
+    ///
+    ///     let factory = FactoryClient::new(&env, &env.register(Factory, ()));
+    ///     let counter = factory.deploy(&wasm_hash);
+    ///     let start: u32 = env.as_contract(&counter, || {
+    ///         env.storage().instance().get(&()).unwrap()
+    ///     });
+    ///     assert_eq!(start, 5);
+    /// }
+    /// ```
+    fn register_contract_for_wasm_hash<C>(&self, wasm_hash: &crate::BytesN<32>, contract: C)
+    where
+        C: ContractFunctionSet + 'static;
+
     /// Gets the TTL of the given contract's instance.
     ///
     /// TTL is the number of ledgers left until the instance entry is considered
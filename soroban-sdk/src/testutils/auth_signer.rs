@@ -0,0 +1,315 @@
+#![cfg(any(test, feature = "testutils"))]
+
+use std::rc::Rc;
+
+use crate::{
+    contracttype, vec, xdr, Address, BytesN, Env, Error, IntoVal, Symbol, TryFromVal, Val, Vec,
+};
+
+use super::{sign::Sign, CheckAuthPayload};
+
+/// AuthSigner implementations sign authorization payloads on behalf of an
+/// address.
+///
+/// Signers are passed to the `sign_with` function of generated contract
+/// clients, or to [`signed_auths`], to authorize invocations with real
+/// signatures in enforcing auth mode.
+///
+/// Implementations are provided for Stellar accounts with an ed25519 key,
+/// [`Ed25519Account`], and for custom account contracts, [`CustomAccount`].
+/// Both sign with keys that implement [`Sign`] for the [`CheckAuthPayload`],
+/// which the [`ed25519`][super::ed25519], [`secp256k1`][super::secp256k1], and
+/// [`secp256r1`][super::secp256r1] keys do. Implement the trait to sign for
+/// accounts with other signature formats.
+pub trait AuthSigner {
+    /// Returns the address that the signer authorizes invocations for.
+    fn address(&self) -> Address;
+
+    /// Returns the signature of the payload, as the value placed in the
+    /// credentials of the authorization entry.
+    ///
+    /// For custom account contracts the value is passed as the `signature`
+    /// argument of `__check_auth`.
+    fn sign_payload(&self, payload: &CheckAuthPayload) -> Val;
+}
+
+/// The signature of a Stellar account, in the format the host verifies.
+#[derive(Clone)]
+#[contracttype(crate_path = "crate", export = false)]
+struct AccountEd25519Signature {
+    public_key: BytesN<32>,
+    signature: BytesN<64>,
+}
+
+/// A Stellar account that signs with the ed25519 key that is its master key.
+///
+/// ### Examples
+/// ```
+/// use soroban_sdk::{
+///     contract, contractimpl,
+///     testutils::{AuthSigner, Ed25519Account},
+///     Address, Env,
+/// };
+/// use ed25519_dalek::SigningKey;
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[contractimpl]
+/// impl Contract {
+///     pub fn hello(from: Address) {
+///         from.require_auth();
+///     }
+/// }
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # fn main() {
+///     let env = Env::default();
+///     let contract_id = env.register(Contract, ());
+///     let client = ContractClient::new(&env, &contract_id);
+///
+///     let account = Ed25519Account::new(&env, SigningKey::from_bytes(&[1; 32]));
+///     client.sign_with(&[&account]).hello(&account.address());
+/// }
+/// ```
+pub struct Ed25519Account {
+    address: Address,
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Account {
+    /// Creates the signer for the account of the signing key, creating the
+    /// account in the ledger if it does not exist.
+    pub fn new(env: &Env, signing_key: ed25519_dalek::SigningKey) -> Self {
+        let account_id = xdr::AccountId(xdr::PublicKey::PublicKeyTypeEd25519(xdr::Uint256(
+            signing_key.verifying_key().to_bytes(),
+        )));
+
+        let k = Rc::new(xdr::LedgerKey::Account(xdr::LedgerKeyAccount {
+            account_id: account_id.clone(),
+        }));
+        if env.host().get_ledger_entry(&k).unwrap().is_none() {
+            let v = Rc::new(xdr::LedgerEntry {
+                data: xdr::LedgerEntryData::Account(xdr::AccountEntry {
+                    account_id: account_id.clone(),
+                    balance: 0,
+                    flags: 0,
+                    home_domain: Default::default(),
+                    inflation_dest: None,
+                    num_sub_entries: 0,
+                    seq_num: xdr::SequenceNumber(0),
+                    thresholds: xdr::Thresholds([1; 4]),
+                    signers: xdr::VecM::default(),
+                    ext: xdr::AccountEntryExt::V0,
+                }),
+                last_modified_ledger_seq: 0,
+                ext: xdr::LedgerEntryExt::V0,
+            });
+            env.host().add_ledger_entry(&k, &v, None).unwrap();
+        }
+
+        let address = Address::try_from_val(env, &xdr::ScAddress::Account(account_id)).unwrap();
+        Self {
+            address,
+            signing_key,
+        }
+    }
+}
+
+impl AuthSigner for Ed25519Account {
+    fn address(&self) -> Address {
+        self.address.clone()
+    }
+
+    fn sign_payload(&self, payload: &CheckAuthPayload) -> Val {
+        let env = self.address.env();
+        let signature = AccountEd25519Signature {
+            public_key: BytesN::from_array(env, &self.signing_key.verifying_key().to_bytes()),
+            signature: self.signing_key.sign(payload.clone()).unwrap(),
+        };
+        vec![env, signature].into_val(env)
+    }
+}
+
+/// A custom account contract that signs with a key, or with a function.
+///
+/// The key signs the payload with its implementation of [`Sign`] for the
+/// [`CheckAuthPayload`], and the signature is passed as the `signature`
+/// argument of the contract's `__check_auth`. A function that receives the
+/// payload and returns the value of the `signature` argument may be used in
+/// place of a key.
+///
+/// ### Examples
+/// ```
+/// use soroban_sdk::{
+///     auth::Context, contract, contractimpl, crypto::Hash,
+///     testutils::{secp256r1, AuthSigner, CustomAccount},
+///     Address, BytesN, Env, Vec,
+/// };
+/// use p256::ecdsa::SigningKey;
+///
+/// #[contract]
+/// pub struct Account;
+///
+/// #[contractimpl]
+/// impl Account {
+///     pub fn __constructor(env: Env, public_key: BytesN<65>) {
+///         env.storage().instance().set(&(), &public_key);
+///     }
+///
+///     #[allow(non_snake_case)]
+///     pub fn __check_auth(
+///         env: Env,
+///         signature_payload: Hash<32>,
+///         signature: BytesN<64>,
+///         _auth_contexts: Vec<Context>,
+///     ) {
+///         let public_key: BytesN<65> = env.storage().instance().get(&()).unwrap();
+///         env.crypto()
+///             .secp256r1_verify(&public_key, &signature_payload, &signature);
+///     }
+/// }
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[contractimpl]
+/// impl Contract {
+///     pub fn hello(from: Address) {
+///         from.require_auth();
+///     }
+/// }
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # fn main() {
+///     let env = Env::default();
+///     let signing_key = SigningKey::from_slice(&[1; 32]).unwrap();
+///     let public_key = secp256r1::public_key(&env, signing_key.verifying_key());
+///     let account_id = env.register(Account, (public_key,));
+///     let contract_id = env.register(Contract, ());
+///     let client = ContractClient::new(&env, &contract_id);
+///
+///     let account = CustomAccount::with_key(&account_id, signing_key);
+///     client.sign_with(&[&account]).hello(&account_id);
+/// }
+/// ```
+pub struct CustomAccount<S> {
+    address: Address,
+    signer: S,
+}
+
+impl<S> CustomAccount<S> {
+    /// Creates the signer for the custom account contract at the address,
+    /// signing with the function.
+    pub fn new<Sig>(address: &Address, sign: S) -> Self
+    where
+        S: Fn(&CheckAuthPayload) -> Sig,
+        Sig: IntoVal<Env, Val>,
+    {
+        Self {
+            address: address.clone(),
+            signer: sign,
+        }
+    }
+
+    /// Creates the signer for the custom account contract at the address,
+    /// signing with the key.
+    pub fn with_key(address: &Address, key: S) -> Self
+    where
+        S: Sign<CheckAuthPayload>,
+    {
+        Self {
+            address: address.clone(),
+            signer: key,
+        }
+    }
+}
+
+impl<S, Sig, E> AuthSigner for CustomAccount<S>
+where
+    S: Sign<CheckAuthPayload, Signature = Sig, Error = E>,
+    Sig: IntoVal<Env, Val>,
+    E: core::fmt::Debug,
+{
+    fn address(&self) -> Address {
+        self.address.clone()
+    }
+
+    fn sign_payload(&self, payload: &CheckAuthPayload) -> Val {
+        let signature = self.signer.sign(payload.clone()).unwrap();
+        signature.into_val(self.address.env())
+    }
+}
+
+/// Functions that receive the payload and return the value of the `signature`
+/// argument of `__check_auth` sign for a [`CustomAccount`].
+impl<F, Sig> Sign<CheckAuthPayload> for F
+where
+    F: Fn(&CheckAuthPayload) -> Sig,
+{
+    type Error = core::convert::Infallible;
+    type Signature = Sig;
+    fn sign(&self, m: CheckAuthPayload) -> Result<Self::Signature, Self::Error> {
+        Ok(self(&m))
+    }
+}
+
+/// Returns the authorization entries, signed by the signers, that authorize
+/// the invocation of the contract function with the arguments.
+///
+/// The invocation is first performed in a fork of the [`Env`] with all auths
+/// mocked, to record the authorizations it requires without affecting the
+/// [`Env`]. An entry is then signed for each recorded authorization of an
+/// address that has a signer. Authorizations required by addresses without a
+/// signer are left out, and so fail when the entries are used.
+///
+/// Pass the entries to [`Env::set_auths`] to perform the invocation in
+/// enforcing auth mode. The `sign_with` function of generated contract
+/// clients does this for a single invocation.
+pub fn signed_auths(
+    env: &Env,
+    contract: &Address,
+    fn_name: &Symbol,
+    args: &Vec<Val>,
+    signers: &[&dyn AuthSigner],
+) -> std::vec::Vec<xdr::SorobanAuthorizationEntry> {
+    let fork = env.fork();
+    let fork_args = Vec::<Val>::try_from_val(
+        &fork,
+        &xdr::ScVal::try_from_val(env, &args.to_val()).unwrap(),
+    )
+    .unwrap();
+    let fork_contract = Address::try_from_val(&fork, &xdr::ScAddress::from(contract)).unwrap();
+    let fork_fn_name = Symbol::try_from_val(
+        &fork,
+        &xdr::ScVal::try_from_val(env, &fn_name.to_val()).unwrap(),
+    )
+    .unwrap();
+    fork.mock_all_auths_allowing_non_root_auth();
+    // An invocation that fails records no authorizations, and fails again
+    // when it is performed with the entries.
+    let _ = fork.try_invoke_contract::<Val, Error>(&fork_contract, &fork_fn_name, fork_args);
+    let recorded = fork.host().get_authenticated_authorizations().unwrap();
+
+    let signature_expiration_ledger = env.ledger().max_live_until_ledger();
+    let mut entries = std::vec::Vec::new();
+    for signer in signers {
+        let address = signer.address();
+        let sc_address = xdr::ScAddress::from(&address);
+        for (_, invocation) in recorded.iter().filter(|(a, _)| *a == sc_address) {
+            let nonce = env.with_generator(|mut g| g.nonce());
+            let payload = CheckAuthPayload::from_invocation(
+                &address,
+                invocation.clone(),
+                nonce,
+                signature_expiration_ledger,
+            );
+            entries.push(payload.to_auth_entry(signer.sign_payload(&payload)));
+        }
+    }
+    entries
+}
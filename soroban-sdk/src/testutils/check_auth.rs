@@ -0,0 +1,265 @@
+#![cfg(any(test, feature = "testutils"))]
+
+use crate::{
+    auth::{
+        Context, ContractContext, ContractExecutable, CreateContractHostFnContext,
+        CreateContractWithConstructorHostFnContext,
+    },
+    xdr::{self, Limits, WriteXdr},
+    Address, Bytes, BytesN, Env, Error, IntoVal, InvokeError, TryFromVal, Val, Vec,
+};
+
+use super::{AuthorizedFunction, MockAuthInvoke};
+
+/// The payload and auth contexts that the host passes to a custom account's
+/// `__check_auth` function when authorizing an invocation tree.
+///
+/// Use [`CheckAuthPayload::new`] to compute the same signature payload that
+/// the host computes in enforcing auth mode, sign the
+/// [`signature_payload`][CheckAuthPayload::signature_payload] with test keys,
+/// then either invoke `__check_auth` directly with
+/// [`try_invoke_check_auth`][CheckAuthPayload::try_invoke_check_auth], or
+/// build an authorization entry for [`Env::set_auths`] with
+/// [`to_auth_entry`][CheckAuthPayload::to_auth_entry].
+///
+/// ### Examples
+/// ```
+/// use soroban_sdk::{
+///     auth::Context, contract, contractimpl, crypto::Hash,
+///     testutils::{CheckAuthPayload, MockAuthInvoke},
+///     Env, IntoVal, Vec,
+/// };
+///
+/// #[contract]
+/// pub struct Account;
+///
+/// #[contractimpl]
+/// impl Account {
+///     #[allow(non_snake_case)]
+///     pub fn __check_auth(
+///         _env: Env,
+///         _signature_payload: Hash<32>,
+///         _signature: (),
+///         auth_contexts: Vec<Context>,
+///     ) {
+///         assert_eq!(auth_contexts.len(), 1);
+///     }
+/// }
+///
+/// #[contract]
+/// pub struct Contract;
+///
+/// #[test]
+/// fn test() {
+/// # }
+/// # fn main() {
+///     let env = Env::default();
+///     let account_id = env.register(Account, ());
+///     let contract_id = env.register(Contract, ());
+///
+///     let payload = CheckAuthPayload::new(
+///         &account_id,
+///         &MockAuthInvoke {
+///             contract: &contract_id,
+///             fn_name: "transfer",
+///             args: (1_i128,).into_val(&env),
+///             sub_invokes: &[],
+///         },
+///     );
+///     assert_eq!(
+///         payload.try_invoke_check_auth::<soroban_sdk::Error>(().into_val(&env)),
+///         Ok(())
+///     );
+/// }
+/// ```
+#[derive(Clone)]
+pub struct CheckAuthPayload {
+    env: Env,
+    address: Address,
+    nonce: i64,
+    signature_expiration_ledger: u32,
+    invocation: xdr::SorobanAuthorizedInvocation,
+    signature_payload: BytesN<32>,
+    auth_contexts: Vec<Context>,
+}
+
+impl CheckAuthPayload {
+    /// Computes the payload for `address` authorizing the `invoke` tree.
+    ///
+    /// A new nonce is generated, and the signature expires at the maximum
+    /// live until ledger, matching the authorizations built by
+    /// [`MockAuth`][super::MockAuth].
+    pub fn new(address: &Address, invoke: &MockAuthInvoke) -> Self {
+        let env = address.env();
+        let nonce = env.with_generator(|mut g| g.nonce());
+        let signature_expiration_ledger = env.ledger().max_live_until_ledger();
+        Self::new_with_nonce(address, invoke, nonce, signature_expiration_ledger)
+    }
+
+    /// Computes the payload for `address` authorizing the `invoke` tree using
+    /// the given nonce and signature expiration ledger.
+    pub fn new_with_nonce(
+        address: &Address,
+        invoke: &MockAuthInvoke,
+        nonce: i64,
+        signature_expiration_ledger: u32,
+    ) -> Self {
+        Self::from_invocation(address, invoke.into(), nonce, signature_expiration_ledger)
+    }
+
+    /// Computes the payload for `address` authorizing the XDR invocation
+    /// tree.
+    pub(crate) fn from_invocation(
+        address: &Address,
+        invocation: xdr::SorobanAuthorizedInvocation,
+        nonce: i64,
+        signature_expiration_ledger: u32,
+    ) -> Self {
+        let env = address.env().clone();
+
+        let preimage =
+            xdr::HashIdPreimage::SorobanAuthorization(xdr::HashIdPreimageSorobanAuthorization {
+                network_id: xdr::Hash(env.ledger().network_id().to_array()),
+                nonce,
+                signature_expiration_ledger,
+                invocation: invocation.clone(),
+            });
+        let preimage = Bytes::from_slice(&env, &preimage.to_xdr(Limits::none()).unwrap());
+        let signature_payload = env.crypto().sha256(&preimage).to_bytes();
+
+        let mut auth_contexts = Vec::new(&env);
+        push_auth_contexts(&env, &invocation, &mut auth_contexts);
+
+        Self {
+            env,
+            address: address.clone(),
+            nonce,
+            signature_expiration_ledger,
+            invocation,
+            signature_payload,
+            auth_contexts,
+        }
+    }
+
+    /// Returns the address being authorized.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Returns the nonce included in the payload.
+    pub fn nonce(&self) -> i64 {
+        self.nonce
+    }
+
+    /// Returns the ledger the signature expires at.
+    pub fn signature_expiration_ledger(&self) -> u32 {
+        self.signature_expiration_ledger
+    }
+
+    /// Returns the hash that signers sign, and that is passed as the
+    /// `signature_payload` argument of `__check_auth`.
+    pub fn signature_payload(&self) -> BytesN<32> {
+        self.signature_payload.clone()
+    }
+
+    /// Returns the auth contexts passed as the `auth_contexts` argument of
+    /// `__check_auth`.
+    ///
+    /// The contexts are in the order the host provides them, a depth-first
+    /// traversal of the invocation tree.
+    pub fn auth_contexts(&self) -> Vec<Context> {
+        self.auth_contexts.clone()
+    }
+
+    /// Invokes `__check_auth` of the custom account contract at the
+    /// payload's address with the payload, the given signature, and the auth
+    /// contexts.
+    ///
+    /// See [`Env::try_invoke_contract_check_auth`] for the meaning of the
+    /// error type.
+    pub fn try_invoke_check_auth<E>(&self, signature: Val) -> Result<(), Result<E, InvokeError>>
+    where
+        E: TryFrom<Error>,
+        E::Error: Into<InvokeError>,
+    {
+        self.env.try_invoke_contract_check_auth(
+            &self.address,
+            &self.signature_payload,
+            signature,
+            &self.auth_contexts,
+        )
+    }
+
+    /// Builds an authorization entry carrying the signature, for use with
+    /// [`Env::set_auths`].
+    pub fn to_auth_entry(
+        &self,
+        signature: impl IntoVal<Env, Val>,
+    ) -> xdr::SorobanAuthorizationEntry {
+        let signature: Val = signature.into_val(&self.env);
+        xdr::SorobanAuthorizationEntry {
+            root_invocation: self.invocation.clone(),
+            credentials: xdr::SorobanCredentials::Address(xdr::SorobanAddressCredentials {
+                address: (&self.address).into(),
+                nonce: self.nonce,
+                signature_expiration_ledger: self.signature_expiration_ledger,
+                signature: xdr::ScVal::try_from_val(&self.env, &signature).unwrap(),
+            }),
+        }
+    }
+}
+
+fn push_auth_contexts(
+    env: &Env,
+    invocation: &xdr::SorobanAuthorizedInvocation,
+    out: &mut Vec<Context>,
+) {
+    let context = match AuthorizedFunction::from_xdr(env, &invocation.function) {
+        AuthorizedFunction::Contract((contract, fn_name, args)) => {
+            Context::Contract(ContractContext {
+                contract,
+                fn_name,
+                args,
+            })
+        }
+        AuthorizedFunction::CreateContractHostFn(args) => {
+            let (executable, salt) =
+                create_contract_context(env, &args.executable, &args.contract_id_preimage);
+            Context::CreateContractHostFn(CreateContractHostFnContext { executable, salt })
+        }
+        AuthorizedFunction::CreateContractV2HostFn(args) => {
+            let (executable, salt) =
+                create_contract_context(env, &args.executable, &args.contract_id_preimage);
+            let mut constructor_args = Vec::new(env);
+            for arg in args.constructor_args.iter() {
+                constructor_args.push_back(Val::try_from_val(env, arg).unwrap());
+            }
+            Context::CreateContractWithCtorHostFn(CreateContractWithConstructorHostFnContext {
+                executable,
+                salt,
+                constructor_args,
+            })
+        }
+    };
+    out.push_back(context);
+    for sub_invocation in invocation.sub_invocations.iter() {
+        push_auth_contexts(env, sub_invocation, out);
+    }
+}
+
+fn create_contract_context(
+    env: &Env,
+    executable: &xdr::ContractExecutable,
+    preimage: &xdr::ContractIdPreimage,
+) -> (ContractExecutable, BytesN<32>) {
+    let xdr::ContractExecutable::Wasm(wasm_hash) = executable else {
+        panic!("only contracts with wasm executables are authorized by addresses");
+    };
+    let xdr::ContractIdPreimage::Address(preimage) = preimage else {
+        panic!("only contracts deployed by addresses are authorized by addresses");
+    };
+    (
+        ContractExecutable::Wasm(BytesN::from_array(env, &wasm_hash.0)),
+        BytesN::from_array(env, &preimage.salt.0),
+    )
+}
@@ -1,6 +1,17 @@
-use soroban_env_host::{fees::FeeConfiguration, FeeEstimate, InvocationResources};
+use core::fmt;
 
-use crate::{testutils::budget::Budget, Env};
+use soroban_env_host::{
+    fees::{compute_rent_fee, FeeConfiguration, LedgerEntryRentChange, RentFeeConfiguration},
+    FeeEstimate, InvocationResources,
+};
+
+use crate::{
+    env::internal::{AddressObject, Symbol as SymbolVal},
+    testutils::{budget::Budget, profile::Profile},
+    unwrap::UnwrapInfallible,
+    xdr::{self, ContractDataDurability},
+    Address, Env, Symbol, TryFromVal,
+};
 
 pub struct CostEstimate {
     env: Env,
@@ -35,7 +46,8 @@ impl CostEstimate {
     /// resources returned by `resources()`.
     ///
     /// The fees are computed using the snapshot of the Stellar Pubnet fees made
-    /// on 2024-12-11.
+    /// on 2024-12-11, see [`FeeConfig::pubnet`]. Use `fee_with()` to estimate
+    /// the fee with another configuration.
     ///
     /// Take the return value with a grain of salt as both the resource estimate
     /// and the fee rates may be imprecise.
@@ -45,32 +57,93 @@ impl CostEstimate {
     /// VM instantiation and execution, as well as Wasm reads/rent bumps will be
     /// missed.    
     pub fn fee(&self) -> FeeEstimate {
-        // This is a snapshot of the fees as of 2024-12-11 with slight
-        // adjustments for p23.
-        // This has to be updated before p23 goes live with the configuration
-        // used at the network upgrade time.
-        let pubnet_fee_config = FeeConfiguration {
-            fee_per_instruction_increment: 25,
-            fee_per_disk_read_entry: 6250,
-            fee_per_write_entry: 10000,
-            fee_per_disk_read_1kb: 1786,
-            fee_per_write_1kb: 3500,
-            fee_per_historical_1kb: 16235,
-            fee_per_contract_event_1kb: 10000,
-            fee_per_transaction_size_1kb: 1624,
+        self.fee_with(&FeeConfig::pubnet())
+    }
+
+    /// Estimates the fee for the last invocation's resources, i.e. the
+    /// resources returned by `resources()`, using the fee configuration of a
+    /// network.
+    ///
+    /// The same caveats as for `fee()` apply.
+    pub fn fee_with(&self, config: &FeeConfig) -> FeeEstimate {
+        config.estimate_fee(&self.resources())
+    }
+
+    /// Returns the resources of the last invocation alongside the fee
+    /// estimated for each of them using the fee configuration of a network.
+    ///
+    /// The breakdown is formatted as a table by its `Display` implementation.
+    pub fn fee_breakdown_with(&self, config: &FeeConfig) -> FeeBreakdown {
+        let resources = self.resources();
+        let fee = config.estimate_fee(&resources);
+        FeeBreakdown { resources, fee }
+    }
+
+    /// Measures the resources and fees of the top level contract invocations
+    /// made during `f`, and returns them with the value returned by `f`.
+    ///
+    /// Unlike `resources()`/`fee()`, which only cover the last top level
+    /// invocation, the measurement covers every invocation made through the
+    /// [Env] during `f`, such as with a contract client, and does not require
+    /// any reset. The resources of the invocations are summed, and so are
+    /// their fees, each estimated as in `fee()`.
+    ///
+    /// Contracts registered during `f` are not measured, and neither are the
+    /// invocations that the environment makes to construct them.
+    ///
+    /// Measurements may be nested, in which case the invocations are measured
+    /// by each of the enclosing measurements.
+    ///
+    /// The same caveats as for `resources()` apply, the returned value is as
+    /// useful as the preceding setup.
+    ///
+    /// ### Examples
+    /// ```
+    /// use soroban_sdk::{assert_cpu_below, contract, contractimpl, Env};
+    ///
+    /// #[contract]
+    /// pub struct Contract;
+    ///
+    /// #[contractimpl]
+    /// impl Contract {
+    ///     pub fn add(a: u64, b: u64) -> u64 {
+    ///         a + b
+    ///     }
+    /// }
+    ///
+    /// #[test]
+    /// fn test() {
+    /// # }
+    /// # fn main() {
+    ///     let env = Env::default();
+    ///     let contract_id = env.register(Contract, ());
+    ///     let client = ContractClient::new(&env, &contract_id);
+    ///
+    ///     let measurement = env.cost_estimate().measure(|| {
+    ///         client.add(&1, &2) + client.add(&3, &4)
+    ///     });
+    ///     assert_eq!(measurement.value, 10);
+    ///     assert_eq!(measurement.invocations, 2);
+    ///     assert_cpu_below!(measurement, 1_000_000);
+    /// }
+    /// ```
+    pub fn measure<T>(&self, f: impl FnOnce() -> T) -> Measurement<T> {
+        let measurements = self.env.measurements();
+        let depth = {
+            let mut measurements = measurements.borrow_mut();
+            measurements.push(Measurement::default());
+            measurements.len() - 1
         };
-        let pubnet_persistent_rent_rate_denominator = 2103;
-        let pubnet_temp_rent_rate_denominator = 4206;
-        // This is a bit higher than the current network fee, it's an
-        // overestimate for the sake of providing a bit more conservative
-        // results in case if the state grows.
-        let fee_per_rent_1kb = 12000;
-        self.resources().estimate_fees(
-            &pubnet_fee_config,
-            fee_per_rent_1kb,
-            pubnet_persistent_rent_rate_denominator,
-            pubnet_temp_rent_rate_denominator,
-        )
+        let value = f();
+        // Measurements left by a panic in a nested measurement are discarded
+        // with this one.
+        let measurement = measurements.borrow_mut().drain(depth..).next().unwrap();
+        Measurement {
+            value,
+            invocations: measurement.invocations,
+            resources: measurement.resources,
+            fee: measurement.fee,
+        }
     }
 
     /// Returns the budget object that provides the detailed CPU and memory
@@ -89,4 +162,457 @@ impl CostEstimate {
     pub fn budget(&self) -> Budget {
         Budget::new(self.env.host().budget_cloned())
     }
+
+    /// Returns the profile of the cost of the contract invocations made in the
+    /// [Env], attributed to the cost types metered in each invocation.
+    ///
+    /// The profile is recorded when
+    /// [`EnvTestOptions::capture_profile`][crate::testutils::EnvTestOptions::capture_profile]
+    /// is enabled, and covers every top level invocation made through the [Env]
+    /// since, such as with a contract client. The invocations made through the
+    /// [Env] by natively registered contracts are profiled as frames of their
+    /// own, while the invocations made by Wasm contracts are attributed to the
+    /// invocation that made them.
+    ///
+    /// The profile may be written as folded stacks, which flamegraph tools
+    /// render, or as JSON, and compared before and after an optimization.
+    ///
+    /// ### Examples
+    /// ```
+    /// use soroban_sdk::{
+    ///     contract, contractimpl,
+    ///     testutils::{profile::ProfileMetric, EnvTestConfig, EnvTestOptions},
+    ///     Env,
+    /// };
+    ///
+    /// #[contract]
+    /// pub struct Contract;
+    ///
+    /// #[contractimpl]
+    /// impl Contract {
+    ///     pub fn add(a: u64, b: u64) -> u64 {
+    ///         a + b
+    ///     }
+    /// }
+    ///
+    /// #[test]
+    /// fn test() {
+    /// # }
+    /// # fn main() {
+    ///     let env = Env::new_with_options(
+    ///         EnvTestConfig::default(),
+    ///         EnvTestOptions::default().with_capture_profile(true),
+    ///     );
+    ///     let contract_id = env.register(Contract, ());
+    ///     let client = ContractClient::new(&env, &contract_id);
+    ///
+    ///     client.add(&1, &2);
+    ///
+    ///     let profile = env.cost_estimate().profile();
+    ///     let folded = profile.to_folded(ProfileMetric::CpuInsns);
+    ///     assert!(folded.lines().all(|l| l.contains(".add;")));
+    /// }
+    /// ```
+    pub fn profile(&self) -> Profile {
+        self.env.profiler().borrow().profile()
+    }
+}
+
+/// The resources and fees of the top level contract invocations made during a
+/// measurement, see [`CostEstimate::measure`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Measurement<T = ()> {
+    /// The value returned by the measured function.
+    pub value: T,
+    /// The number of top level contract invocations measured.
+    pub invocations: u32,
+    /// The sum of the resources metered during the invocations.
+    pub resources: InvocationResources,
+    /// The sum of the fees estimated for the invocations.
+    pub fee: FeeEstimate,
+}
+
+impl Measurement {
+    /// Adds the resources and fee of an invocation to the measurement.
+    pub(crate) fn add(&mut self, resources: &InvocationResources, fee: &FeeEstimate) {
+        self.invocations += 1;
+
+        let r = &mut self.resources;
+        r.instructions = r.instructions.saturating_add(resources.instructions);
+        r.mem_bytes = r.mem_bytes.saturating_add(resources.mem_bytes);
+        r.disk_read_entries = r
+            .disk_read_entries
+            .saturating_add(resources.disk_read_entries);
+        r.memory_read_entries = r
+            .memory_read_entries
+            .saturating_add(resources.memory_read_entries);
+        r.write_entries = r.write_entries.saturating_add(resources.write_entries);
+        r.disk_read_bytes = r.disk_read_bytes.saturating_add(resources.disk_read_bytes);
+        r.write_bytes = r.write_bytes.saturating_add(resources.write_bytes);
+        r.contract_events_size_bytes = r
+            .contract_events_size_bytes
+            .saturating_add(resources.contract_events_size_bytes);
+        r.persistent_rent_ledger_bytes = r
+            .persistent_rent_ledger_bytes
+            .saturating_add(resources.persistent_rent_ledger_bytes);
+        r.persistent_entry_rent_bumps = r
+            .persistent_entry_rent_bumps
+            .saturating_add(resources.persistent_entry_rent_bumps);
+        r.temporary_rent_ledger_bytes = r
+            .temporary_rent_ledger_bytes
+            .saturating_add(resources.temporary_rent_ledger_bytes);
+        r.temporary_entry_rent_bumps = r
+            .temporary_entry_rent_bumps
+            .saturating_add(resources.temporary_entry_rent_bumps);
+
+        let f = &mut self.fee;
+        f.total = f.total.saturating_add(fee.total);
+        f.instructions = f.instructions.saturating_add(fee.instructions);
+        f.disk_read_entries = f.disk_read_entries.saturating_add(fee.disk_read_entries);
+        f.write_entries = f.write_entries.saturating_add(fee.write_entries);
+        f.disk_read_bytes = f.disk_read_bytes.saturating_add(fee.disk_read_bytes);
+        f.write_bytes = f.write_bytes.saturating_add(fee.write_bytes);
+        f.contract_events = f.contract_events.saturating_add(fee.contract_events);
+        f.persistent_entry_rent = f
+            .persistent_entry_rent
+            .saturating_add(fee.persistent_entry_rent);
+        f.temporary_entry_rent = f
+            .temporary_entry_rent
+            .saturating_add(fee.temporary_entry_rent);
+    }
+}
+
+/// Estimates the fee for the resources of an invocation, see
+/// [`CostEstimate::fee`].
+pub(crate) fn estimate_fee(resources: &InvocationResources) -> FeeEstimate {
+    FeeConfig::pubnet().estimate_fee(resources)
+}
+
+/// The fee related configuration of a network, used to estimate fees from
+/// resources.
+///
+/// The configuration of a network is set in its ledger, and may be modelled
+/// with the preset for the Stellar Pubnet, or with custom values.
+///
+/// There is no preset for the Stellar Testnet. Its settings are voted on
+/// separately from the Pubnet's and differ from them over time. Model the
+/// Testnet with custom values taken from the `ConfigSetting` entries of its
+/// ledger.
+///
+/// ### Examples
+/// ```
+/// use soroban_sdk::testutils::cost_estimate::FeeConfig;
+///
+/// // Model a doubling of the fee for instructions.
+/// let pubnet = FeeConfig::pubnet();
+/// let config = FeeConfig {
+///     fee_per_instruction_increment: pubnet.fee_per_instruction_increment * 2,
+///     ..pubnet
+/// };
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Fee per 10000 instructions.
+    pub fee_per_instruction_increment: i64,
+    /// Fee per entry read from disk.
+    pub fee_per_disk_read_entry: i64,
+    /// Fee per entry written to the ledger.
+    pub fee_per_write_entry: i64,
+    /// Fee per 1KB read from disk.
+    pub fee_per_disk_read_1kb: i64,
+    /// Fee per 1KB written to the ledger.
+    pub fee_per_write_1kb: i64,
+    /// Fee per 1KB written to history.
+    pub fee_per_historical_1kb: i64,
+    /// Fee per 1KB of contract events emitted.
+    pub fee_per_contract_event_1kb: i64,
+    /// Fee per 1KB of transaction size.
+    pub fee_per_transaction_size_1kb: i64,
+    /// Fee per 1KB of ledger space rented for
+    /// `persistent_rent_rate_denominator` ledgers.
+    ///
+    /// The network derives this fee from the size of its state.
+    pub fee_per_rent_1kb: i64,
+    /// The number of ledgers that 1KB of persistent ledger space is rented for
+    /// at `fee_per_rent_1kb`.
+    pub persistent_rent_rate_denominator: i64,
+    /// The number of ledgers that 1KB of temporary ledger space is rented for
+    /// at `fee_per_rent_1kb`.
+    pub temporary_rent_rate_denominator: i64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self::pubnet()
+    }
+}
+
+impl FeeConfig {
+    /// The configuration of the Stellar Pubnet.
+    ///
+    /// This is a snapshot of the fees as of 2024-12-11 with slight adjustments
+    /// for p23.
+    pub fn pubnet() -> Self {
+        // This has to be updated before p23 goes live with the configuration
+        // used at the network upgrade time.
+        Self {
+            fee_per_instruction_increment: 25,
+            fee_per_disk_read_entry: 6250,
+            fee_per_write_entry: 10000,
+            fee_per_disk_read_1kb: 1786,
+            fee_per_write_1kb: 3500,
+            fee_per_historical_1kb: 16235,
+            fee_per_contract_event_1kb: 10000,
+            fee_per_transaction_size_1kb: 1624,
+            // This is a bit higher than the current network fee, it's an
+            // overestimate for the sake of providing a bit more conservative
+            // results in case if the state grows.
+            fee_per_rent_1kb: 12000,
+            persistent_rent_rate_denominator: 2103,
+            temporary_rent_rate_denominator: 4206,
+        }
+    }
+
+    /// Estimates the fee for the resources of an invocation.
+    pub fn estimate_fee(&self, resources: &InvocationResources) -> FeeEstimate {
+        resources.estimate_fees(
+            &FeeConfiguration {
+                fee_per_instruction_increment: self.fee_per_instruction_increment,
+                fee_per_disk_read_entry: self.fee_per_disk_read_entry,
+                fee_per_write_entry: self.fee_per_write_entry,
+                fee_per_disk_read_1kb: self.fee_per_disk_read_1kb,
+                fee_per_write_1kb: self.fee_per_write_1kb,
+                fee_per_historical_1kb: self.fee_per_historical_1kb,
+                fee_per_contract_event_1kb: self.fee_per_contract_event_1kb,
+                fee_per_transaction_size_1kb: self.fee_per_transaction_size_1kb,
+            },
+            self.fee_per_rent_1kb,
+            self.persistent_rent_rate_denominator,
+            self.temporary_rent_rate_denominator,
+        )
+    }
+
+    /// Projects the rent fee for extending the TTL of a contract data entry of
+    /// `entry_size_bytes` by `ledgers`, including the fee for writing the
+    /// extended TTL.
+    ///
+    /// The entry size is the size of the XDR of the ledger entry.
+    pub fn rent_fee(
+        &self,
+        durability: ContractDataDurability,
+        entry_size_bytes: u32,
+        ledgers: u32,
+    ) -> i64 {
+        // The rent depends only on the number of ledgers the TTL is extended
+        // by, so the extension is projected from an arbitrary ledger.
+        let current_ledger = 1;
+        compute_rent_fee(
+            &[LedgerEntryRentChange {
+                is_persistent: durability == ContractDataDurability::Persistent,
+                is_code_entry: false,
+                old_size_bytes: entry_size_bytes,
+                new_size_bytes: entry_size_bytes,
+                old_live_until_ledger: current_ledger,
+                new_live_until_ledger: current_ledger.saturating_add(ledgers),
+            }],
+            &RentFeeConfiguration {
+                fee_per_write_1kb: self.fee_per_write_1kb,
+                fee_per_rent_1kb: self.fee_per_rent_1kb,
+                fee_per_write_entry: self.fee_per_write_entry,
+                persistent_rent_rate_denominator: self.persistent_rent_rate_denominator,
+                temporary_rent_rate_denominator: self.temporary_rent_rate_denominator,
+            },
+            current_ledger,
+        )
+    }
+}
+
+/// The resources of an invocation alongside the fee estimated for each of
+/// them, see [`CostEstimate::fee_breakdown_with`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeBreakdown {
+    pub resources: InvocationResources,
+    pub fee: FeeEstimate,
+}
+
+impl fmt::Display for FeeBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = &self.resources;
+        let rows: [(&str, i64, i64); 8] = [
+            ("instructions", r.instructions, self.fee.instructions),
+            (
+                "disk_read_entries",
+                r.disk_read_entries.into(),
+                self.fee.disk_read_entries,
+            ),
+            (
+                "write_entries",
+                r.write_entries.into(),
+                self.fee.write_entries,
+            ),
+            (
+                "disk_read_bytes",
+                r.disk_read_bytes.into(),
+                self.fee.disk_read_bytes,
+            ),
+            ("write_bytes", r.write_bytes.into(), self.fee.write_bytes),
+            (
+                "contract_events_size_bytes",
+                r.contract_events_size_bytes.into(),
+                self.fee.contract_events,
+            ),
+            (
+                "persistent_rent_ledger_bytes",
+                r.persistent_rent_ledger_bytes,
+                self.fee.persistent_entry_rent,
+            ),
+            (
+                "temporary_rent_ledger_bytes",
+                r.temporary_rent_ledger_bytes,
+                self.fee.temporary_entry_rent,
+            ),
+        ];
+        writeln!(f, "{:<30}{:>15}{:>15}", "Resource", "amount", "fee")?;
+        for (resource, amount, fee) in rows {
+            writeln!(f, "{resource:<30}{amount:>15}{fee:>15}")?;
+        }
+        writeln!(f, "{:<30}{:>15}{:>15}", "Total", "", self.fee.total)
+    }
+}
+
+/// The resources metered in the top level contract invocations made through
+/// an [Env], as captured in a test snapshot when
+/// [`EnvTestOptions::capture_cost_in_snapshot`][crate::testutils::EnvTestOptions::capture_cost_in_snapshot]
+/// is enabled.
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CostSnapshot(pub Vec<InvocationResourcesSnapshot>);
+
+/// The resources metered in a top level contract invocation, see
+/// [`CostEstimate::resources`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct InvocationResourcesSnapshot {
+    pub contract: xdr::ScAddress,
+    pub function: xdr::ScSymbol,
+    pub instructions: i64,
+    pub mem_bytes: i64,
+    pub disk_read_entries: u32,
+    pub memory_read_entries: u32,
+    pub write_entries: u32,
+    pub disk_read_bytes: u32,
+    pub write_bytes: u32,
+    pub contract_events_size_bytes: u32,
+    pub persistent_rent_ledger_bytes: i64,
+    pub persistent_entry_rent_bumps: u32,
+    pub temporary_rent_ledger_bytes: i64,
+    pub temporary_entry_rent_bumps: u32,
+}
+
+/// The resources metered in a top level contract invocation made through the
+/// [Env], recorded for [CostSnapshot].
+///
+/// The record holds the objects of the invocation in the host rather than
+/// values that hold an [Env], which would keep the host of the [Env] alive
+/// from within its own test state.
+pub(crate) struct CostRecord {
+    pub(crate) contract: AddressObject,
+    pub(crate) function: SymbolVal,
+    pub(crate) resources: InvocationResources,
+}
+
+impl CostRecord {
+    pub(crate) fn to_snapshot(&self, env: &Env) -> InvocationResourcesSnapshot {
+        let contract = Address::try_from_val(env, &self.contract).unwrap_infallible();
+        let function = Symbol::try_from_val(env, &self.function).unwrap_infallible();
+        let r = &self.resources;
+        InvocationResourcesSnapshot {
+            contract: (&contract).into(),
+            function: xdr::ScSymbol(function.to_string().try_into().unwrap()),
+            instructions: r.instructions,
+            mem_bytes: r.mem_bytes,
+            disk_read_entries: r.disk_read_entries,
+            memory_read_entries: r.memory_read_entries,
+            write_entries: r.write_entries,
+            disk_read_bytes: r.disk_read_bytes,
+            write_bytes: r.write_bytes,
+            contract_events_size_bytes: r.contract_events_size_bytes,
+            persistent_rent_ledger_bytes: r.persistent_rent_ledger_bytes,
+            persistent_entry_rent_bumps: r.persistent_entry_rent_bumps,
+            temporary_rent_ledger_bytes: r.temporary_rent_ledger_bytes,
+            temporary_entry_rent_bumps: r.temporary_entry_rent_bumps,
+        }
+    }
+}
+
+/// Asserts that the CPU instructions of a
+/// [Measurement][crate::testutils::cost_estimate::Measurement] are below a
+/// limit.
+///
+/// Intended to guard against performance regressions, see
+/// [`CostEstimate::measure`][crate::testutils::cost_estimate::CostEstimate::measure].
+/// An optional message may follow the limit, formatted as with [panic!].
+#[macro_export]
+macro_rules! assert_cpu_below {
+    ($measurement:expr, $limit:expr $(,)?) => {
+        $crate::__assert_cost_below!("cpu instructions", $measurement.resources.instructions, $limit)
+    };
+    ($measurement:expr, $limit:expr, $($arg:tt)+) => {
+        $crate::__assert_cost_below!("cpu instructions", $measurement.resources.instructions, $limit, $($arg)+)
+    };
+}
+
+/// Asserts that the memory bytes of a
+/// [Measurement][crate::testutils::cost_estimate::Measurement] are below a
+/// limit.
+///
+/// See [`assert_cpu_below!`].
+#[macro_export]
+macro_rules! assert_mem_below {
+    ($measurement:expr, $limit:expr $(,)?) => {
+        $crate::__assert_cost_below!("memory bytes", $measurement.resources.mem_bytes, $limit)
+    };
+    ($measurement:expr, $limit:expr, $($arg:tt)+) => {
+        $crate::__assert_cost_below!("memory bytes", $measurement.resources.mem_bytes, $limit, $($arg)+)
+    };
+}
+
+/// Asserts that the total estimated fee of a
+/// [Measurement][crate::testutils::cost_estimate::Measurement] is below a
+/// limit, in stroops.
+///
+/// See [`assert_cpu_below!`].
+#[macro_export]
+macro_rules! assert_fee_below {
+    ($measurement:expr, $limit:expr $(,)?) => {
+        $crate::__assert_cost_below!("fee", $measurement.fee.total, $limit)
+    };
+    ($measurement:expr, $limit:expr, $($arg:tt)+) => {
+        $crate::__assert_cost_below!("fee", $measurement.fee.total, $limit, $($arg)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_cost_below {
+    ($name:literal, $value:expr, $limit:expr $(,)?) => {{
+        let value: i64 = $value;
+        let limit: i64 = $limit;
+        if value >= limit {
+            panic!("{} {} is not below the limit {}", $name, value, limit);
+        }
+    }};
+    ($name:literal, $value:expr, $limit:expr, $($arg:tt)+) => {{
+        let value: i64 = $value;
+        let limit: i64 = $limit;
+        if value >= limit {
+            panic!(
+                "{} {} is not below the limit {}: {}",
+                $name,
+                value,
+                limit,
+                format_args!($($arg)+)
+            );
+        }
+    }};
 }
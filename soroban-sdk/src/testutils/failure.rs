@@ -0,0 +1,345 @@
+//! Reports of failed contract invocations.
+//!
+//! See [`Env::last_failure`][crate::Env::last_failure].
+
+use core::fmt;
+use std::{
+    any::Any,
+    cell::RefCell,
+    panic::{set_hook, take_hook},
+    string::{String, ToString},
+    sync::Once,
+    vec::Vec,
+};
+
+use crate::{
+    xdr::{self, ContractEventBody, ContractEventType, ScVal},
+    Address, Env, Error, TryFromVal,
+};
+
+/// A report of a failed top level contract invocation.
+///
+/// The report is captured by the [Env] when an invocation made with
+/// [`Env::try_invoke_contract`][crate::Env::try_invoke_contract], or with the
+/// `try_` functions of a contract client, fails. Invocations that are not
+/// expected to fail print the report before panicking.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailureReport {
+    /// The contract that was invoked.
+    pub contract: Address,
+    /// The function that was invoked.
+    pub function: String,
+    /// The arguments the function was invoked with.
+    pub args: Vec<ScVal>,
+    /// The error the invocation failed with.
+    pub error: Error,
+    /// The panic of the contract that failed, if the contract is registered
+    /// natively and failed by panicking.
+    pub panic: Option<ContractPanic>,
+    /// The contract calls in progress when the failure occurred, starting at
+    /// the invoked contract and ending at the contract that failed.
+    pub call_stack: Vec<CallFrame>,
+    /// The last diagnostic events recorded before the failure, up to the
+    /// number configured with
+    /// [`EnvTestOptions::failure_report_events`][crate::testutils::EnvTestOptions::failure_report_events].
+    pub events: Vec<String>,
+}
+
+/// A panic of a natively registered contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractPanic {
+    /// The contract that panicked.
+    pub contract: Address,
+    /// The function of the contract that panicked.
+    pub function: String,
+    /// The message of the panic.
+    pub message: String,
+    /// The source location of the panic, formatted as `file:line:column`.
+    ///
+    /// For contracts that fail with an error, such as with
+    /// [`Env::panic_with_error`][crate::Env::panic_with_error], the panic
+    /// occurs in the environment, and the location is in the environment.
+    pub location: Option<String>,
+}
+
+/// A contract call in progress.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallFrame {
+    /// The contract that was called.
+    pub contract: Address,
+    /// The function of the contract that was called.
+    pub function: String,
+}
+
+impl fmt::Display for FailureReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "contract invocation failed: {:?}", self.error)?;
+        writeln!(f, "  contract: {:?}", self.contract)?;
+        writeln!(f, "  function: {}", self.function)?;
+        writeln!(f, "  args: {:?}", self.args)?;
+        if let Some(panic) = &self.panic {
+            let message = panic.message.lines().next().unwrap_or_default();
+            write!(f, "  panic: '{message}'")?;
+            if let Some(location) = &panic.location {
+                write!(f, " at {location}")?;
+            }
+            writeln!(f)?;
+        }
+        if !self.call_stack.is_empty() {
+            writeln!(f, "  call stack:")?;
+            for (i, frame) in self.call_stack.iter().enumerate() {
+                writeln!(f, "    {i}: {:?} {}", frame.contract, frame.function)?;
+            }
+        }
+        if !self.events.is_empty() {
+            writeln!(f, "  last diagnostic events:")?;
+            for event in &self.events {
+                writeln!(f, "    {event}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The failures captured by an [Env] during the current top level invocation.
+///
+/// The failures are stored with addresses in their XDR form, because values
+/// that hold an [Env], such as [Address], would keep the host of the [Env]
+/// alive from within its own test state.
+#[derive(Default)]
+pub(crate) struct Failures {
+    pub(crate) panics: Vec<PanicRecord>,
+    pub(crate) last: Option<FailureRecord>,
+}
+
+/// A [ContractPanic] as stored in [Failures].
+#[derive(Clone)]
+pub(crate) struct PanicRecord {
+    contract: xdr::ScAddress,
+    function: String,
+    message: String,
+    location: Option<String>,
+}
+
+/// A [FailureReport] as stored in [Failures].
+#[derive(Clone)]
+pub(crate) struct FailureRecord {
+    contract: xdr::ScAddress,
+    function: String,
+    args: Vec<ScVal>,
+    error: Error,
+    panic: Option<PanicRecord>,
+    call_stack: Vec<(xdr::ScAddress, String)>,
+    events: Vec<String>,
+}
+
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that records the location of panics, so that the
+/// location of panics caught from native contracts can be reported.
+///
+/// The hook is installed around the hook of the host, which suppresses the
+/// printing of panics caught from native contracts, so that the location is
+/// recorded regardless of whether the panic is printed.
+pub(crate) fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let _ = soroban_env_host::testutils::call_with_suppressed_panic_hook(|| ());
+        let hook = take_hook();
+        set_hook(std::boxed::Box::new(move |info| {
+            let location = info.location().map(ToString::to_string);
+            PANIC_LOCATION.with(|l| *l.borrow_mut() = location);
+            hook(info)
+        }));
+    });
+}
+
+impl PanicRecord {
+    pub(crate) fn new(contract: &Address, function: &str, payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+        PanicRecord {
+            contract: contract.into(),
+            function: function.to_string(),
+            message,
+            location: PANIC_LOCATION.with(|l| l.borrow_mut().take()),
+        }
+    }
+}
+
+impl PanicRecord {
+    fn to_panic(&self, env: &Env) -> ContractPanic {
+        ContractPanic {
+            contract: Address::try_from_val(env, &self.contract).unwrap(),
+            function: self.function.clone(),
+            message: self.message.clone(),
+            location: self.location.clone(),
+        }
+    }
+}
+
+impl FailureRecord {
+    pub(crate) fn to_report(&self, env: &Env) -> FailureReport {
+        let address = |a: &xdr::ScAddress| Address::try_from_val(env, a).unwrap();
+        FailureReport {
+            contract: address(&self.contract),
+            function: self.function.clone(),
+            args: self.args.clone(),
+            error: self.error,
+            panic: self.panic.as_ref().map(|p| p.to_panic(env)),
+            call_stack: self
+                .call_stack
+                .iter()
+                .map(|(contract, function)| CallFrame {
+                    contract: address(contract),
+                    function: function.clone(),
+                })
+                .collect(),
+            events: self.events.clone(),
+        }
+    }
+
+    /// Captures the report of an invocation that failed with a panic, taking
+    /// the error from the diagnostic events.
+    pub(crate) fn capture_from_events(
+        env: &Env,
+        contract: &Address,
+        function: &str,
+        args: &crate::Vec<crate::Val>,
+    ) -> Self {
+        let events = env.host().get_diagnostic_events().unwrap().0;
+        let error = last_call_failure(&events).unwrap_or_else(|| {
+            Error::from_type_and_code(xdr::ScErrorType::Context, xdr::ScErrorCode::InternalError)
+        });
+        Self::capture(env, contract, function, args, error)
+    }
+
+    pub(crate) fn capture(
+        env: &Env,
+        contract: &Address,
+        function: &str,
+        args: &crate::Vec<crate::Val>,
+        error: Error,
+    ) -> Self {
+        let events = env.host().get_diagnostic_events().unwrap().0;
+        let call_stack = call_stack(&events);
+        let panic = call_stack.last().and_then(|(contract, function)| {
+            let failures = env.failures().borrow();
+            failures
+                .panics
+                .iter()
+                .rev()
+                .find(|p| p.contract == *contract && p.function == *function)
+                .cloned()
+        });
+        let n = env.test_options().failure_report_events;
+        FailureRecord {
+            contract: contract.into(),
+            function: function.to_string(),
+            args: args
+                .iter()
+                .map(|v| ScVal::try_from_val(env, &v).unwrap())
+                .collect(),
+            error,
+            panic,
+            call_stack,
+            events: events[events.len().saturating_sub(n)..]
+                .iter()
+                .map(|e| e.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Returns the error of the last call failure recorded in the diagnostic
+/// events.
+fn last_call_failure(events: &[soroban_env_host::events::HostEvent]) -> Option<Error> {
+    events.iter().rev().find_map(|e| match call_failure(e) {
+        Some((ScVal::Error(error), _)) => Some(error.clone().into()),
+        _ => None,
+    })
+}
+
+/// Returns the error topic of the event if it is a call failure event, an
+/// event that the host records in the calling frame when a contract call
+/// fails, and whether the call was made with `try_call`.
+pub(crate) fn call_failure(e: &soroban_env_host::events::HostEvent) -> Option<(&ScVal, bool)> {
+    let (ContractEventType::Diagnostic, ContractEventBody::V0(ce)) =
+        (&e.event.type_, &e.event.body)
+    else {
+        return None;
+    };
+    let [ScVal::Symbol(s), error @ ScVal::Error(_)] = ce.topics.as_slice() else {
+        return None;
+    };
+    let msg = match &ce.data {
+        ScVal::Vec(Some(v)) => v.first(),
+        v => Some(v),
+    };
+    match msg {
+        Some(ScVal::String(m)) if s.as_slice() == b"error" => match m.as_slice() {
+            b"contract call failed" => Some((error, false)),
+            b"contract try_call failed" => Some((error, true)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Reconstructs the contract calls in progress at the last failure that was
+/// not handled, from the `fn_call`, `fn_return`, and call failure diagnostic
+/// events of the host.
+///
+/// A failure is followed by a call failure event in each of the calling
+/// frames it propagates through, and the stack is captured at the first of
+/// those events. A failure of a call made with `try_call` from a contract is
+/// handled by the contract, and does not propagate further.
+fn call_stack(events: &[soroban_env_host::events::HostEvent]) -> Vec<(xdr::ScAddress, String)> {
+    let mut stack = Vec::<(xdr::ScAddress, String)>::new();
+    let mut failed_stack = Vec::<(xdr::ScAddress, String)>::new();
+    let mut propagating = false;
+    for e in events {
+        let (ContractEventType::Diagnostic, ContractEventBody::V0(ce)) =
+            (&e.event.type_, &e.event.body)
+        else {
+            continue;
+        };
+        match ce.topics.as_slice() {
+            [ScVal::Symbol(s), ScVal::Bytes(id), ScVal::Symbol(function)]
+                if s.as_slice() == b"fn_call" =>
+            {
+                let Ok(id) = <[u8; 32]>::try_from(id.as_slice()) else {
+                    continue;
+                };
+                let contract = xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash(id)));
+                stack.push((contract, function.to_utf8_string_lossy()));
+                propagating = false;
+            }
+            [ScVal::Symbol(s), ..] if s.as_slice() == b"fn_return" => {
+                stack.pop();
+                propagating = false;
+            }
+            _ => {
+                let Some((_, try_call)) = call_failure(e) else {
+                    continue;
+                };
+                if !propagating {
+                    failed_stack = stack.clone();
+                    propagating = true;
+                }
+                stack.pop();
+                if try_call && !stack.is_empty() {
+                    propagating = false;
+                }
+            }
+        }
+    }
+    failed_stack
+}
@@ -0,0 +1,200 @@
+//! Cost profiles of the contract invocations made in an [Env][crate::Env].
+//!
+//! See [`CostEstimate::profile`][crate::testutils::cost_estimate::CostEstimate::profile].
+
+use std::{collections::BTreeMap, fs::File, io, path::Path, string::String, vec::Vec};
+
+use crate::{env::internal::budget::Budget, xdr::ContractCostType, Address, Symbol};
+
+/// The cost of the contract invocations made in an [Env][crate::Env], attributed to the
+/// cost types metered in each invocation.
+///
+/// Each stack is a path of invocations, starting at a top level invocation,
+/// and the cost type metered in the last invocation of the path, excluding
+/// the cost metered in the invocations it made.
+///
+/// The profile is formatted as folded stacks with [`Profile::to_folded`],
+/// which flamegraph tools such as `inferno` and `flamegraph.pl` render, and
+/// can be serialized to JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Profile {
+    pub stacks: Vec<ProfileStack>,
+}
+
+/// The cost of a cost type metered in a path of invocations, see [Profile].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ProfileStack {
+    /// The invocations, each formatted as `{contract}.{function}`.
+    pub frames: Vec<String>,
+    /// The cost type, named as in [`ContractCostType`].
+    pub cost_type: String,
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+}
+
+/// The cost dimension that a folded stack profile measures.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProfileMetric {
+    #[default]
+    CpuInsns,
+    MemBytes,
+}
+
+impl Profile {
+    /// Formats the profile as folded stacks, one line per stack of the form
+    /// `frame;frame;cost_type value`, omitting stacks with no cost.
+    pub fn to_folded(&self, metric: ProfileMetric) -> String {
+        let mut folded = String::new();
+        for stack in &self.stacks {
+            let value = match metric {
+                ProfileMetric::CpuInsns => stack.cpu_insns,
+                ProfileMetric::MemBytes => stack.mem_bytes,
+            };
+            if value == 0 {
+                continue;
+            }
+            for frame in &stack.frames {
+                folded.push_str(frame);
+                folded.push(';');
+            }
+            folded.push_str(&stack.cost_type);
+            folded.push_str(&std::format!(" {value}\n"));
+        }
+        folded
+    }
+
+    /// Writes the profile as folded stacks to a file, see
+    /// [`Profile::to_folded`].
+    pub fn write_folded(&self, path: impl AsRef<Path>, metric: ProfileMetric) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_folded(metric))
+    }
+
+    /// Writes the profile as JSON to a file.
+    pub fn write_json(&self, path: impl AsRef<Path>) -> Result<(), serde_json::Error> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let file = File::create(path).map_err(serde_json::Error::io)?;
+        serde_json::to_writer_pretty(file, self)
+    }
+}
+
+/// An invocation in progress, with the cost metered by the budget when it
+/// began, and the cost of the invocations it made.
+struct ProfileFrame {
+    name: String,
+    start: Vec<(u64, u64)>,
+    children: Vec<(u64, u64)>,
+}
+
+/// Records the cost of the invocations made through the [Env][crate::Env], by the test or
+/// by natively registered contracts. The cost of invocations made by Wasm
+/// contracts is attributed to the invocation that made them.
+#[derive(Default)]
+pub(crate) struct Profiler {
+    frames: Vec<ProfileFrame>,
+    costs: BTreeMap<(Vec<String>, String), (u64, u64)>,
+}
+
+impl Profiler {
+    /// Begins an invocation, returning the depth to end it at. The budget is
+    /// reset at the start of a top level invocation, so a top level
+    /// invocation starts with no cost.
+    pub(crate) fn begin(
+        &mut self,
+        budget: &Budget,
+        contract: &Address,
+        function: &Symbol,
+        top_level: bool,
+    ) -> usize {
+        if top_level {
+            self.frames.clear();
+        }
+        let start = if top_level {
+            std::vec![(0, 0); ContractCostType::variants().len()]
+        } else {
+            trackers(budget)
+        };
+        self.frames.push(ProfileFrame {
+            name: std::format!("{contract:?}.{}", function.to_string()),
+            children: std::vec![(0, 0); start.len()],
+            start,
+        });
+        self.frames.len() - 1
+    }
+
+    /// Ends the invocation at `depth`, and the invocations above it that did
+    /// not end because of a panic.
+    pub(crate) fn end(&mut self, budget: &Budget, depth: usize) {
+        if depth >= self.frames.len() {
+            return;
+        }
+        let end = trackers(budget);
+        while self.frames.len() > depth {
+            let frame = self.frames.pop().unwrap();
+            let path = self
+                .frames
+                .iter()
+                .map(|f| f.name.clone())
+                .chain([frame.name])
+                .collect::<Vec<_>>();
+            for (i, ty) in ContractCostType::variants().iter().enumerate() {
+                let total = (
+                    end[i].0.saturating_sub(frame.start[i].0),
+                    end[i].1.saturating_sub(frame.start[i].1),
+                );
+                let own = (
+                    total.0.saturating_sub(frame.children[i].0),
+                    total.1.saturating_sub(frame.children[i].1),
+                );
+                if let Some(parent) = self.frames.last_mut() {
+                    parent.children[i].0 += total.0;
+                    parent.children[i].1 += total.1;
+                }
+                if own == (0, 0) {
+                    continue;
+                }
+                let cost = self
+                    .costs
+                    .entry((path.clone(), ty.name().into()))
+                    .or_default();
+                cost.0 += own.0;
+                cost.1 += own.1;
+            }
+        }
+    }
+
+    pub(crate) fn profile(&self) -> Profile {
+        Profile {
+            stacks: self
+                .costs
+                .iter()
+                .map(
+                    |((frames, cost_type), (cpu_insns, mem_bytes))| ProfileStack {
+                        frames: frames.clone(),
+                        cost_type: cost_type.clone(),
+                        cpu_insns: *cpu_insns,
+                        mem_bytes: *mem_bytes,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+fn trackers(budget: &Budget) -> Vec<(u64, u64)> {
+    ContractCostType::variants()
+        .iter()
+        .map(|ty| {
+            let tracker = budget.get_tracker(*ty).unwrap();
+            (tracker.cpu, tracker.mem)
+        })
+        .collect()
+}
@@ -9,11 +9,18 @@ pub trait Sign<MSG> {
     fn sign(&self, m: MSG) -> Result<Self::Signature, Self::Error>;
 }
 
-// TODO: Add a Verify interface and ed25519 implementation to counter the Sign
-// interface.
+/// Verify implementations verify signatures for types that can be represented
+/// as the MSG.
+pub trait Verify<MSG> {
+    type Signature;
+    type Error;
+    /// Verify checks that the signature was produced for the MSG by the signer
+    /// of the key.
+    fn verify(&self, m: MSG, s: &Self::Signature) -> Result<(), Self::Error>;
+}
 
 pub mod ed25519 {
-    use crate::xdr;
+    use crate::{testutils::CheckAuthPayload, xdr, BytesN};
     use xdr::{Limited, Limits, WriteXdr};
 
     #[derive(Debug)]
@@ -55,7 +62,7 @@ pub mod ed25519 {
         }
     }
 
-    pub use super::Sign;
+    pub use super::{Sign, Verify};
 
     impl<S, M> Sign<M> for S
     where
@@ -73,9 +80,41 @@ pub mod ed25519 {
         }
     }
 
+    /// Signs the signature payload of an authorization, as the host verifies
+    /// the signatures of Stellar accounts.
+    impl Sign<CheckAuthPayload> for ed25519_dalek::SigningKey {
+        type Error = ed25519_dalek::SignatureError;
+        type Signature = BytesN<64>;
+        fn sign(&self, m: CheckAuthPayload) -> Result<Self::Signature, Self::Error> {
+            let payload = m.signature_payload();
+            let signature = ed25519_dalek::Signer::try_sign(self, &payload.to_array())?;
+            Ok(BytesN::from_array(payload.env(), &signature.to_bytes()))
+        }
+    }
+
+    impl<V, M> Verify<M> for V
+    where
+        V: ed25519_dalek::Verifier<ed25519_dalek::Signature>,
+        M: TryInto<xdr::ScVal>,
+        <M as TryInto<xdr::ScVal>>::Error: std::error::Error,
+    {
+        type Error = Error<<M as TryInto<xdr::ScVal>>::Error>;
+        type Signature = [u8; 64];
+        fn verify(&self, m: M, s: &Self::Signature) -> Result<(), Self::Error> {
+            let mut buf = Vec::<u8>::new();
+            let val: xdr::ScVal = m.try_into().map_err(Self::Error::ConversionError)?;
+            val.write_xdr(&mut Limited::new(&mut buf, Limits::none()))?;
+            Ok(ed25519_dalek::Verifier::verify(
+                self,
+                &buf,
+                &ed25519_dalek::Signature::from_bytes(s),
+            )?)
+        }
+    }
+
     #[cfg(test)]
     mod test {
-        use super::Sign;
+        use super::{Sign, Verify};
         use ed25519_dalek::SigningKey;
 
         #[test]
@@ -93,5 +132,355 @@ pub mod ed25519 {
                 "a9b9dfac10bc1e5c8bc565e9515e5d086e3264b71bf4daf2c7340e1d10fae86e2563fa1d639ff153559a9710dfa270a9462fe87faa0e18a7a54a8a1a6151e909",
             );
         }
+
+        #[test]
+        fn verify() {
+            let sk = SigningKey::from_bytes(&[1; 32]);
+            let vk = sk.verifying_key();
+            let sig = sk.sign(128i64).unwrap();
+            assert!(vk.verify(128i64, &sig).is_ok());
+            assert!(vk.verify(129i64, &sig).is_err());
+        }
+    }
+}
+
+pub mod secp256k1 {
+    //! Signing of message digests with ECDSA secp256k1 keys, producing
+    //! signatures that [`Crypto::secp256k1_recover`] recovers the public key
+    //! from.
+    //!
+    //! [`Crypto::secp256k1_recover`]: crate::crypto::Crypto::secp256k1_recover
+    use crate::{crypto::Hash, testutils::CheckAuthPayload, BytesN, Env};
+    use k256::ecdsa::{RecoveryId, SigningKey, VerifyingKey};
+
+    pub use super::{Sign, Verify};
+    pub use k256::ecdsa::Error;
+
+    /// A recoverable secp256k1 signature.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Signature {
+        /// The 64-byte signature, `r || s`, with `s` normalized to the low
+        /// half of the curve order.
+        pub signature: BytesN<64>,
+        /// The recovery id that identifies the public key of the signer.
+        pub recovery_id: u32,
+    }
+
+    /// Returns the SEC-1 uncompressed encoding of the public key, the format
+    /// [`Crypto::secp256k1_recover`] returns.
+    ///
+    /// [`Crypto::secp256k1_recover`]: crate::crypto::Crypto::secp256k1_recover
+    pub fn public_key(env: &Env, key: &VerifyingKey) -> BytesN<65> {
+        BytesN::from_array(
+            env,
+            key.to_encoded_point(false).as_bytes().try_into().unwrap(),
+        )
+    }
+
+    impl Sign<&Hash<32>> for SigningKey {
+        type Error = Error;
+        type Signature = Signature;
+        fn sign(&self, m: &Hash<32>) -> Result<Self::Signature, Self::Error> {
+            let (signature, recovery_id) = self.sign_prehash_recoverable(&m.to_array())?;
+            Ok(Signature {
+                signature: BytesN::from_array(m.to_bytes().env(), &signature.to_bytes().into()),
+                recovery_id: recovery_id.to_byte().into(),
+            })
+        }
+    }
+
+    /// Signs the signature payload of an authorization.
+    impl Sign<CheckAuthPayload> for SigningKey {
+        type Error = Error;
+        type Signature = Signature;
+        fn sign(&self, m: CheckAuthPayload) -> Result<Self::Signature, Self::Error> {
+            self.sign(&Hash::from_bytes(m.signature_payload()))
+        }
+    }
+
+    impl Verify<&Hash<32>> for VerifyingKey {
+        type Error = Error;
+        type Signature = Signature;
+        fn verify(&self, m: &Hash<32>, s: &Self::Signature) -> Result<(), Self::Error> {
+            let signature = k256::ecdsa::Signature::from_slice(&s.signature.to_array())?;
+            let recovery_id = u8::try_from(s.recovery_id)
+                .ok()
+                .and_then(RecoveryId::from_byte)
+                .ok_or_else(Error::new)?;
+            let recovered =
+                VerifyingKey::recover_from_prehash(&m.to_array(), &signature, recovery_id)?;
+            if &recovered == self {
+                Ok(())
+            } else {
+                Err(Error::new())
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{public_key, Sign, Verify};
+        use crate::{Bytes, Env};
+        use k256::ecdsa::SigningKey;
+
+        #[test]
+        fn sign_and_recover() {
+            let env = Env::default();
+            let sk = SigningKey::from_slice(&[1; 32]).unwrap();
+            let digest = env
+                .crypto()
+                .keccak256(&Bytes::from_slice(&env, b"hello world"));
+            let sig = sk.sign(&digest).unwrap();
+            assert_eq!(
+                env.crypto()
+                    .secp256k1_recover(&digest, &sig.signature, sig.recovery_id),
+                public_key(&env, sk.verifying_key()),
+            );
+            assert!(sk.verifying_key().verify(&digest, &sig).is_ok());
+        }
+
+        #[test]
+        fn verify_rejects_other_message_and_key() {
+            let env = Env::default();
+            let sk = SigningKey::from_slice(&[1; 32]).unwrap();
+            let other_sk = SigningKey::from_slice(&[2; 32]).unwrap();
+            let digest = env.crypto().keccak256(&Bytes::from_slice(&env, b"hello"));
+            let other_digest = env.crypto().keccak256(&Bytes::from_slice(&env, b"world"));
+            let sig = sk.sign(&digest).unwrap();
+            assert!(sk.verifying_key().verify(&other_digest, &sig).is_err());
+            assert!(other_sk.verifying_key().verify(&digest, &sig).is_err());
+        }
+    }
+}
+
+pub mod secp256r1 {
+    //! Signing of message digests with ECDSA secp256r1 keys, producing
+    //! signatures that [`Crypto::secp256r1_verify`] accepts.
+    //!
+    //! [`Crypto::secp256r1_verify`]: crate::crypto::Crypto::secp256r1_verify
+    use crate::{crypto::Hash, testutils::CheckAuthPayload, BytesN, Env};
+    use p256::ecdsa::{
+        signature::hazmat::{PrehashSigner, PrehashVerifier},
+        SigningKey, VerifyingKey,
+    };
+
+    pub use super::{Sign, Verify};
+    pub use p256::ecdsa::Error;
+
+    /// Returns the SEC-1 uncompressed encoding of the public key, the format
+    /// [`Crypto::secp256r1_verify`] accepts.
+    ///
+    /// [`Crypto::secp256r1_verify`]: crate::crypto::Crypto::secp256r1_verify
+    pub fn public_key(env: &Env, key: &VerifyingKey) -> BytesN<65> {
+        BytesN::from_array(
+            env,
+            key.to_encoded_point(false).as_bytes().try_into().unwrap(),
+        )
+    }
+
+    impl Sign<&Hash<32>> for SigningKey {
+        type Error = Error;
+        /// The 64-byte signature, `r || s`, with `s` normalized to the low half
+        /// of the curve order as the host requires.
+        type Signature = BytesN<64>;
+        fn sign(&self, m: &Hash<32>) -> Result<Self::Signature, Self::Error> {
+            let signature: p256::ecdsa::Signature = self.sign_prehash(&m.to_array())?;
+            let signature = signature.normalize_s().unwrap_or(signature);
+            Ok(BytesN::from_array(
+                m.to_bytes().env(),
+                &signature.to_bytes().into(),
+            ))
+        }
+    }
+
+    /// Signs the signature payload of an authorization.
+    impl Sign<CheckAuthPayload> for SigningKey {
+        type Error = Error;
+        type Signature = BytesN<64>;
+        fn sign(&self, m: CheckAuthPayload) -> Result<Self::Signature, Self::Error> {
+            self.sign(&Hash::from_bytes(m.signature_payload()))
+        }
+    }
+
+    impl Verify<&Hash<32>> for VerifyingKey {
+        type Error = Error;
+        type Signature = BytesN<64>;
+        fn verify(&self, m: &Hash<32>, s: &Self::Signature) -> Result<(), Self::Error> {
+            let signature = p256::ecdsa::Signature::from_slice(&s.to_array())?;
+            if signature.normalize_s().is_some() {
+                // The host rejects signatures with a high s.
+                return Err(Error::new());
+            }
+            self.verify_prehash(&m.to_array(), &signature)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{public_key, Sign, Verify};
+        use crate::{Bytes, Env};
+        use p256::ecdsa::SigningKey;
+
+        #[test]
+        fn sign_and_verify() {
+            let env = Env::default();
+            let sk = SigningKey::from_slice(&[1; 32]).unwrap();
+            for msg in [b"hello".as_slice(), b"world", b"hello world"] {
+                let digest = env.crypto().sha256(&Bytes::from_slice(&env, msg));
+                let sig = sk.sign(&digest).unwrap();
+                env.crypto()
+                    .secp256r1_verify(&public_key(&env, sk.verifying_key()), &digest, &sig);
+                assert!(sk.verifying_key().verify(&digest, &sig).is_ok());
+            }
+        }
+
+        #[test]
+        fn verify_rejects_other_message_and_key() {
+            let env = Env::default();
+            let sk = SigningKey::from_slice(&[1; 32]).unwrap();
+            let other_sk = SigningKey::from_slice(&[2; 32]).unwrap();
+            let digest = env.crypto().sha256(&Bytes::from_slice(&env, b"hello"));
+            let other_digest = env.crypto().sha256(&Bytes::from_slice(&env, b"world"));
+            let sig = sk.sign(&digest).unwrap();
+            assert!(sk.verifying_key().verify(&other_digest, &sig).is_err());
+            assert!(other_sk.verifying_key().verify(&digest, &sig).is_err());
+        }
+    }
+}
+
+pub mod bls12_381 {
+    //! BLS signatures over BLS12-381, with public keys in G1 and signatures in
+    //! G2, computed with the host's [`Bls12_381`] functions so that they verify
+    //! with [`Bls12_381::pairing_check`].
+    //!
+    //! Messages are hashed to G2 with [`Bls12_381::hash_to_g2`] and the
+    //! domain separation tag [`DST`].
+    //!
+    //! [`Bls12_381`]: crate::crypto::bls12_381::Bls12_381
+    //! [`Bls12_381::pairing_check`]: crate::crypto::bls12_381::Bls12_381::pairing_check
+    //! [`Bls12_381::hash_to_g2`]: crate::crypto::bls12_381::Bls12_381::hash_to_g2
+    use crate::{
+        crypto::bls12_381::{Fr, G1Affine, G2Affine},
+        vec, Bytes, BytesN, Env,
+    };
+
+    pub use super::{Sign, Verify};
+
+    /// The domain separation tag of the basic scheme of the IETF BLS signature
+    /// draft, used to hash messages to G2.
+    pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    const G1_GENERATOR: [u8; 96] = [
+        0x17, 0xf1, 0xd3, 0xa7, 0x31, 0x97, 0xd7, 0x94, 0x26, 0x95, 0x63, 0x8c, 0x4f, 0xa9, 0xac,
+        0x0f, 0xc3, 0x68, 0x8c, 0x4f, 0x97, 0x74, 0xb9, 0x05, 0xa1, 0x4e, 0x3a, 0x3f, 0x17, 0x1b,
+        0xac, 0x58, 0x6c, 0x55, 0xe8, 0x3f, 0xf9, 0x7a, 0x1a, 0xef, 0xfb, 0x3a, 0xf0, 0x0a, 0xdb,
+        0x22, 0xc6, 0xbb, 0x08, 0xb3, 0xf4, 0x81, 0xe3, 0xaa, 0xa0, 0xf1, 0xa0, 0x9e, 0x30, 0xed,
+        0x74, 0x1d, 0x8a, 0xe4, 0xfc, 0xf5, 0xe0, 0x95, 0xd5, 0xd0, 0x0a, 0xf6, 0x00, 0xdb, 0x18,
+        0xcb, 0x2c, 0x04, 0xb3, 0xed, 0xd0, 0x3c, 0xc7, 0x44, 0xa2, 0x88, 0x8a, 0xe4, 0x0c, 0xaa,
+        0x23, 0x29, 0x46, 0xc5, 0xe7, 0xe1,
+    ];
+
+    /// Error returned when a signature does not verify.
+    #[derive(Debug)]
+    pub struct Error;
+
+    impl std::error::Error for Error {}
+
+    impl std::fmt::Display for Error {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "bls12-381 signature verification failed")
+        }
+    }
+
+    /// A BLS12-381 secret key, a scalar in `Fr`.
+    #[derive(Clone)]
+    pub struct SigningKey([u8; 32]);
+
+    impl SigningKey {
+        /// Creates the key from the big-endian encoding of the secret scalar.
+        pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+            Self(*bytes)
+        }
+
+        /// Returns the big-endian encoding of the secret scalar.
+        pub fn to_bytes(&self) -> [u8; 32] {
+            self.0
+        }
+
+        /// Returns the public key of the secret key, the point in G1 that
+        /// verifies its signatures.
+        pub fn public_key(&self, env: &Env) -> G1Affine {
+            env.crypto()
+                .bls12_381()
+                .g1_mul(&generator(env), &self.scalar(env))
+        }
+
+        fn scalar(&self, env: &Env) -> Fr {
+            Fr::from_bytes(BytesN::from_array(env, &self.0))
+        }
+    }
+
+    fn generator(env: &Env) -> G1Affine {
+        G1Affine::from_array(env, &G1_GENERATOR)
+    }
+
+    impl Sign<&Bytes> for SigningKey {
+        type Error = core::convert::Infallible;
+        type Signature = G2Affine;
+        fn sign(&self, m: &Bytes) -> Result<Self::Signature, Self::Error> {
+            let env = m.env();
+            let bls = env.crypto().bls12_381();
+            let point = bls.hash_to_g2(m, &Bytes::from_slice(env, DST));
+            Ok(bls.g2_mul(&point, &self.scalar(env)))
+        }
+    }
+
+    impl Verify<&Bytes> for G1Affine {
+        type Error = Error;
+        type Signature = G2Affine;
+        fn verify(&self, m: &Bytes, s: &Self::Signature) -> Result<(), Self::Error> {
+            let env = m.env();
+            let bls = env.crypto().bls12_381();
+            let point = bls.hash_to_g2(m, &Bytes::from_slice(env, DST));
+            // e(-G, s) * e(pk, H(m)) == 1
+            if bls.pairing_check(
+                vec![env, -generator(env), self.clone()],
+                vec![env, s.clone(), point],
+            ) {
+                Ok(())
+            } else {
+                Err(Error)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::{Sign, SigningKey, Verify};
+        use crate::{Bytes, Env};
+
+        #[test]
+        fn sign_and_verify() {
+            let env = Env::default();
+            let sk = SigningKey::from_bytes(&[1; 32]);
+            let pk = sk.public_key(&env);
+            let msg = Bytes::from_slice(&env, b"hello");
+            let sig = sk.sign(&msg).unwrap();
+            assert!(pk.verify(&msg, &sig).is_ok());
+        }
+
+        #[test]
+        fn verify_rejects_other_message_and_key() {
+            let env = Env::default();
+            let sk = SigningKey::from_bytes(&[1; 32]);
+            let other_pk = SigningKey::from_bytes(&[2; 32]).public_key(&env);
+            let msg = Bytes::from_slice(&env, b"hello");
+            let sig = sk.sign(&msg).unwrap();
+            assert!(sk
+                .public_key(&env)
+                .verify(&Bytes::from_slice(&env, b"world"), &sig)
+                .is_err());
+            assert!(other_pk.verify(&msg, &sig).is_err());
+        }
     }
 }
@@ -0,0 +1,325 @@
+//! Traces of the contract invocations made in an [Env].
+//!
+//! See [`Env::trace`][crate::Env::trace]. Invocations made by Wasm contracts
+//! are traced without their cost, see [`Invocation::cost`].
+
+use core::fmt;
+use std::{string::ToString, vec::Vec as StdVec};
+
+use crate::{
+    env::internal::{events::HostEvent, AddressObject, Symbol as SymbolVal, VecObject},
+    unwrap::UnwrapInfallible,
+    xdr::{self, ContractEventBody, ContractEventType, ScVal},
+    Address, Env, Error, Symbol, TryFromVal, TryIntoVal, Val, Vec,
+};
+
+use super::failure::call_failure;
+
+/// The contract invocations made in an [Env] since the start of the last top
+/// level invocation, see [`Env::trace`][crate::Env::trace].
+///
+/// Each entry is a top level invocation, made by the test or by the
+/// environment such as when a contract is constructed, with the invocations it
+/// made nested under it.
+#[derive(Clone, Debug)]
+pub struct Trace(pub StdVec<Invocation>);
+
+/// A contract invocation, and the invocations it made.
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    /// The contract that made the invocation, or None if the invocation was
+    /// made by the test.
+    pub caller: Option<Address>,
+    /// The contract that was invoked.
+    pub contract: Address,
+    /// The function that was invoked.
+    pub function: Symbol,
+    /// The arguments the function was invoked with.
+    pub args: Vec<Val>,
+    /// The value returned by the function, or the error the invocation failed
+    /// with. None if the invocation had not finished when the trace was taken.
+    pub result: Option<Result<Val, Error>>,
+    /// The cost of the invocation, including the invocations it made.
+    ///
+    /// The cost is measured for invocations made by the test or by natively
+    /// registered contracts, and is None for invocations made by Wasm
+    /// contracts or by the environment, and for invocations that failed with a
+    /// panic in the contract that made them.
+    pub cost: Option<InvocationCost>,
+    /// The events emitted by the contract during the invocation, excluding the
+    /// events emitted by the invocations it made. The events of invocations
+    /// that failed are rolled back and not published.
+    pub events: Vec<(Address, Vec<Val>, Val)>,
+    /// The invocations made during the invocation.
+    pub sub_invocations: StdVec<Invocation>,
+}
+
+/// The cost of an invocation, as measured by the [`Budget`][crate::testutils::budget::Budget].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct InvocationCost {
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TraceSnapshot(pub StdVec<InvocationSnapshot>);
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct InvocationSnapshot {
+    pub caller: Option<xdr::ScAddress>,
+    pub contract: xdr::ScAddress,
+    pub function: xdr::ScSymbol,
+    pub args: StdVec<ScVal>,
+    pub result: Option<Result<ScVal, xdr::ScError>>,
+    pub cost: Option<InvocationCost>,
+    pub events: StdVec<xdr::ContractEvent>,
+    pub sub_invocations: StdVec<InvocationSnapshot>,
+}
+
+impl Trace {
+    pub fn from_xdr(env: &Env, v: &TraceSnapshot) -> Self {
+        Self(v.0.iter().map(|i| Invocation::from_xdr(env, i)).collect())
+    }
+}
+
+impl Invocation {
+    pub fn from_xdr(env: &Env, v: &InvocationSnapshot) -> Self {
+        let address = |a: &xdr::ScAddress| Address::try_from_val(env, a).unwrap();
+        let val = |v: &ScVal| Val::try_from_val(env, v).unwrap();
+        Self {
+            caller: v.caller.as_ref().map(address),
+            contract: address(&v.contract),
+            function: Symbol::try_from_val(env, &v.function).unwrap(),
+            args: Vec::from_slice(env, &v.args.iter().map(val).collect::<StdVec<_>>()),
+            result: v.result.as_ref().map(|r| match r {
+                Ok(v) => Ok(val(v)),
+                Err(e) => Err(e.clone().into()),
+            }),
+            cost: v.cost,
+            events: {
+                let mut events = Vec::new(env);
+                for e in &v.events {
+                    if let xdr::ContractEvent {
+                        contract_id: Some(contract_id),
+                        body: ContractEventBody::V0(xdr::ContractEventV0 { topics, data }),
+                        ..
+                    } = e
+                    {
+                        events.push_back((
+                            Address::from_contract_id(env, contract_id.0 .0),
+                            topics.try_into_val(env).unwrap(),
+                            val(data),
+                        ));
+                    }
+                }
+                events
+            },
+            sub_invocations: v
+                .sub_invocations
+                .iter()
+                .map(|i| Invocation::from_xdr(env, i))
+                .collect(),
+        }
+    }
+
+    /// Writes the invocation as a line starting with `head`, followed by its
+    /// events and sub-invocations on lines starting with `prefix`.
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, head: &str, prefix: &str) -> fmt::Result {
+        let env = self.contract.env();
+        let sc = |v: &Val| ScVal::try_from_val(env, v).unwrap();
+        let args = self.args.iter().map(|a| sc(&a)).collect::<StdVec<_>>();
+        write!(
+            f,
+            "{head}{:?}.{}({args:?})",
+            self.contract,
+            self.function.to_string()
+        )?;
+        match &self.result {
+            Some(Ok(v)) => write!(f, " -> {:?}", sc(v))?,
+            Some(Err(e)) => write!(f, " -> {e:?}")?,
+            None => write!(f, " -> ...")?,
+        }
+        if let Some(cost) = self.cost {
+            write!(f, " [cpu: {}, mem: {}]", cost.cpu_insns, cost.mem_bytes)?;
+        }
+        writeln!(f)?;
+        let events = self.events.len() as usize;
+        let n = events + self.sub_invocations.len();
+        let branch = |i: usize| {
+            if i + 1 == n {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            }
+        };
+        for (i, (_, topics, data)) in self.events.iter().enumerate() {
+            let topics = topics.iter().map(|t| sc(&t)).collect::<StdVec<_>>();
+            writeln!(f, "{prefix}{}event {topics:?} {:?}", branch(i).0, sc(&data))?;
+        }
+        for (i, invocation) in self.sub_invocations.iter().enumerate() {
+            let (b, indent) = branch(events + i);
+            invocation.fmt_tree(
+                f,
+                &std::format!("{prefix}{b}"),
+                &std::format!("{prefix}{indent}"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for invocation in &self.0 {
+            invocation.fmt_tree(f, "", "")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, "", "")
+    }
+}
+
+/// An invocation made through the [Env], by the test or by a natively
+/// registered contract, with the arguments it was made with and its cost.
+///
+/// The record holds the objects of the invocation in the host rather than
+/// values that hold an [Env], such as [Address], which would keep the host of
+/// the [Env] alive from within its own test state.
+pub(crate) struct CallRecord {
+    pub(crate) caller: Option<AddressObject>,
+    pub(crate) contract: AddressObject,
+    pub(crate) function: SymbolVal,
+    pub(crate) args: VecObject,
+    pub(crate) cost: Option<InvocationCost>,
+}
+
+impl CallRecord {
+    fn matches(
+        &self,
+        env: &Env,
+        caller: &Option<xdr::ScAddress>,
+        contract: &xdr::ScAddress,
+        function: &xdr::ScSymbol,
+    ) -> bool {
+        let to_xdr = |a: &AddressObject| {
+            xdr::ScAddress::from(&Address::try_from_val(env, a).unwrap_infallible())
+        };
+        let self_function = Symbol::try_from_val(env, &self.function).unwrap_infallible();
+        self.caller.as_ref().map(to_xdr) == *caller
+            && to_xdr(&self.contract) == *contract
+            && self_function.to_string().as_bytes() == function.as_slice()
+    }
+
+    fn args(&self, env: &Env) -> StdVec<ScVal> {
+        Vec::<Val>::try_from_val(env, &self.args)
+            .unwrap_infallible()
+            .iter()
+            .map(|a| ScVal::try_from_val(env, &a).unwrap())
+            .collect()
+    }
+}
+
+/// Builds the trace from the events of the host, in which the host records a
+/// `fn_call` diagnostic event when a contract is called, a `fn_return`
+/// diagnostic event when it returns, and a call failure event when it fails.
+///
+/// The events record the arguments of a call as a single value when there is
+/// one argument, so the arguments, and the costs, are taken from the records
+/// of the calls made through the [Env] where available.
+pub(crate) fn trace_snapshot(
+    events: &[HostEvent],
+    records: &[CallRecord],
+    env: &Env,
+) -> TraceSnapshot {
+    let mut top = StdVec::<InvocationSnapshot>::new();
+    let mut stack = StdVec::<InvocationSnapshot>::new();
+    let mut next_record = 0;
+
+    fn finish(
+        top: &mut StdVec<InvocationSnapshot>,
+        stack: &mut StdVec<InvocationSnapshot>,
+        result: Result<ScVal, xdr::ScError>,
+    ) {
+        if let Some(mut invocation) = stack.pop() {
+            invocation.result = Some(result);
+            match stack.last_mut() {
+                Some(parent) => parent.sub_invocations.push(invocation),
+                None => top.push(invocation),
+            }
+        }
+    }
+
+    for e in events {
+        let ContractEventBody::V0(ce) = &e.event.body;
+        match e.event.type_ {
+            ContractEventType::Contract => {
+                if let Some(current) = stack.last_mut() {
+                    current.events.push(e.event.clone());
+                }
+                continue;
+            }
+            ContractEventType::System => continue,
+            ContractEventType::Diagnostic => {}
+        }
+        match ce.topics.as_slice() {
+            [ScVal::Symbol(s), ScVal::Bytes(id), ScVal::Symbol(function)]
+                if s.as_slice() == b"fn_call" =>
+            {
+                let Ok(id) = <[u8; 32]>::try_from(id.as_slice()) else {
+                    continue;
+                };
+                let caller = e.event.contract_id.clone().map(xdr::ScAddress::Contract);
+                let contract = xdr::ScAddress::Contract(xdr::ContractId(xdr::Hash(id)));
+                let record = records[next_record..]
+                    .iter()
+                    .position(|r| r.matches(env, &caller, &contract, function))
+                    .map(|i| {
+                        next_record += i + 1;
+                        &records[next_record - 1]
+                    });
+                let args = match (record, &ce.data) {
+                    (Some(r), _) => r.args(env),
+                    (None, ScVal::Void) => StdVec::new(),
+                    (None, ScVal::Vec(Some(args))) => args.to_vec(),
+                    (None, arg) => std::vec![arg.clone()],
+                };
+                stack.push(InvocationSnapshot {
+                    caller,
+                    contract,
+                    function: function.clone(),
+                    args,
+                    result: None,
+                    cost: record.and_then(|r| r.cost),
+                    events: StdVec::new(),
+                    sub_invocations: StdVec::new(),
+                });
+            }
+            [ScVal::Symbol(s), ..] if s.as_slice() == b"fn_return" => {
+                finish(&mut top, &mut stack, Ok(ce.data.clone()));
+            }
+            _ => {
+                if let Some((ScVal::Error(error), _)) = call_failure(e) {
+                    finish(&mut top, &mut stack, Err(error.clone()));
+                }
+            }
+        }
+    }
+
+    // Invocations that have not finished, when the trace is taken during an
+    // invocation.
+    while let Some(invocation) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.sub_invocations.push(invocation),
+            None => top.push(invocation),
+        }
+    }
+
+    TraceSnapshot(top)
+}
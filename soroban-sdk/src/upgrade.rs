@@ -0,0 +1,183 @@
+//! Upgrade contains types for versioning and migrating the storage of
+//! upgradeable contracts.
+//!
+//! [`Deployer::update_current_contract_wasm`] replaces the code of a contract,
+//! but the data stored by the previous code stays as it was. Contracts that
+//! implement [`Upgradeable`] track the version of their storage layout in
+//! instance storage, and run the migrations between versions exactly once
+//! after an upgrade.
+//!
+//! Apply [`contractupgradeable`] to the implementation of [`Upgradeable`] to
+//! export the following functions from the contract:
+//!
+//! - `version() -> u32` – Returns the version of the stored data.
+//! - `upgrade(new_wasm_hash: BytesN<32>)` – Calls
+//!   [`Upgradeable::authorize_upgrade`] then replaces the code of the contract.
+//! - `migrate() -> u32` – Runs the pending migrations of the new code and
+//!   returns the version of the migrated data.
+//!
+//! The code is only replaced once the `upgrade` invocation finishes, so
+//! `migrate` must be invoked separately after `upgrade`.
+//!
+//! ### Examples
+//!
+//! ```
+//! use soroban_sdk::{
+//!     contract, contractimpl, contractupgradeable, symbol_short,
+//!     upgrade::{self, Upgradeable},
+//!     Address, Env,
+//! };
+//!
+//! #[contract]
+//! pub struct Contract;
+//!
+//! #[contractimpl]
+//! impl Contract {
+//!     pub fn __constructor(env: Env, admin: Address) {
+//!         env.storage().instance().set(&symbol_short!("admin"), &admin);
+//!         upgrade::init::<Self>(&env);
+//!     }
+//! }
+//!
+//! #[contractupgradeable]
+//! impl Upgradeable for Contract {
+//!     const VERSION: u32 = 2;
+//!
+//!     fn authorize_upgrade(env: &Env) {
+//!         let admin: Address = env.storage().instance().get(&symbol_short!("admin")).unwrap();
+//!         admin.require_auth();
+//!     }
+//!
+//!     fn migrate(env: &Env, from_version: u32) {
+//!         match from_version {
+//!             1 => env.storage().instance().set(&symbol_short!("count"), &0_u32),
+//!             _ => unreachable!(),
+//!         }
+//!     }
+//! }
+//!
+//! #[test]
+//! fn test() {
+//! # }
+//! # #[cfg(feature = "testutils")]
+//! # fn main() {
+//!     use soroban_sdk::testutils::Address as _;
+//!
+//!     let env = Env::default();
+//!     let contract_id = env.register(Contract, (Address::generate(&env),));
+//!     let client = ContractClient::new(&env, &contract_id);
+//!     assert_eq!(client.version(), 2);
+//!     // Nothing to migrate, the data was initialized at the latest version.
+//!     assert_eq!(client.migrate(), 2);
+//! }
+//! # #[cfg(not(feature = "testutils"))]
+//! # fn main() { }
+//! ```
+//!
+//! [`Deployer::update_current_contract_wasm`]: crate::deploy::Deployer::update_current_contract_wasm
+//! [`contractupgradeable`]: crate::contractupgradeable
+
+use crate::{
+    xdr::{ScErrorCode, ScErrorType},
+    BytesN, Env, Error, Symbol,
+};
+
+/// Versioning and migration of the storage of an upgradeable contract.
+///
+/// See the [module documentation][self] for how to use it.
+///
+/// ### Data between upgrade and migration
+///
+/// The new code takes effect when the `upgrade` invocation finishes, but the
+/// stored data is only migrated when `migrate` is invoked afterwards. Until
+/// then, every function of the new code runs against data at the previous
+/// version. `migrate` is permissionless, so anyone can close the window, but
+/// nothing guarantees that it is closed before other functions are invoked.
+///
+/// Functions that depend on migrated data should call [`migrate`] before
+/// reading it, which only reads the stored version once the data is up to
+/// date, or reject calls while [`version`] is behind
+/// [`VERSION`][Upgradeable::VERSION].
+pub trait Upgradeable {
+    /// The version of the storage layout the code of the contract expects.
+    ///
+    /// Increment the version whenever a change to the code requires the
+    /// stored data to be migrated.
+    const VERSION: u32;
+
+    /// Authorizes an upgrade of the contract, typically by requiring the
+    /// auth of an admin.
+    ///
+    /// Panics to reject the upgrade.
+    fn authorize_upgrade(env: &Env);
+
+    /// Migrates the stored data from `from_version` to `from_version + 1`.
+    ///
+    /// Called once for every version between the stored version and
+    /// [`VERSION`][Upgradeable::VERSION]. Each call is recorded in storage,
+    /// so a migration step never runs twice.
+    fn migrate(env: &Env, from_version: u32) {
+        let _ = (env, from_version);
+    }
+}
+
+fn version_key() -> Symbol {
+    soroban_sdk_macros::internal_symbol_short!("__version")
+}
+
+/// Returns the version of the data stored by the current contract.
+///
+/// Contracts that have never stored a version, such as contracts deployed
+/// before becoming upgradeable, are at version 1.
+pub fn version(env: &Env) -> u32 {
+    env.storage().instance().get(&version_key()).unwrap_or(1)
+}
+
+/// Stores the [`VERSION`][Upgradeable::VERSION] of the code as the version of
+/// the data of the current contract.
+///
+/// Call from the constructor so that newly deployed contracts, whose data is
+/// already laid out for the current code, have no migrations to run.
+pub fn init<C: Upgradeable>(env: &Env) {
+    env.storage().instance().set(&version_key(), &C::VERSION);
+}
+
+/// Authorizes the upgrade with [`Upgradeable::authorize_upgrade`], then
+/// replaces the code of the current contract with the Wasm identified by
+/// `new_wasm_hash`.
+///
+/// The new code only takes effect after the current invocation finishes,
+/// and [`migrate`] must then be invoked with the new code. See
+/// [`Upgradeable`] for the data the new code reads until then.
+pub fn upgrade<C: Upgradeable>(env: &Env, new_wasm_hash: BytesN<32>) {
+    C::authorize_upgrade(env);
+    env.deployer().update_current_contract_wasm(new_wasm_hash);
+}
+
+/// Runs each pending migration of the current contract, from the stored
+/// version up to the [`VERSION`][Upgradeable::VERSION] of the code, and
+/// returns the new version.
+///
+/// The version is stored after every step, so migrations that already ran
+/// are not repeated, and calling it when the data is up to date does
+/// nothing.
+///
+/// ### Panics
+///
+/// If the stored version is greater than the version of the code, which
+/// happens when the contract was downgraded.
+pub fn migrate<C: Upgradeable>(env: &Env) -> u32 {
+    let mut current = version(env);
+    if current > C::VERSION {
+        env.panic_with_error(Error::from_type_and_code(
+            ScErrorType::Context,
+            ScErrorCode::InvalidAction,
+        ));
+    }
+    while current < C::VERSION {
+        C::migrate(env, current);
+        current += 1;
+        env.storage().instance().set(&version_key(), &current);
+    }
+    current
+}
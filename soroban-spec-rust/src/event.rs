@@ -0,0 +1,196 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{ScSpecEventDataFormat, ScSpecEventParamLocationV0, ScSpecEventV0};
+
+use super::types::generate_type_ident;
+
+/// Constructs a token stream containing a single struct that mirrors the event
+/// spec, with functions for publishing the event and for decoding it from the
+/// topics and data of a published event. The doc of the spec is output as the
+/// rustdoc of the struct.
+///
+/// The topics of the event are the prefix topics of the spec followed by the
+/// params located in the topic list. The data of the event is formed from the
+/// params located in the data as specified by the data format of the spec.
+pub fn generate_event(spec: &ScSpecEventV0) -> TokenStream {
+    let ident = format_ident!("{}", spec.name.to_utf8_string().unwrap());
+    if !spec.lib.is_empty() {
+        let lib_ident = format_ident!("{}", spec.lib.to_utf8_string_lossy());
+        return quote! {
+            pub type #ident = ::#lib_ident::#ident;
+        };
+    }
+
+    let doc = spec.doc.to_utf8_string_lossy();
+    let doc = (!doc.is_empty()).then(|| quote! { #[doc = #doc] });
+
+    let fields = spec.params.iter().map(|p| {
+        let p_ident = format_ident!("{}", p.name.to_utf8_string().unwrap());
+        let p_type = generate_type_ident(&p.type_);
+        quote! { pub #p_ident: #p_type }
+    });
+    let field_idents = spec
+        .params
+        .iter()
+        .map(|p| format_ident!("{}", p.name.to_utf8_string().unwrap()));
+
+    let prefix_topics: Vec<_> = spec
+        .prefix_topics
+        .iter()
+        .map(|t| t.to_utf8_string().unwrap())
+        .collect();
+    let topic_params: Vec<_> = spec
+        .params
+        .iter()
+        .filter(|p| p.location == ScSpecEventParamLocationV0::TopicList)
+        .map(|p| {
+            (
+                format_ident!("{}", p.name.to_utf8_string().unwrap()),
+                generate_type_ident(&p.type_),
+            )
+        })
+        .collect();
+    let data_params: Vec<_> = spec
+        .params
+        .iter()
+        .filter(|p| p.location == ScSpecEventParamLocationV0::Data)
+        .map(|p| {
+            (
+                p.name.to_utf8_string().unwrap(),
+                format_ident!("{}", p.name.to_utf8_string().unwrap()),
+                generate_type_ident(&p.type_),
+            )
+        })
+        .collect();
+
+    // Topics.
+    let topics_len = Literal::u32_unsuffixed((prefix_topics.len() + topic_params.len()) as u32);
+    let prefix_indexes = (0..prefix_topics.len() as u32).map(Literal::u32_unsuffixed);
+    let topic_indexes = (prefix_topics.len() as u32..).map(Literal::u32_unsuffixed);
+    let topic_idents = topic_params.iter().map(|(i, _)| i);
+    let topic_types = topic_params.iter().map(|(_, t)| t);
+    let publish_topics = quote! {
+        let topics = soroban_sdk::Vec::<soroban_sdk::Val>::from_array(
+            env,
+            [
+                #(soroban_sdk::Symbol::new(env, #prefix_topics).into_val(env),)*
+                #(self.#topic_idents.into_val(env),)*
+            ],
+        );
+    };
+    let topic_idents = topic_params.iter().map(|(i, _)| i);
+    let decode_topics = quote! {
+        if topics.len() != #topics_len {
+            return Err(soroban_sdk::ConversionError);
+        }
+        #(
+            let topic: soroban_sdk::Symbol = soroban_sdk::TryFromVal::try_from_val(env, &topics.get_unchecked(#prefix_indexes))
+                .map_err(|_| soroban_sdk::ConversionError)?;
+            if topic != soroban_sdk::Symbol::new(env, #prefix_topics) {
+                return Err(soroban_sdk::ConversionError);
+            }
+        )*
+        #(
+            let #topic_idents: #topic_types = soroban_sdk::TryFromVal::try_from_val(env, &topics.get_unchecked(#topic_indexes))
+                .map_err(|_| soroban_sdk::ConversionError)?;
+        )*
+    };
+
+    // Data.
+    let data_len = Literal::u32_unsuffixed(data_params.len() as u32);
+    let data_names = data_params.iter().map(|(n, _, _)| n);
+    let data_idents = data_params.iter().map(|(_, i, _)| i);
+    let data_types = data_params.iter().map(|(_, _, t)| t);
+    let (publish_data, decode_data) = match (spec.data_format, &data_params[..]) {
+        (ScSpecEventDataFormat::SingleValue, []) => {
+            (quote! { () }, quote! { let _ = (env, data); })
+        }
+        (ScSpecEventDataFormat::SingleValue, [(_, ident, type_)]) => (
+            quote! { self.#ident },
+            quote! {
+                let #ident: #type_ = soroban_sdk::TryFromVal::try_from_val(env, data)
+                    .map_err(|_| soroban_sdk::ConversionError)?;
+            },
+        ),
+        (ScSpecEventDataFormat::Map, _) => (
+            quote! {
+                soroban_sdk::Map::<soroban_sdk::Symbol, soroban_sdk::Val>::from_array(
+                    env,
+                    [#((soroban_sdk::Symbol::new(env, #data_names), self.#data_idents.into_val(env)),)*],
+                )
+            },
+            {
+                let data_names = data_params.iter().map(|(n, _, _)| n);
+                let data_idents = data_params.iter().map(|(_, i, _)| i);
+                quote! {
+                    let data: soroban_sdk::Map<soroban_sdk::Symbol, soroban_sdk::Val> = soroban_sdk::TryFromVal::try_from_val(env, data)
+                        .map_err(|_| soroban_sdk::ConversionError)?;
+                    if data.len() != #data_len {
+                        return Err(soroban_sdk::ConversionError);
+                    }
+                    #(
+                        let #data_idents: #data_types = soroban_sdk::TryFromVal::try_from_val(
+                            env,
+                            &data.get(soroban_sdk::Symbol::new(env, #data_names)).ok_or(soroban_sdk::ConversionError)?,
+                        )
+                        .map_err(|_| soroban_sdk::ConversionError)?;
+                    )*
+                }
+            },
+        ),
+        // Events with more than one param in single value data cannot be
+        // published, and are treated as having vec data.
+        (ScSpecEventDataFormat::Vec | ScSpecEventDataFormat::SingleValue, _) => {
+            let data_indexes = (0..data_params.len() as u32).map(Literal::u32_unsuffixed);
+            let data_idents_decode = data_params.iter().map(|(_, i, _)| i);
+            (
+                quote! {
+                    soroban_sdk::Vec::<soroban_sdk::Val>::from_array(
+                        env,
+                        [#(self.#data_idents.into_val(env),)*],
+                    )
+                },
+                quote! {
+                    let data: soroban_sdk::Vec<soroban_sdk::Val> = soroban_sdk::TryFromVal::try_from_val(env, data)
+                        .map_err(|_| soroban_sdk::ConversionError)?;
+                    if data.len() != #data_len {
+                        return Err(soroban_sdk::ConversionError);
+                    }
+                    #(
+                        let #data_idents_decode: #data_types = soroban_sdk::TryFromVal::try_from_val(env, &data.get_unchecked(#data_indexes))
+                            .map_err(|_| soroban_sdk::ConversionError)?;
+                    )*
+                },
+            )
+        }
+    };
+
+    quote! {
+        #doc
+        #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+        pub struct #ident { #(#fields,)* }
+
+        impl #ident {
+            /// Publishes the event from the currently executing contract.
+            pub fn publish(&self, env: &soroban_sdk::Env) {
+                use soroban_sdk::IntoVal;
+                #publish_topics
+                let data: soroban_sdk::Val = #publish_data.into_val(env);
+                env.events().publish(topics, data);
+            }
+
+            /// Decodes the event from the topics and data of a published
+            /// event, returning an error if they do not match the event.
+            pub fn try_from_event(
+                env: &soroban_sdk::Env,
+                topics: &soroban_sdk::Vec<soroban_sdk::Val>,
+                data: &soroban_sdk::Val,
+            ) -> Result<Self, soroban_sdk::ConversionError> {
+                #decode_topics
+                #decode_data
+                Ok(Self { #(#field_idents,)* })
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod event;
 pub mod r#trait;
 pub mod types;
 
@@ -12,7 +13,11 @@ use syn::Error;
 
 use soroban_spec::read::{from_wasm, FromWasmError};
 
-use types::{generate_enum, generate_error_enum, generate_struct, generate_union};
+use event::generate_event;
+use types::{
+    generate_enum, generate_error_enum, generate_struct, generate_union_with_structs,
+    variant_struct,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum GenerateFromFileError {
@@ -70,24 +75,47 @@ pub fn generate_without_file(specs: &[ScSpecEntry]) -> TokenStream {
     let mut spec_unions = Vec::new();
     let mut spec_enums = Vec::new();
     let mut spec_error_enums = Vec::new();
-    for s in specs {
+    let mut spec_events = Vec::new();
+    for (i, s) in specs.iter().enumerate() {
+        // Generic type instances are emitted by every contract impl that uses
+        // them, so the same entry can appear more than once.
+        if specs[..i].contains(s) {
+            continue;
+        }
         match s {
             ScSpecEntry::FunctionV0(f) => spec_fns.push(f),
             ScSpecEntry::UdtStructV0(s) => spec_structs.push(s),
             ScSpecEntry::UdtUnionV0(u) => spec_unions.push(u),
             ScSpecEntry::UdtEnumV0(e) => spec_enums.push(e),
             ScSpecEntry::UdtErrorEnumV0(e) => spec_error_enums.push(e),
-            ScSpecEntry::EventV0(_) => todo!(),
+            ScSpecEntry::EventV0(e) => spec_events.push(e),
         }
     }
 
     let trait_name = "Contract";
 
+    // Structs that hold the named fields of union variants are generated as
+    // part of the union.
+    let variant_structs = spec_unions
+        .iter()
+        .flat_map(|u| {
+            u.cases
+                .iter()
+                .filter_map(|c| variant_struct(u, c, &spec_structs))
+        })
+        .collect::<Vec<_>>();
+
     let trait_ = r#trait::generate_trait(trait_name, &spec_fns);
-    let structs = spec_structs.iter().map(|s| generate_struct(s));
-    let unions = spec_unions.iter().map(|s| generate_union(s));
+    let structs = spec_structs
+        .iter()
+        .filter(|s| !variant_structs.contains(s))
+        .map(|s| generate_struct(s));
+    let unions = spec_unions
+        .iter()
+        .map(|s| generate_union_with_structs(s, &spec_structs));
     let enums = spec_enums.iter().map(|s| generate_enum(s));
     let error_enums = spec_error_enums.iter().map(|s| generate_error_enum(s));
+    let events = spec_events.iter().map(|s| generate_event(s));
 
     quote! {
         #[soroban_sdk::contractargs(name = "Args")]
@@ -98,6 +126,7 @@ pub fn generate_without_file(specs: &[ScSpecEntry]) -> TokenStream {
         #(#unions)*
         #(#enums)*
         #(#error_enums)*
+        #(#events)*
     }
 }
 
@@ -121,8 +150,14 @@ impl ToFormattedString for TokenStream {
 mod test {
     use pretty_assertions::assert_eq;
 
-    use super::{generate, ToFormattedString};
+    use super::{generate, generate_without_file, ToFormattedString};
+    use soroban_spec::marker::ENUM_VARIANT_STRUCT_DOC;
     use soroban_spec::read::from_wasm;
+    use stellar_xdr::curr::{
+        ScSpecEntry, ScSpecEventDataFormat, ScSpecEventParamLocationV0, ScSpecEventParamV0,
+        ScSpecEventV0, ScSpecTypeDef, ScSpecTypeUdt, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+        ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionV0,
+    };
 
     const EXAMPLE_WASM: &[u8] = include_bytes!("../../target/wasm32v1-none/release/test_udt.wasm");
 
@@ -142,6 +177,12 @@ pub trait Contract {
 }
 #[soroban_sdk::contracttype(export = false)]
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct UdtUnit;
+#[soroban_sdk::contracttype(export = false)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct UdtEmpty {}
+#[soroban_sdk::contracttype(export = false)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct UdtTuple(pub i64, pub soroban_sdk::Vec<i64>);
 #[soroban_sdk::contracttype(export = false)]
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -152,11 +193,16 @@ pub struct UdtStruct {
 }
 #[soroban_sdk::contracttype(export = false)]
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct UdtEmptyTuple();
+#[soroban_sdk::contracttype(export = false)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum UdtEnum {
     UdtA,
     UdtB(UdtStruct),
     UdtC(UdtEnum2),
     UdtD(UdtTuple),
+    UdtE { a: i64, b: soroban_sdk::Vec<i64> },
+    UdtF(),
 }
 #[soroban_sdk::contracttype(export = false)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -164,6 +210,188 @@ pub enum UdtEnum2 {
     A = 10,
     B = 15,
 }
+"#,
+        );
+    }
+
+    #[test]
+    fn variant_struct_requires_marker() {
+        let field = |name: &str, type_| ScSpecUdtStructFieldV0 {
+            doc: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            type_,
+        };
+        let case = |name: &str, udt: &str| {
+            ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                doc: "".try_into().unwrap(),
+                name: name.try_into().unwrap(),
+                type_: [ScSpecTypeDef::Udt(ScSpecTypeUdt {
+                    name: udt.try_into().unwrap(),
+                })]
+                .try_into()
+                .unwrap(),
+            })
+        };
+        // The user defined ActionTransfer struct has the name that a variant
+        // with named fields would be exported with, but only ActionBurn is
+        // marked as holding the fields of a variant.
+        let entries = [
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Action".try_into().unwrap(),
+                cases: [
+                    case("Transfer", "ActionTransfer"),
+                    case("Burn", "ActionBurn"),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "ActionTransfer".try_into().unwrap(),
+                fields: [
+                    field("amount", ScSpecTypeDef::I128),
+                    field("to", ScSpecTypeDef::Address),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: ENUM_VARIANT_STRUCT_DOC.try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "ActionBurn".try_into().unwrap(),
+                fields: [field("amount", ScSpecTypeDef::I128)].try_into().unwrap(),
+            }),
+        ];
+        let rust = generate_without_file(&entries)
+            .to_formatted_string()
+            .unwrap();
+        assert_eq!(
+            rust,
+            r#"#[soroban_sdk::contractargs(name = "Args")]
+#[soroban_sdk::contractclient(name = "Client")]
+pub trait Contract {}
+#[soroban_sdk::contracttype(export = false)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ActionTransfer {
+    pub amount: i128,
+    pub to: soroban_sdk::Address,
+}
+#[soroban_sdk::contracttype(export = false)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Action {
+    Transfer(ActionTransfer),
+    Burn { amount: i128 },
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn event() {
+        let entries = [ScSpecEntry::EventV0(ScSpecEventV0 {
+            doc: "Published when tokens are transferred.".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: "Transfer".try_into().unwrap(),
+            prefix_topics: ["transfer".try_into().unwrap()].try_into().unwrap(),
+            params: [
+                ScSpecEventParamV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "from".try_into().unwrap(),
+                    type_: ScSpecTypeDef::Address,
+                    location: ScSpecEventParamLocationV0::TopicList,
+                },
+                ScSpecEventParamV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "amount".try_into().unwrap(),
+                    type_: ScSpecTypeDef::I128,
+                    location: ScSpecEventParamLocationV0::Data,
+                },
+            ]
+            .try_into()
+            .unwrap(),
+            data_format: ScSpecEventDataFormat::Map,
+        })];
+        let rust = generate_without_file(&entries)
+            .to_formatted_string()
+            .unwrap();
+        assert_eq!(
+            rust,
+            r#"#[soroban_sdk::contractargs(name = "Args")]
+#[soroban_sdk::contractclient(name = "Client")]
+pub trait Contract {}
+///Published when tokens are transferred.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Transfer {
+    pub from: soroban_sdk::Address,
+    pub amount: i128,
+}
+impl Transfer {
+    /// Publishes the event from the currently executing contract.
+    pub fn publish(&self, env: &soroban_sdk::Env) {
+        use soroban_sdk::IntoVal;
+        let topics = soroban_sdk::Vec::<
+            soroban_sdk::Val,
+        >::from_array(
+            env,
+            [
+                soroban_sdk::Symbol::new(env, "transfer").into_val(env),
+                self.from.into_val(env),
+            ],
+        );
+        let data: soroban_sdk::Val = soroban_sdk::Map::<
+            soroban_sdk::Symbol,
+            soroban_sdk::Val,
+        >::from_array(
+                env,
+                [(soroban_sdk::Symbol::new(env, "amount"), self.amount.into_val(env))],
+            )
+            .into_val(env);
+        env.events().publish(topics, data);
+    }
+    /// Decodes the event from the topics and data of a published
+    /// event, returning an error if they do not match the event.
+    pub fn try_from_event(
+        env: &soroban_sdk::Env,
+        topics: &soroban_sdk::Vec<soroban_sdk::Val>,
+        data: &soroban_sdk::Val,
+    ) -> Result<Self, soroban_sdk::ConversionError> {
+        if topics.len() != 2 {
+            return Err(soroban_sdk::ConversionError);
+        }
+        let topic: soroban_sdk::Symbol = soroban_sdk::TryFromVal::try_from_val(
+                env,
+                &topics.get_unchecked(0),
+            )
+            .map_err(|_| soroban_sdk::ConversionError)?;
+        if topic != soroban_sdk::Symbol::new(env, "transfer") {
+            return Err(soroban_sdk::ConversionError);
+        }
+        let from: soroban_sdk::Address = soroban_sdk::TryFromVal::try_from_val(
+                env,
+                &topics.get_unchecked(1),
+            )
+            .map_err(|_| soroban_sdk::ConversionError)?;
+        let data: soroban_sdk::Map<soroban_sdk::Symbol, soroban_sdk::Val> = soroban_sdk::TryFromVal::try_from_val(
+                env,
+                data,
+            )
+            .map_err(|_| soroban_sdk::ConversionError)?;
+        if data.len() != 1 {
+            return Err(soroban_sdk::ConversionError);
+        }
+        let amount: i128 = soroban_sdk::TryFromVal::try_from_val(
+                env,
+                &data
+                    .get(soroban_sdk::Symbol::new(env, "amount"))
+                    .ok_or(soroban_sdk::ConversionError)?,
+            )
+            .map_err(|_| soroban_sdk::ConversionError)?;
+        Ok(Self { from, amount })
+    }
+}
 "#,
         );
     }
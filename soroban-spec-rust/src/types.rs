@@ -1,8 +1,12 @@
-use proc_macro2::{Literal, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote};
+use soroban_spec::marker::{
+    doc_has_marker, is_tuple_struct, is_unit_struct, ENUM_VARIANT_STRUCT_DOC,
+};
 use stellar_xdr::curr as stellar_xdr;
 use stellar_xdr::{
-    ScSpecTypeDef, ScSpecUdtEnumV0, ScSpecUdtErrorEnumV0, ScSpecUdtStructV0, ScSpecUdtUnionV0,
+    ScSpecTypeDef, ScSpecUdtEnumV0, ScSpecUdtErrorEnumV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseV0,
+    ScSpecUdtUnionV0,
 };
 
 // IMPORTANT: The "docs" fields of spec entries are not output in Rust token
@@ -18,18 +22,24 @@ use stellar_xdr::{
 /// spec.
 pub fn generate_struct(spec: &ScSpecUdtStructV0) -> TokenStream {
     let ident = format_ident!("{}", spec.name.to_utf8_string().unwrap());
+    let allow = allow_instance_name(&ident);
 
     if spec.lib.len() > 0 {
         let lib_ident = format_ident!("{}", spec.lib.to_utf8_string().unwrap());
         quote! {
             type #ident = ::#lib_ident::#ident;
         }
-    } else if spec
-        .fields
-        .iter()
-        .all(|f| f.name.to_utf8_string().unwrap().parse::<usize>().is_ok())
-    {
-        // If all fields are numeric, generate a tuple with unnamed fields.
+    } else if is_unit_struct(spec) {
+        // If marked as a unit struct, generate a unit struct.
+        quote! {
+            #[soroban_sdk::contracttype(export = false)]
+            #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+            #allow
+            pub struct #ident;
+        }
+    } else if is_tuple_struct(spec) {
+        // If all fields are numeric, or there are no fields and the struct is
+        // marked as a tuple struct, generate a tuple with unnamed fields.
         let fields = spec.fields.iter().map(|f| {
             let f_type = generate_type_ident(&f.type_);
             quote! { pub #f_type }
@@ -37,10 +47,12 @@ pub fn generate_struct(spec: &ScSpecUdtStructV0) -> TokenStream {
         quote! {
             #[soroban_sdk::contracttype(export = false)]
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+            #allow
             pub struct #ident ( #(#fields),* );
         }
     } else {
-        // Otherwise generate a struct with named fields.
+        // Otherwise generate a struct with named fields, which includes structs
+        // with no fields that are not marked, that are stored as empty maps.
         let fields = spec.fields.iter().map(|f| {
             let f_ident = format_ident!("{}", f.name.to_utf8_string().unwrap());
             let f_type = generate_type_ident(&f.type_);
@@ -49,6 +61,7 @@ pub fn generate_struct(spec: &ScSpecUdtStructV0) -> TokenStream {
         quote! {
             #[soroban_sdk::contracttype(export = false)]
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+            #allow
             pub struct #ident { #(#fields,)* }
         }
     }
@@ -57,7 +70,40 @@ pub fn generate_struct(spec: &ScSpecUdtStructV0) -> TokenStream {
 /// Constructs a token stream containing a single enum that mirrors the union
 /// spec.
 pub fn generate_union(spec: &ScSpecUdtUnionV0) -> TokenStream {
+    generate_union_with_structs(spec, &[])
+}
+
+/// Returns the struct spec that holds the named fields of a union case, if the
+/// case is a variant with named fields.
+///
+/// Variants with named fields are exported as a tuple case containing a single
+/// struct that has the doc [`ENUM_VARIANT_STRUCT_DOC`]. Tuple cases containing
+/// other structs, even with the same name pattern, are user defined types.
+pub fn variant_struct<'a>(
+    union: &ScSpecUdtUnionV0,
+    case: &ScSpecUdtUnionCaseV0,
+    structs: &[&'a ScSpecUdtStructV0],
+) -> Option<&'a ScSpecUdtStructV0> {
+    let ScSpecUdtUnionCaseV0::TupleV0(t) = case else {
+        return None;
+    };
+    let [ScSpecTypeDef::Udt(udt)] = t.type_.as_slice() else {
+        return None;
+    };
+    structs.iter().copied().find(|s| {
+        s.name == udt.name && s.lib == union.lib && doc_has_marker(&s.doc, ENUM_VARIANT_STRUCT_DOC)
+    })
+}
+
+/// Constructs a token stream containing a single enum that mirrors the union
+/// spec, where cases that are variants with named fields, with the fields in
+/// one of the structs, are generated as variants with named fields.
+pub fn generate_union_with_structs(
+    spec: &ScSpecUdtUnionV0,
+    structs: &[&ScSpecUdtStructV0],
+) -> TokenStream {
     let ident = format_ident!("{}", spec.name.to_utf8_string().unwrap());
+    let allow = allow_instance_name(&ident);
     if spec.lib.len() > 0 {
         let lib_ident = format_ident!("{}", spec.lib.to_utf8_string_lossy());
         quote! {
@@ -74,6 +120,20 @@ pub fn generate_union(spec: &ScSpecUdtUnionV0) -> TokenStream {
                 stellar_xdr::ScSpecUdtUnionCaseV0::VoidV0(_) => {
                     quote! { #v_ident }
                 }
+                stellar_xdr::ScSpecUdtUnionCaseV0::TupleV0(_)
+                    if variant_struct(spec, c, structs).is_some() =>
+                {
+                    let fields = variant_struct(spec, c, structs)
+                        .unwrap()
+                        .fields
+                        .iter()
+                        .map(|f| {
+                            let f_ident = format_ident!("{}", f.name.to_utf8_string().unwrap());
+                            let f_type = generate_type_ident(&f.type_);
+                            quote! { #f_ident: #f_type }
+                        });
+                    quote! { #v_ident { #(#fields),* } }
+                }
                 stellar_xdr::ScSpecUdtUnionCaseV0::TupleV0(t) => {
                     let v_type = t.type_.iter().map(generate_type_ident);
                     quote! { #v_ident ( #(#v_type),* ) }
@@ -83,11 +143,21 @@ pub fn generate_union(spec: &ScSpecUdtUnionV0) -> TokenStream {
         quote! {
             #[soroban_sdk::contracttype(export = false)]
             #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+            #allow
             pub enum #ident { #(#variants,)* }
         }
     }
 }
 
+/// Returns an allow attribute for the names of generic type instances, such as
+/// `Page_Address`, that are not camel case.
+fn allow_instance_name(ident: &Ident) -> Option<TokenStream> {
+    ident
+        .to_string()
+        .contains('_')
+        .then(|| quote! { #[allow(non_camel_case_types)] })
+}
+
 /// Constructs a token stream containing a single enum that mirrors the enum
 /// spec.
 pub fn generate_enum(spec: &ScSpecUdtEnumV0) -> TokenStream {
@@ -0,0 +1,758 @@
+//! Compat compares the specs of two versions of a contract, reporting the
+//! changes to the interface and whether they break existing callers.
+use std::fmt;
+
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtEnumCaseV0, ScSpecUdtErrorEnumCaseV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionV0,
+};
+
+use crate::read::{from_wasm, FromWasmError};
+
+/// The impact of a change on existing callers of the contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The change does not affect existing callers.
+    Info,
+    /// The change affects existing callers that receive values of a changed
+    /// type, such as a return value with a case they do not know about.
+    Warning,
+    /// The change breaks existing callers.
+    Breaking,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// A change between the old and new spec of a contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    FunctionAdded {
+        function: String,
+    },
+    FunctionRemoved {
+        function: String,
+    },
+    /// The number, order, or types of the inputs of the function changed.
+    FunctionInputsChanged {
+        function: String,
+        old: Vec<ScSpecTypeDef>,
+        new: Vec<ScSpecTypeDef>,
+    },
+    /// The inputs of the function were renamed without changing their types.
+    FunctionInputsRenamed {
+        function: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    FunctionOutputChanged {
+        function: String,
+        old: Option<ScSpecTypeDef>,
+        new: Option<ScSpecTypeDef>,
+    },
+    TypeAdded {
+        name: String,
+    },
+    TypeRemoved {
+        name: String,
+    },
+    /// The type changed kind, such as from a struct to a union.
+    TypeKindChanged {
+        name: String,
+    },
+    StructFieldAdded {
+        name: String,
+        field: String,
+    },
+    StructFieldRemoved {
+        name: String,
+        field: String,
+    },
+    StructFieldTypeChanged {
+        name: String,
+        field: String,
+        old: ScSpecTypeDef,
+        new: ScSpecTypeDef,
+    },
+    UnionCaseAdded {
+        name: String,
+        case: String,
+    },
+    UnionCaseRemoved {
+        name: String,
+        case: String,
+    },
+    /// The case changed from or to a void case, or the types of its values
+    /// changed.
+    UnionCaseChanged {
+        name: String,
+        case: String,
+    },
+    /// A case was added to an enum or error enum.
+    EnumCaseAdded {
+        name: String,
+        case: String,
+    },
+    /// A case was removed from an enum or error enum.
+    EnumCaseRemoved {
+        name: String,
+        case: String,
+    },
+    /// The value of a case of an enum or error enum changed.
+    EnumCaseRenumbered {
+        name: String,
+        case: String,
+        old: u32,
+        new: u32,
+    },
+}
+
+impl Change {
+    /// Returns the impact of the change on existing callers.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Change::FunctionAdded { .. }
+            | Change::FunctionInputsRenamed { .. }
+            | Change::TypeAdded { .. } => Severity::Info,
+            Change::TypeRemoved { .. }
+            | Change::UnionCaseAdded { .. }
+            | Change::EnumCaseAdded { .. } => Severity::Warning,
+            Change::FunctionRemoved { .. }
+            | Change::FunctionInputsChanged { .. }
+            | Change::FunctionOutputChanged { .. }
+            | Change::TypeKindChanged { .. }
+            | Change::StructFieldAdded { .. }
+            | Change::StructFieldRemoved { .. }
+            | Change::StructFieldTypeChanged { .. }
+            | Change::UnionCaseRemoved { .. }
+            | Change::UnionCaseChanged { .. }
+            | Change::EnumCaseRemoved { .. }
+            | Change::EnumCaseRenumbered { .. } => Severity::Breaking,
+        }
+    }
+
+    /// Returns true if the change breaks existing callers.
+    pub fn is_breaking(&self) -> bool {
+        self.severity() == Severity::Breaking
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity())?;
+        match self {
+            Change::FunctionAdded { function } => write!(f, "function {function} added"),
+            Change::FunctionRemoved { function } => write!(f, "function {function} removed"),
+            Change::FunctionInputsChanged { function, old, new } => write!(
+                f,
+                "function {function} inputs changed from {old:?} to {new:?}"
+            ),
+            Change::FunctionInputsRenamed { function, old, new } => write!(
+                f,
+                "function {function} inputs renamed from {old:?} to {new:?}"
+            ),
+            Change::FunctionOutputChanged { function, old, new } => write!(
+                f,
+                "function {function} output changed from {old:?} to {new:?}"
+            ),
+            Change::TypeAdded { name } => write!(f, "type {name} added"),
+            Change::TypeRemoved { name } => write!(f, "type {name} removed"),
+            Change::TypeKindChanged { name } => write!(f, "type {name} changed kind"),
+            Change::StructFieldAdded { name, field } => {
+                write!(f, "struct {name} field {field} added")
+            }
+            Change::StructFieldRemoved { name, field } => {
+                write!(f, "struct {name} field {field} removed")
+            }
+            Change::StructFieldTypeChanged {
+                name,
+                field,
+                old,
+                new,
+            } => write!(
+                f,
+                "struct {name} field {field} type changed from {old:?} to {new:?}"
+            ),
+            Change::UnionCaseAdded { name, case } => write!(f, "union {name} case {case} added"),
+            Change::UnionCaseRemoved { name, case } => {
+                write!(f, "union {name} case {case} removed")
+            }
+            Change::UnionCaseChanged { name, case } => {
+                write!(f, "union {name} case {case} changed")
+            }
+            Change::EnumCaseAdded { name, case } => write!(f, "enum {name} case {case} added"),
+            Change::EnumCaseRemoved { name, case } => {
+                write!(f, "enum {name} case {case} removed")
+            }
+            Change::EnumCaseRenumbered {
+                name,
+                case,
+                old,
+                new,
+            } => write!(
+                f,
+                "enum {name} case {case} value changed from {old} to {new}"
+            ),
+        }
+    }
+}
+
+/// Compares the spec of the contract in the old wasm with the spec of the
+/// contract in the new wasm.
+pub fn compare_wasm(old: &[u8], new: &[u8]) -> Result<Vec<Change>, FromWasmError> {
+    Ok(compare(&from_wasm(old)?, &from_wasm(new)?))
+}
+
+/// Compares the old spec of a contract with the new spec, returning the
+/// changes to functions and types in the order they appear in the old spec,
+/// followed by additions in the order they appear in the new spec.
+///
+/// Types are compared by name. A change to a type is reported once for the
+/// type, and not for each function that uses it.
+pub fn compare(old: &[ScSpecEntry], new: &[ScSpecEntry]) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for o in old {
+        let Some(name) = entry_name(o) else {
+            continue;
+        };
+        match (o, new.iter().find(|n| is_same_kind_and_name(o, n, &name))) {
+            (ScSpecEntry::FunctionV0(o), Some(ScSpecEntry::FunctionV0(n))) => {
+                compare_functions(&mut changes, o, n)
+            }
+            (ScSpecEntry::UdtStructV0(o), Some(ScSpecEntry::UdtStructV0(n))) => {
+                compare_structs(&mut changes, o, n)
+            }
+            (ScSpecEntry::UdtUnionV0(o), Some(ScSpecEntry::UdtUnionV0(n))) => {
+                compare_unions(&mut changes, o, n)
+            }
+            (ScSpecEntry::UdtEnumV0(o), Some(ScSpecEntry::UdtEnumV0(n))) => compare_enum_cases(
+                &mut changes,
+                &name,
+                &enum_cases(&o.cases),
+                &enum_cases(&n.cases),
+            ),
+            (ScSpecEntry::UdtErrorEnumV0(o), Some(ScSpecEntry::UdtErrorEnumV0(n))) => {
+                compare_enum_cases(
+                    &mut changes,
+                    &name,
+                    &error_enum_cases(&o.cases),
+                    &error_enum_cases(&n.cases),
+                )
+            }
+            (ScSpecEntry::FunctionV0(_), None) => {
+                changes.push(Change::FunctionRemoved { function: name })
+            }
+            (_, None) => {
+                if new
+                    .iter()
+                    .any(|n| is_type(n) && entry_name(n) == Some(name.clone()))
+                {
+                    changes.push(Change::TypeKindChanged { name });
+                } else {
+                    changes.push(Change::TypeRemoved { name });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for n in new {
+        let Some(name) = entry_name(n) else {
+            continue;
+        };
+        let existed = old.iter().any(|o| match n {
+            ScSpecEntry::FunctionV0(_) => is_same_kind_and_name(n, o, &name),
+            _ => is_type(o) && entry_name(o) == Some(name.clone()),
+        });
+        if !existed {
+            match n {
+                ScSpecEntry::FunctionV0(_) => {
+                    changes.push(Change::FunctionAdded { function: name })
+                }
+                _ => changes.push(Change::TypeAdded { name }),
+            }
+        }
+    }
+
+    changes
+}
+
+/// Returns the name of functions and types, or None for entries that are not
+/// compared.
+fn entry_name(entry: &ScSpecEntry) -> Option<String> {
+    match entry {
+        ScSpecEntry::FunctionV0(f) => Some(f.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtStructV0(s) => Some(s.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtUnionV0(u) => Some(u.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtEnumV0(e) => Some(e.name.to_utf8_string_lossy()),
+        ScSpecEntry::UdtErrorEnumV0(e) => Some(e.name.to_utf8_string_lossy()),
+        ScSpecEntry::EventV0(_) => None,
+    }
+}
+
+fn is_type(entry: &ScSpecEntry) -> bool {
+    matches!(
+        entry,
+        ScSpecEntry::UdtStructV0(_)
+            | ScSpecEntry::UdtUnionV0(_)
+            | ScSpecEntry::UdtEnumV0(_)
+            | ScSpecEntry::UdtErrorEnumV0(_)
+    )
+}
+
+fn is_same_kind_and_name(a: &ScSpecEntry, b: &ScSpecEntry, name: &str) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b) && entry_name(b).as_deref() == Some(name)
+}
+
+fn compare_functions(changes: &mut Vec<Change>, old: &ScSpecFunctionV0, new: &ScSpecFunctionV0) {
+    let function = old.name.to_utf8_string_lossy();
+
+    let old_types: Vec<_> = old.inputs.iter().map(|i| i.type_.clone()).collect();
+    let new_types: Vec<_> = new.inputs.iter().map(|i| i.type_.clone()).collect();
+    if old_types != new_types {
+        changes.push(Change::FunctionInputsChanged {
+            function: function.clone(),
+            old: old_types,
+            new: new_types,
+        });
+    } else {
+        let old_names: Vec<_> = old
+            .inputs
+            .iter()
+            .map(|i| i.name.to_utf8_string_lossy())
+            .collect();
+        let new_names: Vec<_> = new
+            .inputs
+            .iter()
+            .map(|i| i.name.to_utf8_string_lossy())
+            .collect();
+        if old_names != new_names {
+            changes.push(Change::FunctionInputsRenamed {
+                function: function.clone(),
+                old: old_names,
+                new: new_names,
+            });
+        }
+    }
+
+    let old_output = old.outputs.to_option();
+    let new_output = new.outputs.to_option();
+    if old_output != new_output {
+        changes.push(Change::FunctionOutputChanged {
+            function,
+            old: old_output,
+            new: new_output,
+        });
+    }
+}
+
+fn compare_structs(changes: &mut Vec<Change>, old: &ScSpecUdtStructV0, new: &ScSpecUdtStructV0) {
+    let name = old.name.to_utf8_string_lossy();
+    for o in old.fields.iter() {
+        let field = o.name.to_utf8_string_lossy();
+        match new.fields.iter().find(|n| n.name == o.name) {
+            Some(n) if n.type_ != o.type_ => changes.push(Change::StructFieldTypeChanged {
+                name: name.clone(),
+                field,
+                old: o.type_.clone(),
+                new: n.type_.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(Change::StructFieldRemoved {
+                name: name.clone(),
+                field,
+            }),
+        }
+    }
+    for n in new.fields.iter() {
+        if !old.fields.iter().any(|o| o.name == n.name) {
+            changes.push(Change::StructFieldAdded {
+                name: name.clone(),
+                field: n.name.to_utf8_string_lossy(),
+            });
+        }
+    }
+}
+
+fn union_case_name(case: &ScSpecUdtUnionCaseV0) -> String {
+    match case {
+        ScSpecUdtUnionCaseV0::VoidV0(v) => v.name.to_utf8_string_lossy(),
+        ScSpecUdtUnionCaseV0::TupleV0(t) => t.name.to_utf8_string_lossy(),
+    }
+}
+
+fn union_case_types(case: &ScSpecUdtUnionCaseV0) -> Option<&[ScSpecTypeDef]> {
+    match case {
+        ScSpecUdtUnionCaseV0::VoidV0(_) => None,
+        ScSpecUdtUnionCaseV0::TupleV0(t) => Some(&t.type_),
+    }
+}
+
+fn compare_unions(changes: &mut Vec<Change>, old: &ScSpecUdtUnionV0, new: &ScSpecUdtUnionV0) {
+    let name = old.name.to_utf8_string_lossy();
+    for o in old.cases.iter() {
+        let case = union_case_name(o);
+        match new.cases.iter().find(|n| union_case_name(n) == case) {
+            Some(n) if union_case_types(n) != union_case_types(o) => {
+                changes.push(Change::UnionCaseChanged {
+                    name: name.clone(),
+                    case,
+                })
+            }
+            Some(_) => {}
+            None => changes.push(Change::UnionCaseRemoved {
+                name: name.clone(),
+                case,
+            }),
+        }
+    }
+    for n in new.cases.iter() {
+        let case = union_case_name(n);
+        if !old.cases.iter().any(|o| union_case_name(o) == case) {
+            changes.push(Change::UnionCaseAdded {
+                name: name.clone(),
+                case,
+            });
+        }
+    }
+}
+
+fn enum_cases(cases: &[ScSpecUdtEnumCaseV0]) -> Vec<(String, u32)> {
+    cases
+        .iter()
+        .map(|c| (c.name.to_utf8_string_lossy(), c.value))
+        .collect()
+}
+
+fn error_enum_cases(cases: &[ScSpecUdtErrorEnumCaseV0]) -> Vec<(String, u32)> {
+    cases
+        .iter()
+        .map(|c| (c.name.to_utf8_string_lossy(), c.value))
+        .collect()
+}
+
+fn compare_enum_cases(
+    changes: &mut Vec<Change>,
+    name: &str,
+    old: &[(String, u32)],
+    new: &[(String, u32)],
+) {
+    for (case, old_value) in old {
+        match new.iter().find(|(n, _)| n == case) {
+            Some((_, new_value)) if new_value != old_value => {
+                changes.push(Change::EnumCaseRenumbered {
+                    name: name.to_string(),
+                    case: case.clone(),
+                    old: *old_value,
+                    new: *new_value,
+                })
+            }
+            Some(_) => {}
+            None => changes.push(Change::EnumCaseRemoved {
+                name: name.to_string(),
+                case: case.clone(),
+            }),
+        }
+    }
+    for (case, _) in new {
+        if !old.iter().any(|(o, _)| o == case) {
+            changes.push(Change::EnumCaseAdded {
+                name: name.to_string(),
+                case: case.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use stellar_xdr::curr as stellar_xdr;
+    use stellar_xdr::{
+        ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSpecTypeUdt,
+        ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0, ScSpecUdtErrorEnumCaseV0, ScSpecUdtErrorEnumV0,
+        ScSpecUdtStructFieldV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0,
+        ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0,
+    };
+
+    use super::{compare, Change, Severity};
+
+    fn function(
+        name: &str,
+        inputs: &[(&str, ScSpecTypeDef)],
+        output: Option<ScSpecTypeDef>,
+    ) -> ScSpecEntry {
+        ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+            doc: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            inputs: inputs
+                .iter()
+                .map(|(name, type_)| ScSpecFunctionInputV0 {
+                    doc: "".try_into().unwrap(),
+                    name: (*name).try_into().unwrap(),
+                    type_: type_.clone(),
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            outputs: output.into_iter().collect::<Vec<_>>().try_into().unwrap(),
+        })
+    }
+
+    fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: name.try_into().unwrap(),
+        })
+    }
+
+    fn struct_(name: &str, fields: &[(&str, ScSpecTypeDef)]) -> ScSpecEntry {
+        ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            fields: fields
+                .iter()
+                .map(|(name, type_)| ScSpecUdtStructFieldV0 {
+                    doc: "".try_into().unwrap(),
+                    name: (*name).try_into().unwrap(),
+                    type_: type_.clone(),
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        })
+    }
+
+    fn union(name: &str, cases: &[(&str, &[ScSpecTypeDef])]) -> ScSpecEntry {
+        ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            cases: cases
+                .iter()
+                .map(|(name, types)| {
+                    if types.is_empty() {
+                        ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                            doc: "".try_into().unwrap(),
+                            name: (*name).try_into().unwrap(),
+                        })
+                    } else {
+                        ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                            doc: "".try_into().unwrap(),
+                            name: (*name).try_into().unwrap(),
+                            type_: types.to_vec().try_into().unwrap(),
+                        })
+                    }
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        })
+    }
+
+    fn enum_(name: &str, cases: &[(&str, u32)]) -> ScSpecEntry {
+        ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            cases: cases
+                .iter()
+                .map(|(name, value)| ScSpecUdtEnumCaseV0 {
+                    doc: "".try_into().unwrap(),
+                    name: (*name).try_into().unwrap(),
+                    value: *value,
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        })
+    }
+
+    fn error_enum(name: &str, cases: &[(&str, u32)]) -> ScSpecEntry {
+        ScSpecEntry::UdtErrorEnumV0(ScSpecUdtErrorEnumV0 {
+            doc: "".try_into().unwrap(),
+            lib: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            cases: cases
+                .iter()
+                .map(|(name, value)| ScSpecUdtErrorEnumCaseV0 {
+                    doc: "".try_into().unwrap(),
+                    name: (*name).try_into().unwrap(),
+                    value: *value,
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        })
+    }
+
+    #[test]
+    fn unchanged() {
+        let spec = [
+            function(
+                "add",
+                &[("a", ScSpecTypeDef::U32)],
+                Some(ScSpecTypeDef::U32),
+            ),
+            struct_("S", &[("a", ScSpecTypeDef::U32)]),
+        ];
+        assert_eq!(compare(&spec, &spec), vec![]);
+    }
+
+    #[test]
+    fn functions() {
+        let old = [
+            function(
+                "add",
+                &[("a", ScSpecTypeDef::U32)],
+                Some(ScSpecTypeDef::U32),
+            ),
+            function("sub", &[("a", ScSpecTypeDef::U32)], None),
+            function(
+                "mul",
+                &[("a", ScSpecTypeDef::U32), ("b", ScSpecTypeDef::I32)],
+                None,
+            ),
+            function("div", &[("a", ScSpecTypeDef::U32)], None),
+        ];
+        let new = [
+            function(
+                "add",
+                &[("a", ScSpecTypeDef::U32)],
+                Some(ScSpecTypeDef::U64),
+            ),
+            function(
+                "mul",
+                &[("b", ScSpecTypeDef::I32), ("a", ScSpecTypeDef::U32)],
+                None,
+            ),
+            function("div", &[("x", ScSpecTypeDef::U32)], None),
+            function("pow", &[], None),
+        ];
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                Change::FunctionOutputChanged {
+                    function: "add".to_string(),
+                    old: Some(ScSpecTypeDef::U32),
+                    new: Some(ScSpecTypeDef::U64),
+                },
+                Change::FunctionRemoved {
+                    function: "sub".to_string()
+                },
+                Change::FunctionInputsChanged {
+                    function: "mul".to_string(),
+                    old: vec![ScSpecTypeDef::U32, ScSpecTypeDef::I32],
+                    new: vec![ScSpecTypeDef::I32, ScSpecTypeDef::U32],
+                },
+                Change::FunctionInputsRenamed {
+                    function: "div".to_string(),
+                    old: vec!["a".to_string()],
+                    new: vec!["x".to_string()],
+                },
+                Change::FunctionAdded {
+                    function: "pow".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            changes.iter().map(Change::severity).collect::<Vec<_>>(),
+            vec![
+                Severity::Breaking,
+                Severity::Breaking,
+                Severity::Breaking,
+                Severity::Info,
+                Severity::Info,
+            ]
+        );
+    }
+
+    #[test]
+    fn types() {
+        let old = [
+            struct_("S", &[("a", ScSpecTypeDef::U32), ("b", ScSpecTypeDef::U32)]),
+            union("U", &[("A", &[]), ("B", &[ScSpecTypeDef::U32]), ("C", &[])]),
+            enum_("E", &[("A", 1), ("B", 2)]),
+            error_enum("Error", &[("A", 1), ("B", 2)]),
+            struct_("K", &[]),
+            struct_("R", &[]),
+        ];
+        let new = [
+            struct_("S", &[("a", ScSpecTypeDef::U64), ("c", udt("E"))]),
+            union("U", &[("A", &[]), ("B", &[ScSpecTypeDef::U64]), ("D", &[])]),
+            enum_("E", &[("A", 1), ("B", 3), ("C", 4)]),
+            error_enum("Error", &[("B", 2)]),
+            union("K", &[]),
+            enum_("N", &[]),
+        ];
+        let changes = compare(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                Change::StructFieldTypeChanged {
+                    name: "S".to_string(),
+                    field: "a".to_string(),
+                    old: ScSpecTypeDef::U32,
+                    new: ScSpecTypeDef::U64,
+                },
+                Change::StructFieldRemoved {
+                    name: "S".to_string(),
+                    field: "b".to_string(),
+                },
+                Change::StructFieldAdded {
+                    name: "S".to_string(),
+                    field: "c".to_string(),
+                },
+                Change::UnionCaseChanged {
+                    name: "U".to_string(),
+                    case: "B".to_string(),
+                },
+                Change::UnionCaseRemoved {
+                    name: "U".to_string(),
+                    case: "C".to_string(),
+                },
+                Change::UnionCaseAdded {
+                    name: "U".to_string(),
+                    case: "D".to_string(),
+                },
+                Change::EnumCaseRenumbered {
+                    name: "E".to_string(),
+                    case: "B".to_string(),
+                    old: 2,
+                    new: 3,
+                },
+                Change::EnumCaseAdded {
+                    name: "E".to_string(),
+                    case: "C".to_string(),
+                },
+                Change::EnumCaseRemoved {
+                    name: "Error".to_string(),
+                    case: "A".to_string(),
+                },
+                Change::TypeKindChanged {
+                    name: "K".to_string()
+                },
+                Change::TypeRemoved {
+                    name: "R".to_string()
+                },
+                Change::TypeAdded {
+                    name: "N".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            changes[9].to_string(),
+            "breaking: type K changed kind".to_string()
+        );
+    }
+}
@@ -0,0 +1,1006 @@
+//! JSON converts values between [`ScVal`] and JSON, using the types in a
+//! contract spec to determine the representation of each value.
+//!
+//! Values are represented in JSON as described by the schemas generated by
+//! [`json_schema`][crate::json_schema], so that JSON produced by [`to_json`]
+//! validates against those schemas, and values converted with [`from_json`]
+//! and back again are unchanged.
+//!
+//! When converting from JSON, integers that are represented as strings may
+//! also be given as JSON numbers, and enum and error enum cases may also be
+//! given by name.
+//!
+//! Values of type `val` carry no type information in the spec. They are
+//! converted using the representation of the type of the value, and JSON is
+//! converted to the closest type, which means values of type `val` are not
+//! guaranteed to be unchanged after being converted to JSON and back again.
+use std::str::FromStr;
+
+use ethnum::{I256, U256};
+use serde_json::{Map, Value};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    Duration, Int256Parts, ScAddress, ScBytes, ScError, ScErrorCode, ScErrorType, ScMap,
+    ScMapEntry, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtEnumV0,
+    ScSpecUdtErrorEnumV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionV0, ScString,
+    ScSymbol, ScVal, ScVec, TimePoint, UInt256Parts,
+};
+
+use crate::marker::{is_tuple_struct, is_unit_struct};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("type {0} not found in contract spec")]
+    UdtNotFound(String),
+    #[error("function {0} not found in contract spec")]
+    FunctionNotFound(String),
+    #[error("argument {0} not found")]
+    ArgumentNotFound(String),
+    #[error("json value {1} is not a valid {0}")]
+    InvalidJson(String, Value),
+    #[error("value {1:?} is not a valid {0}")]
+    InvalidScVal(String, ScVal),
+}
+
+/// Converts the value of the type to JSON.
+pub fn to_json(specs: &[ScSpecEntry], type_: &ScSpecTypeDef, val: &ScVal) -> Result<Value, Error> {
+    let invalid = || Error::InvalidScVal(type_name(type_), val.clone());
+    Ok(match (type_, val) {
+        (ScSpecTypeDef::Val, _) => val_to_json(val)?,
+        (ScSpecTypeDef::Bool, ScVal::Bool(b)) => Value::Bool(*b),
+        (ScSpecTypeDef::Void, ScVal::Void) => Value::Null,
+        (ScSpecTypeDef::Error, ScVal::Error(e)) => error_to_json(e),
+        (ScSpecTypeDef::U32, ScVal::U32(v)) => Value::from(*v),
+        (ScSpecTypeDef::I32, ScVal::I32(v)) => Value::from(*v),
+        (ScSpecTypeDef::U64, ScVal::U64(v))
+        | (ScSpecTypeDef::Timepoint, ScVal::Timepoint(TimePoint(v)))
+        | (ScSpecTypeDef::Duration, ScVal::Duration(Duration(v))) => Value::String(v.to_string()),
+        (ScSpecTypeDef::I64, ScVal::I64(v)) => Value::String(v.to_string()),
+        (ScSpecTypeDef::U128, ScVal::U128(v)) => Value::String(u128::from(v).to_string()),
+        (ScSpecTypeDef::I128, ScVal::I128(v)) => Value::String(i128::from(v).to_string()),
+        (ScSpecTypeDef::U256, ScVal::U256(v)) => Value::String(u256_from_parts(v).to_string()),
+        (ScSpecTypeDef::I256, ScVal::I256(v)) => Value::String(i256_from_parts(v).to_string()),
+        (ScSpecTypeDef::Bytes, ScVal::Bytes(b)) => Value::String(hex::encode(b)),
+        (ScSpecTypeDef::BytesN(n), ScVal::Bytes(b)) if b.len() == n.n as usize => {
+            Value::String(hex::encode(b))
+        }
+        (ScSpecTypeDef::String, ScVal::String(s)) => Value::String(s.to_utf8_string_lossy()),
+        (ScSpecTypeDef::Symbol, ScVal::Symbol(s)) => Value::String(s.to_utf8_string_lossy()),
+        (ScSpecTypeDef::Address, ScVal::Address(a)) if !matches!(a, ScAddress::MuxedAccount(_)) => {
+            Value::String(a.to_string())
+        }
+        (ScSpecTypeDef::MuxedAddress, ScVal::Address(a)) => Value::String(a.to_string()),
+        (ScSpecTypeDef::Option(_), ScVal::Void) => Value::Null,
+        (ScSpecTypeDef::Option(o), _) => to_json(specs, &o.value_type, val)?,
+        (ScSpecTypeDef::Result(r), ScVal::Error(_)) => {
+            object("error", to_json(specs, &r.error_type, val)?)
+        }
+        (ScSpecTypeDef::Result(r), _) => object("ok", to_json(specs, &r.ok_type, val)?),
+        (ScSpecTypeDef::Vec(v), ScVal::Vec(Some(items))) => Value::Array(
+            items
+                .iter()
+                .map(|i| to_json(specs, &v.element_type, i))
+                .collect::<Result<_, _>>()?,
+        ),
+        (ScSpecTypeDef::Map(m), ScVal::Map(Some(entries))) => match *m.key_type {
+            ScSpecTypeDef::String | ScSpecTypeDef::Symbol => Value::Object(
+                entries
+                    .iter()
+                    .map(|e| {
+                        let Value::String(k) = to_json(specs, &m.key_type, &e.key)? else {
+                            unreachable!()
+                        };
+                        Ok((k, to_json(specs, &m.value_type, &e.val)?))
+                    })
+                    .collect::<Result<_, Error>>()?,
+            ),
+            _ => Value::Array(
+                entries
+                    .iter()
+                    .map(|e| {
+                        Ok(Value::Array(vec![
+                            to_json(specs, &m.key_type, &e.key)?,
+                            to_json(specs, &m.value_type, &e.val)?,
+                        ]))
+                    })
+                    .collect::<Result<_, Error>>()?,
+            ),
+        },
+        (ScSpecTypeDef::Tuple(t), ScVal::Vec(Some(items)))
+            if items.len() == t.value_types.len() =>
+        {
+            Value::Array(
+                t.value_types
+                    .iter()
+                    .zip(items.iter())
+                    .map(|(t, i)| to_json(specs, t, i))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        (ScSpecTypeDef::Udt(u), _) => {
+            let name = u.name.to_utf8_string_lossy();
+            match find_udt(specs, &name)? {
+                ScSpecEntry::UdtStructV0(s) => struct_to_json(specs, s, val)?,
+                ScSpecEntry::UdtUnionV0(u) => union_to_json(specs, u, val)?,
+                ScSpecEntry::UdtEnumV0(e) => enum_to_json(e, val)?,
+                ScSpecEntry::UdtErrorEnumV0(e) => error_enum_to_json(e, val)?,
+                ScSpecEntry::FunctionV0(_) | ScSpecEntry::EventV0(_) => {
+                    return Err(Error::UdtNotFound(name))
+                }
+            }
+        }
+        _ => return Err(invalid()),
+    })
+}
+
+/// Converts the JSON to a value of the type.
+pub fn from_json(
+    specs: &[ScSpecEntry],
+    type_: &ScSpecTypeDef,
+    value: &Value,
+) -> Result<ScVal, Error> {
+    let invalid = || Error::InvalidJson(type_name(type_), value.clone());
+    Ok(match (type_, value) {
+        (ScSpecTypeDef::Val, _) => val_from_json(value)?,
+        (ScSpecTypeDef::Bool, Value::Bool(b)) => ScVal::Bool(*b),
+        (ScSpecTypeDef::Void, Value::Null) => ScVal::Void,
+        (ScSpecTypeDef::Error, _) => ScVal::Error(error_from_json(value).ok_or_else(invalid)?),
+        (ScSpecTypeDef::U32, _) => ScVal::U32(parse_int(value).ok_or_else(invalid)?),
+        (ScSpecTypeDef::I32, _) => ScVal::I32(parse_int(value).ok_or_else(invalid)?),
+        (ScSpecTypeDef::U64, _) => ScVal::U64(parse_int(value).ok_or_else(invalid)?),
+        (ScSpecTypeDef::I64, _) => ScVal::I64(parse_int(value).ok_or_else(invalid)?),
+        (ScSpecTypeDef::Timepoint, _) => {
+            ScVal::Timepoint(TimePoint(parse_int(value).ok_or_else(invalid)?))
+        }
+        (ScSpecTypeDef::Duration, _) => {
+            ScVal::Duration(Duration(parse_int(value).ok_or_else(invalid)?))
+        }
+        (ScSpecTypeDef::U128, _) => parse_int::<u128>(value).ok_or_else(invalid)?.into(),
+        (ScSpecTypeDef::I128, _) => parse_int::<i128>(value).ok_or_else(invalid)?.into(),
+        (ScSpecTypeDef::U256, _) => {
+            ScVal::U256(u256_to_parts(parse_int(value).ok_or_else(invalid)?))
+        }
+        (ScSpecTypeDef::I256, _) => {
+            ScVal::I256(i256_to_parts(parse_int(value).ok_or_else(invalid)?))
+        }
+        (ScSpecTypeDef::Bytes, Value::String(s)) => {
+            ScVal::Bytes(parse_bytes(s).ok_or_else(invalid)?)
+        }
+        (ScSpecTypeDef::BytesN(n), Value::String(s)) => {
+            let b = parse_bytes(s).ok_or_else(invalid)?;
+            if b.len() != n.n as usize {
+                return Err(invalid());
+            }
+            ScVal::Bytes(b)
+        }
+        (ScSpecTypeDef::String, Value::String(s)) => {
+            ScVal::String(ScString(s.as_str().try_into().map_err(|_| invalid())?))
+        }
+        (ScSpecTypeDef::Symbol, Value::String(s)) => {
+            ScVal::Symbol(ScSymbol(s.as_str().try_into().map_err(|_| invalid())?))
+        }
+        (ScSpecTypeDef::Address, Value::String(s)) => match ScAddress::from_str(s) {
+            Ok(ScAddress::MuxedAccount(_)) | Err(_) => return Err(invalid()),
+            Ok(a) => ScVal::Address(a),
+        },
+        (ScSpecTypeDef::MuxedAddress, Value::String(s)) => {
+            ScVal::Address(ScAddress::from_str(s).map_err(|_| invalid())?)
+        }
+        (ScSpecTypeDef::Option(_), Value::Null) => ScVal::Void,
+        (ScSpecTypeDef::Option(o), _) => from_json(specs, &o.value_type, value)?,
+        (ScSpecTypeDef::Result(r), Value::Object(o)) if o.len() == 1 => {
+            match o.iter().next().unwrap() {
+                (k, v) if k == "ok" => from_json(specs, &r.ok_type, v)?,
+                (k, v) if k == "error" => from_json(specs, &r.error_type, v)?,
+                _ => return Err(invalid()),
+            }
+        }
+        (ScSpecTypeDef::Vec(v), Value::Array(items)) => vec(items
+            .iter()
+            .map(|i| from_json(specs, &v.element_type, i))
+            .collect::<Result<_, _>>()?)
+        .ok_or_else(invalid)?,
+        (ScSpecTypeDef::Map(m), Value::Object(o))
+            if matches!(*m.key_type, ScSpecTypeDef::String | ScSpecTypeDef::Symbol) =>
+        {
+            map(o
+                .iter()
+                .map(|(k, v)| {
+                    Ok(ScMapEntry {
+                        key: from_json(specs, &m.key_type, &Value::String(k.clone()))?,
+                        val: from_json(specs, &m.value_type, v)?,
+                    })
+                })
+                .collect::<Result<_, Error>>()?)
+            .ok_or_else(invalid)?
+        }
+        (ScSpecTypeDef::Map(m), Value::Array(entries)) => map(entries
+            .iter()
+            .map(|e| match e {
+                Value::Array(kv) if kv.len() == 2 => Ok(ScMapEntry {
+                    key: from_json(specs, &m.key_type, &kv[0])?,
+                    val: from_json(specs, &m.value_type, &kv[1])?,
+                }),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<_, Error>>()?)
+        .ok_or_else(invalid)?,
+        (ScSpecTypeDef::Tuple(t), Value::Array(items)) if items.len() == t.value_types.len() => {
+            vec(t
+                .value_types
+                .iter()
+                .zip(items)
+                .map(|(t, i)| from_json(specs, t, i))
+                .collect::<Result<_, _>>()?)
+            .ok_or_else(invalid)?
+        }
+        (ScSpecTypeDef::Udt(u), _) => {
+            let name = u.name.to_utf8_string_lossy();
+            match find_udt(specs, &name)? {
+                ScSpecEntry::UdtStructV0(s) => struct_from_json(specs, s, value)?,
+                ScSpecEntry::UdtUnionV0(u) => union_from_json(specs, u, value)?,
+                ScSpecEntry::UdtEnumV0(e) => enum_from_json(e, value)?,
+                ScSpecEntry::UdtErrorEnumV0(e) => error_enum_from_json(e, value)?,
+                ScSpecEntry::FunctionV0(_) | ScSpecEntry::EventV0(_) => {
+                    return Err(Error::UdtNotFound(name))
+                }
+            }
+        }
+        _ => return Err(invalid()),
+    })
+}
+
+/// Converts the JSON object of the arguments of the function to the values of
+/// the arguments, in the order they are passed to the function.
+///
+/// The object has a property for each argument, as described by
+/// [`generate_function_args`][crate::json_schema::generate_function_args].
+pub fn args_from_json(
+    specs: &[ScSpecEntry],
+    function: &str,
+    args: &Value,
+) -> Result<Vec<ScVal>, Error> {
+    let f = find_function(specs, function)?;
+    let Value::Object(o) = args else {
+        return Err(Error::InvalidJson(
+            format!("arguments of {function}"),
+            args.clone(),
+        ));
+    };
+    f.inputs
+        .iter()
+        .map(|i| {
+            let name = i.name.to_utf8_string_lossy();
+            let value = o.get(&name).ok_or(Error::ArgumentNotFound(name))?;
+            from_json(specs, &i.type_, value)
+        })
+        .collect()
+}
+
+/// Converts the value returned by the function to JSON.
+pub fn result_to_json(specs: &[ScSpecEntry], function: &str, val: &ScVal) -> Result<Value, Error> {
+    let f = find_function(specs, function)?;
+    match f.outputs.first() {
+        Some(output) => to_json(specs, output, val),
+        None => to_json(specs, &ScSpecTypeDef::Void, val),
+    }
+}
+
+fn struct_to_json(
+    specs: &[ScSpecEntry],
+    spec: &ScSpecUdtStructV0,
+    val: &ScVal,
+) -> Result<Value, Error> {
+    let invalid = || Error::InvalidScVal(spec.name.to_utf8_string_lossy(), val.clone());
+    if is_unit_struct(spec) {
+        let ScVal::Void = val else {
+            return Err(invalid());
+        };
+        Ok(Value::Null)
+    } else if is_tuple_struct(spec) {
+        let ScVal::Vec(Some(items)) = val else {
+            return Err(invalid());
+        };
+        if items.len() != spec.fields.len() {
+            return Err(invalid());
+        }
+        Ok(Value::Array(
+            spec.fields
+                .iter()
+                .zip(items.iter())
+                .map(|(f, i)| to_json(specs, &f.type_, i))
+                .collect::<Result<_, _>>()?,
+        ))
+    } else {
+        let ScVal::Map(Some(entries)) = val else {
+            return Err(invalid());
+        };
+        if entries.len() != spec.fields.len() {
+            return Err(invalid());
+        }
+        spec.fields
+            .iter()
+            .map(|f| {
+                let name = f.name.to_utf8_string_lossy();
+                let key =
+                    ScVal::Symbol(ScSymbol(f.name.to_vec().try_into().map_err(|_| invalid())?));
+                let entry = entries.iter().find(|e| e.key == key).ok_or_else(invalid)?;
+                Ok((name, to_json(specs, &f.type_, &entry.val)?))
+            })
+            .collect::<Result<Map<_, _>, _>>()
+            .map(Value::Object)
+    }
+}
+
+fn struct_from_json(
+    specs: &[ScSpecEntry],
+    spec: &ScSpecUdtStructV0,
+    value: &Value,
+) -> Result<ScVal, Error> {
+    let invalid = || Error::InvalidJson(spec.name.to_utf8_string_lossy(), value.clone());
+    match value {
+        Value::Null if is_unit_struct(spec) => Ok(ScVal::Void),
+        Value::Array(items) if is_tuple_struct(spec) && items.len() == spec.fields.len() => {
+            vec(spec
+                .fields
+                .iter()
+                .zip(items)
+                .map(|(f, i)| from_json(specs, &f.type_, i))
+                .collect::<Result<_, _>>()?)
+            .ok_or_else(invalid)
+        }
+        Value::Object(o) if !is_tuple_struct(spec) && o.len() == spec.fields.len() => map(spec
+            .fields
+            .iter()
+            .map(|f| {
+                let v = o.get(&f.name.to_utf8_string_lossy()).ok_or_else(invalid)?;
+                Ok(ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol(
+                        f.name.to_vec().try_into().map_err(|_| invalid())?,
+                    )),
+                    val: from_json(specs, &f.type_, v)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?)
+        .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+fn union_to_json(
+    specs: &[ScSpecEntry],
+    spec: &ScSpecUdtUnionV0,
+    val: &ScVal,
+) -> Result<Value, Error> {
+    let invalid = || Error::InvalidScVal(spec.name.to_utf8_string_lossy(), val.clone());
+    let ScVal::Vec(Some(items)) = val else {
+        return Err(invalid());
+    };
+    let Some((ScVal::Symbol(name), values)) = items.split_first() else {
+        return Err(invalid());
+    };
+    let case = spec
+        .cases
+        .iter()
+        .find(|c| case_name(c) == name.as_vec())
+        .ok_or_else(invalid)?;
+    match case {
+        ScSpecUdtUnionCaseV0::VoidV0(v) if values.is_empty() => {
+            Ok(Value::String(v.name.to_utf8_string_lossy()))
+        }
+        ScSpecUdtUnionCaseV0::TupleV0(t) if values.len() == t.type_.len() => Ok(object(
+            &t.name.to_utf8_string_lossy(),
+            Value::Array(
+                t.type_
+                    .iter()
+                    .zip(values)
+                    .map(|(t, v)| to_json(specs, t, v))
+                    .collect::<Result<_, _>>()?,
+            ),
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn union_from_json(
+    specs: &[ScSpecEntry],
+    spec: &ScSpecUdtUnionV0,
+    value: &Value,
+) -> Result<ScVal, Error> {
+    let invalid = || Error::InvalidJson(spec.name.to_utf8_string_lossy(), value.clone());
+    let (name, values) = match value {
+        Value::String(name) => (name, None),
+        Value::Object(o) if o.len() == 1 => {
+            let (name, values) = o.iter().next().unwrap();
+            (name, Some(values))
+        }
+        _ => return Err(invalid()),
+    };
+    let case = spec
+        .cases
+        .iter()
+        .find(|c| case_name(c) == name.as_bytes())
+        .ok_or_else(invalid)?;
+    let symbol = ScVal::Symbol(ScSymbol(
+        case_name(case).to_vec().try_into().map_err(|_| invalid())?,
+    ));
+    match (case, values) {
+        (ScSpecUdtUnionCaseV0::VoidV0(_), None) => vec(vec![symbol]).ok_or_else(invalid),
+        (ScSpecUdtUnionCaseV0::TupleV0(t), Some(Value::Array(values)))
+            if values.len() == t.type_.len() =>
+        {
+            let mut items = vec![symbol];
+            for (t, v) in t.type_.iter().zip(values) {
+                items.push(from_json(specs, t, v)?);
+            }
+            vec(items).ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn enum_to_json(spec: &ScSpecUdtEnumV0, val: &ScVal) -> Result<Value, Error> {
+    match val {
+        ScVal::U32(v) if spec.cases.iter().any(|c| c.value == *v) => Ok(Value::from(*v)),
+        _ => Err(Error::InvalidScVal(
+            spec.name.to_utf8_string_lossy(),
+            val.clone(),
+        )),
+    }
+}
+
+fn enum_from_json(spec: &ScSpecUdtEnumV0, value: &Value) -> Result<ScVal, Error> {
+    spec.cases
+        .iter()
+        .find(|c| is_case(value, &c.name.to_utf8_string_lossy(), c.value))
+        .map(|c| ScVal::U32(c.value))
+        .ok_or_else(|| Error::InvalidJson(spec.name.to_utf8_string_lossy(), value.clone()))
+}
+
+fn error_enum_to_json(spec: &ScSpecUdtErrorEnumV0, val: &ScVal) -> Result<Value, Error> {
+    match val {
+        ScVal::Error(ScError::Contract(v)) if spec.cases.iter().any(|c| c.value == *v) => {
+            Ok(Value::from(*v))
+        }
+        _ => Err(Error::InvalidScVal(
+            spec.name.to_utf8_string_lossy(),
+            val.clone(),
+        )),
+    }
+}
+
+fn error_enum_from_json(spec: &ScSpecUdtErrorEnumV0, value: &Value) -> Result<ScVal, Error> {
+    spec.cases
+        .iter()
+        .find(|c| is_case(value, &c.name.to_utf8_string_lossy(), c.value))
+        .map(|c| ScVal::Error(ScError::Contract(c.value)))
+        .ok_or_else(|| Error::InvalidJson(spec.name.to_utf8_string_lossy(), value.clone()))
+}
+
+/// Converts a value without type information in the spec to JSON, using the
+/// representation of the type of the value.
+fn val_to_json(val: &ScVal) -> Result<Value, Error> {
+    let type_ = match val {
+        ScVal::Bool(_) => ScSpecTypeDef::Bool,
+        ScVal::Void => ScSpecTypeDef::Void,
+        ScVal::Error(_) => ScSpecTypeDef::Error,
+        ScVal::U32(_) => ScSpecTypeDef::U32,
+        ScVal::I32(_) => ScSpecTypeDef::I32,
+        ScVal::U64(_) => ScSpecTypeDef::U64,
+        ScVal::I64(_) => ScSpecTypeDef::I64,
+        ScVal::Timepoint(_) => ScSpecTypeDef::Timepoint,
+        ScVal::Duration(_) => ScSpecTypeDef::Duration,
+        ScVal::U128(_) => ScSpecTypeDef::U128,
+        ScVal::I128(_) => ScSpecTypeDef::I128,
+        ScVal::U256(_) => ScSpecTypeDef::U256,
+        ScVal::I256(_) => ScSpecTypeDef::I256,
+        ScVal::Bytes(_) => ScSpecTypeDef::Bytes,
+        ScVal::String(_) => ScSpecTypeDef::String,
+        ScVal::Symbol(_) => ScSpecTypeDef::Symbol,
+        ScVal::Address(_) => ScSpecTypeDef::MuxedAddress,
+        ScVal::Vec(Some(items)) => {
+            return Ok(Value::Array(
+                items.iter().map(val_to_json).collect::<Result<_, _>>()?,
+            ))
+        }
+        ScVal::Map(Some(entries)) => {
+            return if entries
+                .iter()
+                .all(|e| matches!(e.key, ScVal::Symbol(_) | ScVal::String(_)))
+            {
+                Ok(Value::Object(
+                    entries
+                        .iter()
+                        .map(|e| {
+                            let Value::String(k) = val_to_json(&e.key)? else {
+                                unreachable!()
+                            };
+                            Ok((k, val_to_json(&e.val)?))
+                        })
+                        .collect::<Result<_, Error>>()?,
+                ))
+            } else {
+                Ok(Value::Array(
+                    entries
+                        .iter()
+                        .map(|e| {
+                            Ok(Value::Array(vec![
+                                val_to_json(&e.key)?,
+                                val_to_json(&e.val)?,
+                            ]))
+                        })
+                        .collect::<Result<_, Error>>()?,
+                ))
+            };
+        }
+        _ => {
+            return Err(Error::InvalidScVal(
+                type_name(&ScSpecTypeDef::Val),
+                val.clone(),
+            ))
+        }
+    };
+    to_json(&[], &type_, val)
+}
+
+/// Converts JSON to a value without type information in the spec, using the
+/// closest type to the JSON.
+fn val_from_json(value: &Value) -> Result<ScVal, Error> {
+    let invalid = || Error::InvalidJson(type_name(&ScSpecTypeDef::Val), value.clone());
+    Ok(match value {
+        Value::Null => ScVal::Void,
+        Value::Bool(b) => ScVal::Bool(*b),
+        Value::Number(n) => {
+            if let Some(v) = n.as_u64() {
+                u32::try_from(v).map_or(ScVal::U64(v), ScVal::U32)
+            } else if let Some(v) = n.as_i64() {
+                i32::try_from(v).map_or(ScVal::I64(v), ScVal::I32)
+            } else {
+                return Err(invalid());
+            }
+        }
+        Value::String(s) => ScVal::String(ScString(s.as_str().try_into().map_err(|_| invalid())?)),
+        Value::Array(items) => {
+            vec(items.iter().map(val_from_json).collect::<Result<_, _>>()?).ok_or_else(invalid)?
+        }
+        Value::Object(o) => map(o
+            .iter()
+            .map(|(k, v)| {
+                Ok(ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol(k.as_str().try_into().map_err(|_| invalid())?)),
+                    val: val_from_json(v)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?)
+        .ok_or_else(invalid)?,
+    })
+}
+
+fn error_to_json(e: &ScError) -> Value {
+    let code = match e {
+        ScError::Contract(c) => *c,
+        ScError::WasmVm(c)
+        | ScError::Context(c)
+        | ScError::Storage(c)
+        | ScError::Object(c)
+        | ScError::Crypto(c)
+        | ScError::Events(c)
+        | ScError::Budget(c)
+        | ScError::Value(c)
+        | ScError::Auth(c) => *c as u32,
+    };
+    let mut o = Map::new();
+    o.insert("type".to_string(), e.name().into());
+    o.insert("code".to_string(), code.into());
+    Value::Object(o)
+}
+
+fn error_from_json(value: &Value) -> Option<ScError> {
+    let o = value.as_object().filter(|o| o.len() == 2)?;
+    let type_ = o.get("type")?.as_str()?;
+    let code: u32 = parse_int(o.get("code")?)?;
+    let type_ = ScErrorType::VARIANTS
+        .into_iter()
+        .find(|t| t.name() == type_)?;
+    if type_ == ScErrorType::Contract {
+        return Some(ScError::Contract(code));
+    }
+    let code = ScErrorCode::try_from(i32::try_from(code).ok()?).ok()?;
+    Some(match type_ {
+        ScErrorType::Contract => unreachable!(),
+        ScErrorType::WasmVm => ScError::WasmVm(code),
+        ScErrorType::Context => ScError::Context(code),
+        ScErrorType::Storage => ScError::Storage(code),
+        ScErrorType::Object => ScError::Object(code),
+        ScErrorType::Crypto => ScError::Crypto(code),
+        ScErrorType::Events => ScError::Events(code),
+        ScErrorType::Budget => ScError::Budget(code),
+        ScErrorType::Value => ScError::Value(code),
+        ScErrorType::Auth => ScError::Auth(code),
+    })
+}
+
+fn find_udt<'a>(specs: &'a [ScSpecEntry], name: &str) -> Result<&'a ScSpecEntry, Error> {
+    specs
+        .iter()
+        .find(|s| match s {
+            ScSpecEntry::UdtStructV0(s) => s.name.to_utf8_string_lossy() == name,
+            ScSpecEntry::UdtUnionV0(u) => u.name.to_utf8_string_lossy() == name,
+            ScSpecEntry::UdtEnumV0(e) => e.name.to_utf8_string_lossy() == name,
+            ScSpecEntry::UdtErrorEnumV0(e) => e.name.to_utf8_string_lossy() == name,
+            ScSpecEntry::FunctionV0(_) | ScSpecEntry::EventV0(_) => false,
+        })
+        .ok_or_else(|| Error::UdtNotFound(name.to_string()))
+}
+
+fn find_function<'a>(specs: &'a [ScSpecEntry], name: &str) -> Result<&'a ScSpecFunctionV0, Error> {
+    specs
+        .iter()
+        .find_map(|s| match s {
+            ScSpecEntry::FunctionV0(f) if f.name.to_utf8_string_lossy() == name => Some(f),
+            _ => None,
+        })
+        .ok_or_else(|| Error::FunctionNotFound(name.to_string()))
+}
+
+fn type_name(type_: &ScSpecTypeDef) -> String {
+    match type_ {
+        ScSpecTypeDef::Udt(u) => u.name.to_utf8_string_lossy(),
+        _ => type_.name().to_string(),
+    }
+}
+
+fn case_name(case: &ScSpecUdtUnionCaseV0) -> &[u8] {
+    match case {
+        ScSpecUdtUnionCaseV0::VoidV0(v) => v.name.as_vec(),
+        ScSpecUdtUnionCaseV0::TupleV0(t) => t.name.as_vec(),
+    }
+}
+
+fn is_case(value: &Value, name: &str, case_value: u32) -> bool {
+    match value {
+        Value::String(s) => s == name,
+        _ => parse_int::<u32>(value) == Some(case_value),
+    }
+}
+
+fn object(key: &str, value: Value) -> Value {
+    let mut o = Map::new();
+    o.insert(key.to_string(), value);
+    Value::Object(o)
+}
+
+fn vec(items: Vec<ScVal>) -> Option<ScVal> {
+    Some(ScVal::Vec(Some(ScVec(items.try_into().ok()?))))
+}
+
+fn map(entries: Vec<ScMapEntry>) -> Option<ScVal> {
+    Some(ScVal::Map(Some(ScMap::sorted_from(entries).ok()?)))
+}
+
+/// Parses an integer from a JSON string containing the decimal integer, or
+/// from a JSON number.
+fn parse_int<T: FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) if n.is_u64() || n.is_i64() => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_bytes(s: &str) -> Option<ScBytes> {
+    hex::decode(s).ok()?.try_into().ok().map(ScBytes)
+}
+
+fn u256_from_parts(v: &UInt256Parts) -> U256 {
+    U256::from_words(
+        (u128::from(v.hi_hi) << 64) | u128::from(v.hi_lo),
+        (u128::from(v.lo_hi) << 64) | u128::from(v.lo_lo),
+    )
+}
+
+fn u256_to_parts(v: U256) -> UInt256Parts {
+    let (hi, lo) = v.into_words();
+    UInt256Parts {
+        hi_hi: (hi >> 64) as u64,
+        hi_lo: hi as u64,
+        lo_hi: (lo >> 64) as u64,
+        lo_lo: lo as u64,
+    }
+}
+
+fn i256_from_parts(v: &Int256Parts) -> I256 {
+    I256::from_words(
+        (i128::from(v.hi_hi) << 64) | i128::from(v.hi_lo),
+        ((u128::from(v.lo_hi) << 64) | u128::from(v.lo_lo)) as i128,
+    )
+}
+
+fn i256_to_parts(v: I256) -> Int256Parts {
+    let (hi, lo) = v.into_words();
+    Int256Parts {
+        hi_hi: (hi >> 64) as i64,
+        hi_lo: hi as u64,
+        lo_hi: ((lo as u128) >> 64) as u64,
+        lo_lo: lo as u64,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+    use stellar_xdr::curr as stellar_xdr;
+    use stellar_xdr::{
+        ScError, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeBytesN,
+        ScSpecTypeDef, ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeResult, ScSpecTypeTuple,
+        ScSpecTypeUdt, ScSpecTypeVec, ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0,
+        ScSpecUdtErrorEnumCaseV0, ScSpecUdtErrorEnumV0, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+        ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+        ScSpecUdtUnionV0, ScVal,
+    };
+
+    use super::{args_from_json, from_json, result_to_json, to_json};
+    use crate::marker::UNIT_STRUCT_DOC;
+
+    fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: name.try_into().unwrap(),
+        })
+    }
+
+    fn specs() -> Vec<ScSpecEntry> {
+        vec![
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Point".try_into().unwrap(),
+                fields: [("x", ScSpecTypeDef::I128), ("y", ScSpecTypeDef::U256)]
+                    .into_iter()
+                    .map(|(name, type_)| ScSpecUdtStructFieldV0 {
+                        doc: "".try_into().unwrap(),
+                        name: name.try_into().unwrap(),
+                        type_,
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            }),
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: UNIT_STRUCT_DOC.try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Marker".try_into().unwrap(),
+                fields: vec![].try_into().unwrap(),
+            }),
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Empty".try_into().unwrap(),
+                fields: vec![].try_into().unwrap(),
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Shape".try_into().unwrap(),
+                cases: vec![
+                    ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "Empty".try_into().unwrap(),
+                    }),
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "Line".try_into().unwrap(),
+                        type_: vec![udt("Point"), udt("Point")].try_into().unwrap(),
+                    }),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Color".try_into().unwrap(),
+                cases: vec![ScSpecUdtEnumCaseV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "Red".try_into().unwrap(),
+                    value: 1,
+                }]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtErrorEnumV0(ScSpecUdtErrorEnumV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Error".try_into().unwrap(),
+                cases: vec![ScSpecUdtErrorEnumCaseV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "NotFound".try_into().unwrap(),
+                    value: 3,
+                }]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+                doc: "".try_into().unwrap(),
+                name: "draw".try_into().unwrap(),
+                inputs: vec![
+                    ScSpecFunctionInputV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "shape".try_into().unwrap(),
+                        type_: udt("Shape"),
+                    },
+                    ScSpecFunctionInputV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "color".try_into().unwrap(),
+                        type_: ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                            value_type: Box::new(udt("Color")),
+                        })),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+                outputs: vec![ScSpecTypeDef::Result(Box::new(ScSpecTypeResult {
+                    ok_type: Box::new(ScSpecTypeDef::U32),
+                    error_type: Box::new(udt("Error")),
+                }))]
+                .try_into()
+                .unwrap(),
+            }),
+        ]
+    }
+
+    fn roundtrip(type_: ScSpecTypeDef, value: Value) {
+        let specs = specs();
+        let val = from_json(&specs, &type_, &value).unwrap();
+        assert_eq!(to_json(&specs, &type_, &val).unwrap(), value);
+    }
+
+    #[test]
+    fn roundtrip_types() {
+        roundtrip(ScSpecTypeDef::U32, json!(7));
+        roundtrip(ScSpecTypeDef::I64, json!("-9223372036854775808"));
+        roundtrip(
+            ScSpecTypeDef::U128,
+            json!("340282366920938463463374607431768211455"),
+        );
+        roundtrip(
+            ScSpecTypeDef::I128,
+            json!("-170141183460469231731687303715884105728"),
+        );
+        roundtrip(
+            ScSpecTypeDef::U256,
+            json!("115792089237316195423570985008687907853269984665640564039457584007913129639935"),
+        );
+        roundtrip(
+            ScSpecTypeDef::I256,
+            json!("-57896044618658097711785492504343953926634992332820282019728792003956564819968"),
+        );
+        roundtrip(ScSpecTypeDef::Bytes, json!("00ff10"));
+        roundtrip(
+            ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 2 }),
+            json!("abcd"),
+        );
+        roundtrip(
+            ScSpecTypeDef::Address,
+            json!("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"),
+        );
+        roundtrip(
+            ScSpecTypeDef::Address,
+            json!("CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE"),
+        );
+        roundtrip(
+            ScSpecTypeDef::Error,
+            json!({ "type": "Contract", "code": 4 }),
+        );
+        roundtrip(ScSpecTypeDef::Error, json!({ "type": "Auth", "code": 2 }));
+        roundtrip(
+            ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                element_type: Box::new(ScSpecTypeDef::Symbol),
+            })),
+            json!(["a", "b"]),
+        );
+        roundtrip(
+            ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+                key_type: Box::new(ScSpecTypeDef::Symbol),
+                value_type: Box::new(ScSpecTypeDef::Bool),
+            })),
+            json!({ "a": true, "b": false }),
+        );
+        roundtrip(
+            ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+                key_type: Box::new(ScSpecTypeDef::U32),
+                value_type: Box::new(ScSpecTypeDef::Bool),
+            })),
+            json!([[1, true], [2, false]]),
+        );
+        roundtrip(
+            ScSpecTypeDef::Tuple(Box::new(ScSpecTypeTuple {
+                value_types: vec![ScSpecTypeDef::U64, ScSpecTypeDef::String]
+                    .try_into()
+                    .unwrap(),
+            })),
+            json!(["1", "hello"]),
+        );
+        roundtrip(ScSpecTypeDef::Val, json!({ "a": [1, null, "s"] }));
+    }
+
+    #[test]
+    fn roundtrip_udts() {
+        roundtrip(udt("Point"), json!({ "x": "-1", "y": "2" }));
+        roundtrip(udt("Shape"), json!("Empty"));
+        roundtrip(udt("Marker"), json!(null));
+        roundtrip(udt("Empty"), json!({}));
+        roundtrip(
+            udt("Shape"),
+            json!({ "Line": [{ "x": "0", "y": "0" }, { "x": "1", "y": "1" }] }),
+        );
+        roundtrip(udt("Color"), json!(1));
+        roundtrip(udt("Error"), json!(3));
+    }
+
+    #[test]
+    fn udt_representation() {
+        let specs = specs();
+        assert_eq!(
+            from_json(&specs, &udt("Shape"), &json!("Empty")).unwrap(),
+            ScVal::Vec(Some(
+                vec![ScVal::Symbol("Empty".try_into().unwrap())]
+                    .try_into()
+                    .unwrap()
+            )),
+        );
+        assert_eq!(
+            from_json(&specs, &udt("Color"), &json!("Red")).unwrap(),
+            ScVal::U32(1),
+        );
+        assert_eq!(
+            from_json(&specs, &udt("Marker"), &Value::Null).unwrap(),
+            ScVal::Void,
+        );
+        // Structs with no fields that are not marked as unit structs are
+        // stored as empty maps.
+        assert_eq!(
+            from_json(&specs, &udt("Empty"), &json!({})).unwrap(),
+            ScVal::Map(Some(vec![].try_into().unwrap())),
+        );
+        assert_eq!(
+            from_json(&specs, &udt("Error"), &json!("NotFound")).unwrap(),
+            ScVal::Error(ScError::Contract(3)),
+        );
+        assert!(from_json(&specs, &udt("Color"), &json!(2)).is_err());
+        assert!(from_json(&specs, &udt("Point"), &json!({ "x": "1" })).is_err());
+        assert!(from_json(&specs, &udt("Missing"), &json!(1)).is_err());
+        assert!(from_json(
+            &specs,
+            &ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 2 }),
+            &json!("ab")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn function() {
+        let specs = specs();
+        assert_eq!(
+            args_from_json(&specs, "draw", &json!({ "shape": "Empty", "color": null })).unwrap(),
+            vec![
+                ScVal::Vec(Some(
+                    vec![ScVal::Symbol("Empty".try_into().unwrap())]
+                        .try_into()
+                        .unwrap()
+                )),
+                ScVal::Void,
+            ],
+        );
+        assert!(args_from_json(&specs, "draw", &json!({ "shape": "Empty" })).is_err());
+        assert!(args_from_json(&specs, "missing", &json!({})).is_err());
+        assert_eq!(
+            result_to_json(&specs, "draw", &ScVal::U32(2)).unwrap(),
+            json!({ "ok": 2 }),
+        );
+        assert_eq!(
+            result_to_json(&specs, "draw", &ScVal::Error(ScError::Contract(3))).unwrap(),
+            json!({ "error": 3 }),
+        );
+    }
+}
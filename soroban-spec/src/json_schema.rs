@@ -0,0 +1,542 @@
+//! JSON Schema generates [JSON Schemas] for the types and function arguments
+//! of a contract spec, so that JSON values can be validated against the
+//! interface of a contract.
+//!
+//! Values are represented in JSON as follows:
+//!
+//! - `bool` – A boolean.
+//! - `void` – `null`.
+//! - `u32`, `i32` – An integer.
+//! - `u64`, `i64`, `u128`, `i128`, `u256`, `i256`, `timepoint`, `duration` –
+//!   A string of the decimal integer, because JSON numbers cannot represent
+//!   all values exactly.
+//! - `bytes`, `bytesN` – A string of the hex encoded bytes.
+//! - `string`, `symbol` – A string.
+//! - `address`, `muxed_address` – A string of the strkey encoded address.
+//! - `error` – An object with the `type` and `code` of the error.
+//! - `option` – `null` or the value.
+//! - `result` – An object with either an `ok` or `error` property.
+//! - `vec`, `tuple` – An array.
+//! - `map` – An object when keys are strings or symbols, otherwise an array of
+//!   `[key, value]` arrays.
+//! - Structs – An object with a property for each field, an array if the
+//!   struct is a tuple struct, or `null` if the struct is a unit struct.
+//! - Unions – The name of the case for void cases, otherwise an object with a
+//!   single property, the name of the case, containing an array of the values.
+//! - Enums and error enums – The integer value of the case.
+//!
+//! [JSON Schemas]: https://json-schema.org
+use serde_json::{json, Map, Value};
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{
+    ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtEnumV0, ScSpecUdtErrorEnumV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionV0,
+};
+
+use crate::marker::{doc_without_marker, is_tuple_struct, is_unit_struct};
+
+/// The JSON Schema dialect of the generated schemas.
+pub const SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const ADDRESS_PATTERN: &str = "^[GC][A-Z2-7]{55}$";
+const MUXED_ADDRESS_PATTERN: &str = "^([GC][A-Z2-7]{55}|M[A-Z2-7]{68})$";
+const UNSIGNED_PATTERN: &str = "^[0-9]+$";
+const SIGNED_PATTERN: &str = "^-?[0-9]+$";
+const HEX_PATTERN: &str = "^([0-9a-fA-F]{2})*$";
+const SYMBOL_PATTERN: &str = "^[a-zA-Z0-9_]*$";
+
+/// Generates a schema document for the spec.
+///
+/// The schemas of the types are placed in `$defs`, and the schemas of the
+/// arguments of each function are placed in `functions`, and can be
+/// referenced with the JSON pointer `#/functions/<name>`.
+pub fn generate(specs: &[ScSpecEntry]) -> Value {
+    let functions: Map<String, Value> = specs
+        .iter()
+        .filter_map(|s| match s {
+            ScSpecEntry::FunctionV0(f) => {
+                Some((f.name.to_utf8_string_lossy(), generate_function_args(f)))
+            }
+            _ => None,
+        })
+        .collect();
+    json!({
+        "$schema": SCHEMA,
+        "$defs": generate_defs(specs),
+        "functions": functions,
+    })
+}
+
+/// Generates a standalone schema document for the arguments of the function,
+/// or None if the spec has no function with the name.
+pub fn generate_for_function(specs: &[ScSpecEntry], name: &str) -> Option<Value> {
+    let f = specs.iter().find_map(|s| match s {
+        ScSpecEntry::FunctionV0(f) if f.name.to_utf8_string_lossy() == name => Some(f),
+        _ => None,
+    })?;
+    let mut schema = generate_function_args(f);
+    let o = schema.as_object_mut().unwrap();
+    o.insert("$schema".to_string(), SCHEMA.into());
+    o.insert("$defs".to_string(), generate_defs(specs).into());
+    Some(schema)
+}
+
+/// Generates the schemas of the types in the spec, keyed by type name.
+pub fn generate_defs(specs: &[ScSpecEntry]) -> Map<String, Value> {
+    specs
+        .iter()
+        .filter_map(|s| match s {
+            ScSpecEntry::UdtStructV0(s) => {
+                Some((s.name.to_utf8_string_lossy(), generate_struct(s)))
+            }
+            ScSpecEntry::UdtUnionV0(u) => Some((u.name.to_utf8_string_lossy(), generate_union(u))),
+            ScSpecEntry::UdtEnumV0(e) => Some((e.name.to_utf8_string_lossy(), generate_enum(e))),
+            ScSpecEntry::UdtErrorEnumV0(e) => {
+                Some((e.name.to_utf8_string_lossy(), generate_error_enum(e)))
+            }
+            ScSpecEntry::FunctionV0(_) | ScSpecEntry::EventV0(_) => None,
+        })
+        .collect()
+}
+
+/// Generates the schema of an object with a property for each argument of the
+/// function.
+pub fn generate_function_args(spec: &ScSpecFunctionV0) -> Value {
+    let properties: Map<String, Value> = spec
+        .inputs
+        .iter()
+        .map(|i| {
+            (
+                i.name.to_utf8_string_lossy(),
+                with_description(generate_type(&i.type_), &i.doc),
+            )
+        })
+        .collect();
+    let required: Vec<String> = spec
+        .inputs
+        .iter()
+        .map(|i| i.name.to_utf8_string_lossy())
+        .collect();
+    with_description(
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }),
+        &spec.doc,
+    )
+}
+
+/// Generates the schema of a struct.
+pub fn generate_struct(spec: &ScSpecUdtStructV0) -> Value {
+    let schema = if is_unit_struct(spec) {
+        json!({ "type": "null" })
+    } else if is_tuple_struct(spec) {
+        let items: Vec<Value> = spec
+            .fields
+            .iter()
+            .map(|f| with_description(generate_type(&f.type_), &f.doc))
+            .collect();
+        tuple(items)
+    } else {
+        let properties: Map<String, Value> = spec
+            .fields
+            .iter()
+            .map(|f| {
+                (
+                    f.name.to_utf8_string_lossy(),
+                    with_description(generate_type(&f.type_), &f.doc),
+                )
+            })
+            .collect();
+        let required: Vec<String> = spec
+            .fields
+            .iter()
+            .map(|f| f.name.to_utf8_string_lossy())
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    };
+    with_description(schema, &spec.doc)
+}
+
+/// Generates the schema of a union.
+pub fn generate_union(spec: &ScSpecUdtUnionV0) -> Value {
+    let cases: Vec<Value> = spec
+        .cases
+        .iter()
+        .map(|c| match c {
+            ScSpecUdtUnionCaseV0::VoidV0(v) => {
+                with_description(json!({ "const": v.name.to_utf8_string_lossy() }), &v.doc)
+            }
+            ScSpecUdtUnionCaseV0::TupleV0(t) => {
+                let name = t.name.to_utf8_string_lossy();
+                let items = t.type_.iter().map(generate_type).collect();
+                with_description(
+                    json!({
+                        "type": "object",
+                        "properties": { name.clone(): tuple(items) },
+                        "required": [name],
+                        "additionalProperties": false,
+                    }),
+                    &t.doc,
+                )
+            }
+        })
+        .collect();
+    with_description(json!({ "oneOf": cases }), &spec.doc)
+}
+
+/// Generates the schema of an enum.
+pub fn generate_enum(spec: &ScSpecUdtEnumV0) -> Value {
+    let cases: Vec<Value> = spec
+        .cases
+        .iter()
+        .map(|c| {
+            with_description(
+                json!({ "title": c.name.to_utf8_string_lossy(), "const": c.value }),
+                &c.doc,
+            )
+        })
+        .collect();
+    with_description(json!({ "oneOf": cases }), &spec.doc)
+}
+
+/// Generates the schema of an error enum.
+pub fn generate_error_enum(spec: &ScSpecUdtErrorEnumV0) -> Value {
+    let cases: Vec<Value> = spec
+        .cases
+        .iter()
+        .map(|c| {
+            with_description(
+                json!({ "title": c.name.to_utf8_string_lossy(), "const": c.value }),
+                &c.doc,
+            )
+        })
+        .collect();
+    with_description(json!({ "oneOf": cases }), &spec.doc)
+}
+
+/// Generates the schema of a type. User defined types are referenced as
+/// `#/$defs/<name>`.
+pub fn generate_type(spec: &ScSpecTypeDef) -> Value {
+    match spec {
+        ScSpecTypeDef::Val => json!({}),
+        ScSpecTypeDef::Bool => json!({ "type": "boolean" }),
+        ScSpecTypeDef::Void => json!({ "type": "null" }),
+        ScSpecTypeDef::Error => json!({
+            "type": "object",
+            "properties": {
+                "type": { "type": "string" },
+                "code": { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+            },
+            "required": ["type", "code"],
+            "additionalProperties": false,
+        }),
+        ScSpecTypeDef::U32 => json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX }),
+        ScSpecTypeDef::I32 => {
+            json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX })
+        }
+        ScSpecTypeDef::U64
+        | ScSpecTypeDef::U128
+        | ScSpecTypeDef::U256
+        | ScSpecTypeDef::Timepoint
+        | ScSpecTypeDef::Duration => json!({ "type": "string", "pattern": UNSIGNED_PATTERN }),
+        ScSpecTypeDef::I64 | ScSpecTypeDef::I128 | ScSpecTypeDef::I256 => {
+            json!({ "type": "string", "pattern": SIGNED_PATTERN })
+        }
+        ScSpecTypeDef::Bytes => json!({ "type": "string", "pattern": HEX_PATTERN }),
+        ScSpecTypeDef::BytesN(b) => json!({
+            "type": "string",
+            "pattern": HEX_PATTERN,
+            "minLength": b.n * 2,
+            "maxLength": b.n * 2,
+        }),
+        ScSpecTypeDef::String => json!({ "type": "string" }),
+        ScSpecTypeDef::Symbol => json!({
+            "type": "string",
+            "pattern": SYMBOL_PATTERN,
+            "maxLength": 32,
+        }),
+        ScSpecTypeDef::Address => json!({ "type": "string", "pattern": ADDRESS_PATTERN }),
+        ScSpecTypeDef::MuxedAddress => {
+            json!({ "type": "string", "pattern": MUXED_ADDRESS_PATTERN })
+        }
+        ScSpecTypeDef::Option(o) => json!({
+            "anyOf": [{ "type": "null" }, generate_type(&o.value_type)],
+        }),
+        ScSpecTypeDef::Result(r) => json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": { "ok": generate_type(&r.ok_type) },
+                    "required": ["ok"],
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "properties": { "error": generate_type(&r.error_type) },
+                    "required": ["error"],
+                    "additionalProperties": false,
+                },
+            ],
+        }),
+        ScSpecTypeDef::Vec(v) => json!({
+            "type": "array",
+            "items": generate_type(&v.element_type),
+        }),
+        ScSpecTypeDef::Map(m) => match *m.key_type {
+            ScSpecTypeDef::String | ScSpecTypeDef::Symbol => json!({
+                "type": "object",
+                "propertyNames": generate_type(&m.key_type),
+                "additionalProperties": generate_type(&m.value_type),
+            }),
+            _ => json!({
+                "type": "array",
+                "items": tuple(vec![generate_type(&m.key_type), generate_type(&m.value_type)]),
+            }),
+        },
+        ScSpecTypeDef::Tuple(t) => tuple(t.value_types.iter().map(generate_type).collect()),
+        ScSpecTypeDef::Udt(u) => {
+            json!({ "$ref": format!("#/$defs/{}", u.name.to_utf8_string_lossy()) })
+        }
+    }
+}
+
+/// Returns the schema of an array with exactly the items.
+fn tuple(items: Vec<Value>) -> Value {
+    let len = items.len();
+    json!({
+        "type": "array",
+        "prefixItems": items,
+        "minItems": len,
+        "maxItems": len,
+    })
+}
+
+fn with_description(mut schema: Value, doc: &[u8]) -> Value {
+    // Markers set by the SDK are not part of the description of the type.
+    let doc = doc_without_marker(doc);
+    if !doc.is_empty() {
+        if let Some(o) = schema.as_object_mut() {
+            o.insert(
+                "description".to_string(),
+                String::from_utf8_lossy(doc).into_owned().into(),
+            );
+        }
+    }
+    schema
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use stellar_xdr::curr as stellar_xdr;
+    use stellar_xdr::{
+        ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeBytesN, ScSpecTypeDef,
+        ScSpecTypeMap, ScSpecTypeOption, ScSpecTypeResult, ScSpecTypeTuple, ScSpecTypeUdt,
+        ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0, ScSpecUdtStructFieldV0, ScSpecUdtStructV0,
+        ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+        ScSpecUdtUnionV0,
+    };
+
+    use super::{generate, generate_for_function, generate_type};
+    use crate::marker::UNIT_STRUCT_DOC;
+
+    fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: name.try_into().unwrap(),
+        })
+    }
+
+    fn specs() -> Vec<ScSpecEntry> {
+        vec![
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: "A point.".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Point".try_into().unwrap(),
+                fields: [("x", ScSpecTypeDef::I32), ("y", ScSpecTypeDef::I32)]
+                    .into_iter()
+                    .map(|(name, type_)| ScSpecUdtStructFieldV0 {
+                        doc: "".try_into().unwrap(),
+                        name: name.try_into().unwrap(),
+                        type_,
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            }),
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: UNIT_STRUCT_DOC.try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Marker".try_into().unwrap(),
+                fields: vec![].try_into().unwrap(),
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Shape".try_into().unwrap(),
+                cases: vec![
+                    ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "Empty".try_into().unwrap(),
+                    }),
+                    ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "Line".try_into().unwrap(),
+                        type_: vec![udt("Point"), udt("Point")].try_into().unwrap(),
+                    }),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+                doc: "".try_into().unwrap(),
+                lib: "".try_into().unwrap(),
+                name: "Color".try_into().unwrap(),
+                cases: vec![ScSpecUdtEnumCaseV0 {
+                    doc: "".try_into().unwrap(),
+                    name: "Red".try_into().unwrap(),
+                    value: 1,
+                }]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+                doc: "".try_into().unwrap(),
+                name: "draw".try_into().unwrap(),
+                inputs: vec![
+                    ScSpecFunctionInputV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "shape".try_into().unwrap(),
+                        type_: udt("Shape"),
+                    },
+                    ScSpecFunctionInputV0 {
+                        doc: "".try_into().unwrap(),
+                        name: "color".try_into().unwrap(),
+                        type_: ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                            value_type: Box::new(udt("Color")),
+                        })),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+                outputs: vec![].try_into().unwrap(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(
+            generate_type(&ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: 32 })),
+            json!({
+                "type": "string",
+                "pattern": "^([0-9a-fA-F]{2})*$",
+                "minLength": 64,
+                "maxLength": 64,
+            })
+        );
+        assert_eq!(
+            generate_type(&ScSpecTypeDef::Result(Box::new(ScSpecTypeResult {
+                ok_type: Box::new(ScSpecTypeDef::Void),
+                error_type: Box::new(ScSpecTypeDef::Error),
+            })))["oneOf"][0],
+            json!({
+                "type": "object",
+                "properties": { "ok": { "type": "null" } },
+                "required": ["ok"],
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(
+            generate_type(&ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+                key_type: Box::new(ScSpecTypeDef::U32),
+                value_type: Box::new(ScSpecTypeDef::Bool),
+            }))),
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "array",
+                    "prefixItems": [
+                        { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+                        { "type": "boolean" },
+                    ],
+                    "minItems": 2,
+                    "maxItems": 2,
+                },
+            })
+        );
+        assert_eq!(
+            generate_type(&ScSpecTypeDef::Tuple(Box::new(ScSpecTypeTuple {
+                value_types: vec![ScSpecTypeDef::U64, ScSpecTypeDef::I128]
+                    .try_into()
+                    .unwrap(),
+            }))),
+            json!({
+                "type": "array",
+                "prefixItems": [
+                    { "type": "string", "pattern": "^[0-9]+$" },
+                    { "type": "string", "pattern": "^-?[0-9]+$" },
+                ],
+                "minItems": 2,
+                "maxItems": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn document() {
+        let schema = generate(&specs());
+        assert_eq!(schema["$defs"]["Marker"], json!({ "type": "null" }));
+        assert_eq!(
+            schema["$defs"]["Point"],
+            json!({
+                "description": "A point.",
+                "type": "object",
+                "properties": {
+                    "x": { "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX },
+                    "y": { "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX },
+                },
+                "required": ["x", "y"],
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(
+            schema["$defs"]["Shape"]["oneOf"][0],
+            json!({ "const": "Empty" })
+        );
+        assert_eq!(
+            schema["$defs"]["Shape"]["oneOf"][1]["properties"]["Line"]["prefixItems"],
+            json!([{ "$ref": "#/$defs/Point" }, { "$ref": "#/$defs/Point" }])
+        );
+        assert_eq!(
+            schema["$defs"]["Color"],
+            json!({ "oneOf": [{ "title": "Red", "const": 1 }] })
+        );
+        assert_eq!(
+            schema["functions"]["draw"]["required"],
+            json!(["shape", "color"])
+        );
+        assert_eq!(
+            schema["functions"]["draw"]["properties"]["color"],
+            json!({ "anyOf": [{ "type": "null" }, { "$ref": "#/$defs/Color" }] })
+        );
+    }
+
+    #[test]
+    fn function_document() {
+        let specs = specs();
+        let schema = generate_for_function(&specs, "draw").unwrap();
+        assert_eq!(schema["$schema"], super::SCHEMA);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["$defs"], generate(&specs)["$defs"]);
+        assert_eq!(generate_for_function(&specs, "missing"), None);
+    }
+}
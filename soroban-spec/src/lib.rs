@@ -1 +1,6 @@
+pub mod compat;
+pub mod json;
+pub mod json_schema;
+pub mod marker;
+pub mod meta;
 pub mod read;
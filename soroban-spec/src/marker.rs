@@ -0,0 +1,72 @@
+//! Markers that the SDK sets on spec entries, so that tools reading a spec can
+//! tell apart entries that have the same shape but that are stored differently,
+//! or that are synthesized by the SDK rather than written by contract authors.
+//!
+//! Markers are set as the first line of the doc of the entry.
+
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::ScSpecUdtStructV0;
+
+/// The doc of the struct spec entries that hold the named fields of enum
+/// variants with named fields.
+///
+/// Variants with named fields are exported as a tuple case of the union
+/// containing a single struct that is named after the enum and the variant,
+/// and that has this doc. Structs written by contract authors may have the
+/// same name and fields, and are told apart by the doc.
+pub const ENUM_VARIANT_STRUCT_DOC: &str = "@soroban_enum_variant_fields";
+
+/// The marker of the struct spec entries of unit structs, that are stored as
+/// void.
+pub const UNIT_STRUCT_DOC: &str = "@soroban_unit_struct";
+
+/// The marker of the struct spec entries of tuple structs with no fields, that
+/// are stored as an empty vec.
+///
+/// Structs with no fields that have no marker are stored as an empty map, the
+/// same as structs with named fields.
+pub const TUPLE_STRUCT_DOC: &str = "@soroban_tuple_struct";
+
+/// Returns the doc with the marker set as its first line.
+pub fn doc_with_marker(marker: &str, doc: &str) -> String {
+    if doc.is_empty() {
+        marker.to_string()
+    } else {
+        format!("{marker}\n{doc}")
+    }
+}
+
+/// Returns true if the first line of the doc is the marker.
+pub fn doc_has_marker(doc: &[u8], marker: &str) -> bool {
+    doc.strip_prefix(marker.as_bytes())
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"\n"))
+}
+
+/// Returns true if the struct spec is of a unit struct, that is stored as void.
+pub fn is_unit_struct(spec: &ScSpecUdtStructV0) -> bool {
+    spec.fields.is_empty() && doc_has_marker(&spec.doc, UNIT_STRUCT_DOC)
+}
+
+/// Returns true if the struct spec is of a tuple struct, that is stored as a
+/// vec. Structs with fields are tuple structs if all their field names are
+/// numeric, and structs without fields are if they are marked as such.
+pub fn is_tuple_struct(spec: &ScSpecUdtStructV0) -> bool {
+    if spec.fields.is_empty() {
+        doc_has_marker(&spec.doc, TUPLE_STRUCT_DOC)
+    } else {
+        spec.fields
+            .iter()
+            .all(|f| f.name.to_utf8_string_lossy().parse::<usize>().is_ok())
+    }
+}
+
+/// Returns the doc without its first line if that line is one of the markers.
+pub fn doc_without_marker(doc: &[u8]) -> &[u8] {
+    [ENUM_VARIANT_STRUCT_DOC, UNIT_STRUCT_DOC, TUPLE_STRUCT_DOC]
+        .into_iter()
+        .find(|m| doc_has_marker(doc, m))
+        .map_or(doc, |m| {
+            let rest = &doc[m.len()..];
+            rest.strip_prefix(b"\n").unwrap_or(rest)
+        })
+}
@@ -0,0 +1,116 @@
+use stellar_xdr::curr as stellar_xdr;
+use stellar_xdr::{ScEnvMetaEntry, ScMetaEntry};
+
+use crate::read::{env_meta_from_wasm, meta_from_wasm, MetaFromWasmError};
+
+/// The meta key that the SDK stores the version of rustc used to build the
+/// contract under.
+pub const RUSTC_VERSION_KEY: &str = "rsver";
+
+/// The meta key that the SDK stores its version under, formatted as
+/// `<version>#<git revision>`.
+pub const SDK_VERSION_KEY: &str = "rssdkver";
+
+/// The metadata of a contract, combining the entries written by
+/// `contractmeta!` with the environment meta that identifies the protocol the
+/// contract was built for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractMeta {
+    /// The protocol version of the environment interface the contract was
+    /// built for.
+    pub protocol_version: Option<u32>,
+    /// The pre-release version of the environment interface the contract was
+    /// built for, zero when built for a released protocol.
+    pub pre_release_version: Option<u32>,
+    /// The key-value entries of the contract meta, in the order they appear
+    /// in the contract.
+    pub entries: Vec<(String, String)>,
+}
+
+impl ContractMeta {
+    /// Reads the metadata of the contract in the wasm.
+    pub fn from_wasm(wasm: &[u8]) -> Result<Self, MetaFromWasmError> {
+        let meta = meta_from_wasm(wasm)?;
+        let env_meta = env_meta_from_wasm(wasm)?;
+        Ok(Self::from_entries(&meta, &env_meta))
+    }
+
+    /// Constructs the metadata from meta and environment meta entries.
+    pub fn from_entries(meta: &[ScMetaEntry], env_meta: &[ScEnvMetaEntry]) -> Self {
+        let mut res = Self {
+            entries: meta
+                .iter()
+                .map(|ScMetaEntry::ScMetaV0(v)| {
+                    (v.key.to_utf8_string_lossy(), v.val.to_utf8_string_lossy())
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for e in env_meta {
+            match e {
+                ScEnvMetaEntry::ScEnvMetaKindInterfaceVersion(v) => {
+                    res.protocol_version = Some(v.protocol);
+                    res.pre_release_version = Some(v.pre_release);
+                }
+            }
+        }
+        res
+    }
+
+    /// Returns the value of the first entry with the key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the version of rustc used to build the contract.
+    pub fn rustc_version(&self) -> Option<&str> {
+        self.get(RUSTC_VERSION_KEY)
+    }
+
+    /// Returns the version of the SDK used to build the contract, without the
+    /// git revision.
+    pub fn sdk_version(&self) -> Option<&str> {
+        self.get(SDK_VERSION_KEY)
+            .map(|v| v.split_once('#').map_or(v, |(version, _)| version))
+    }
+
+    /// Returns the git revision of the SDK used to build the contract.
+    pub fn sdk_git_revision(&self) -> Option<&str> {
+        self.get(SDK_VERSION_KEY)
+            .and_then(|v| v.split_once('#'))
+            .map(|(_, revision)| revision)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::ContractMeta;
+
+    const EXAMPLE_WASM: &[u8] =
+        include_bytes!("../../soroban-sdk/test_wasms/test_contract_data.wasm");
+
+    #[test]
+    fn from_wasm() {
+        let meta = ContractMeta::from_wasm(EXAMPLE_WASM).unwrap();
+        assert_eq!(meta.rustc_version(), Some("1.81.0"));
+        assert_eq!(meta.sdk_version(), Some("22.0.2"));
+        assert_eq!(
+            meta.sdk_git_revision(),
+            Some("dfe899bc12c2297551063e30151ff654f97b82e8-dirty")
+        );
+        assert_eq!(meta.protocol_version, Some(22));
+        assert_eq!(meta.pre_release_version, Some(0));
+        assert_eq!(meta.get("unknown"), None);
+    }
+
+    #[test]
+    fn from_wasm_without_meta() {
+        let meta = ContractMeta::from_wasm(b"\0asm\x01\0\0\0").unwrap();
+        assert_eq!(meta, ContractMeta::default());
+    }
+}
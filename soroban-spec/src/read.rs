@@ -1,8 +1,8 @@
-use std::io::Cursor;
+use std::{collections::HashSet, io::Cursor};
 
 use base64::Engine;
 use stellar_xdr::curr as stellar_xdr;
-use stellar_xdr::{Limited, Limits, ReadXdr, ScSpecEntry};
+use stellar_xdr::{Limited, Limits, ReadXdr, ScEnvMetaEntry, ScMetaEntry, ScSpecEntry};
 use wasmparser::{BinaryReaderError, Parser, Payload};
 
 // TODO: Move these functions into stellar_xdr.
@@ -22,9 +22,15 @@ pub fn parse_base64(spec: &[u8]) -> Result<Vec<ScSpecEntry>, ParseSpecBase64Erro
     parse_raw(&decoded).map_err(ParseSpecBase64Error::ParseXdr)
 }
 
+/// Parses the spec entries, dropping entries that are repeats of an earlier
+/// entry.
+///
+/// The spec entries of instances of generic contract types are emitted by every
+/// contract type and contract impl that references the instance, and so the
+/// same entry can appear in a spec more than once.
 pub fn parse_raw(spec: &[u8]) -> Result<Vec<ScSpecEntry>, stellar_xdr::Error> {
     let cursor = Cursor::new(spec);
-    let entries = ScSpecEntry::read_xdr_iter(&mut Limited::new(
+    let mut entries = ScSpecEntry::read_xdr_iter(&mut Limited::new(
         cursor,
         Limits {
             depth: 500,
@@ -32,6 +38,8 @@ pub fn parse_raw(spec: &[u8]) -> Result<Vec<ScSpecEntry>, stellar_xdr::Error> {
         },
     ))
     .collect::<Result<Vec<_>, _>>()?;
+    let mut seen = HashSet::new();
+    entries.retain(|e| seen.insert(e.clone()));
     Ok(entries)
 }
 
@@ -68,3 +76,96 @@ pub fn from_wasm(wasm: &[u8]) -> Result<Vec<ScSpecEntry>, FromWasmError> {
     let spec = raw_from_wasm(wasm)?;
     parse_raw(&spec).map_err(FromWasmError::Parse)
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum MetaFromWasmError {
+    #[error("reading wasm")]
+    Read(BinaryReaderError),
+    #[error("parsing contract meta")]
+    Parse(stellar_xdr::Error),
+}
+
+/// Returns the contents of all custom sections with the name, concatenated in
+/// the order they appear in the wasm.
+fn raw_sections_from_wasm(wasm: &[u8], name: &str) -> Result<Vec<u8>, BinaryReaderError> {
+    let mut raw = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CustomSection(section) = payload? {
+            if section.name() == name {
+                raw.extend_from_slice(section.data());
+            }
+        };
+    }
+    Ok(raw)
+}
+
+pub fn parse_meta_raw(meta: &[u8]) -> Result<Vec<ScMetaEntry>, stellar_xdr::Error> {
+    let cursor = Cursor::new(meta);
+    let entries = ScMetaEntry::read_xdr_iter(&mut Limited::new(
+        cursor,
+        Limits {
+            depth: 500,
+            len: 0x1000000,
+        },
+    ))
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+pub fn parse_env_meta_raw(env_meta: &[u8]) -> Result<Vec<ScEnvMetaEntry>, stellar_xdr::Error> {
+    let cursor = Cursor::new(env_meta);
+    let entries = ScEnvMetaEntry::read_xdr_iter(&mut Limited::new(
+        cursor,
+        Limits {
+            depth: 500,
+            len: 0x1000000,
+        },
+    ))
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+/// Returns the contents of the `contractmetav0` custom sections, that
+/// `contractmeta!` writes to, or an empty vec if the wasm has none.
+pub fn raw_meta_from_wasm(wasm: &[u8]) -> Result<Vec<u8>, MetaFromWasmError> {
+    raw_sections_from_wasm(wasm, "contractmetav0").map_err(MetaFromWasmError::Read)
+}
+
+/// Returns the contents of the `contractenvmetav0` custom sections, or an empty
+/// vec if the wasm has none.
+pub fn raw_env_meta_from_wasm(wasm: &[u8]) -> Result<Vec<u8>, MetaFromWasmError> {
+    raw_sections_from_wasm(wasm, "contractenvmetav0").map_err(MetaFromWasmError::Read)
+}
+
+pub fn meta_from_wasm(wasm: &[u8]) -> Result<Vec<ScMetaEntry>, MetaFromWasmError> {
+    let meta = raw_meta_from_wasm(wasm)?;
+    parse_meta_raw(&meta).map_err(MetaFromWasmError::Parse)
+}
+
+pub fn env_meta_from_wasm(wasm: &[u8]) -> Result<Vec<ScEnvMetaEntry>, MetaFromWasmError> {
+    let env_meta = raw_env_meta_from_wasm(wasm)?;
+    parse_env_meta_raw(&env_meta).map_err(MetaFromWasmError::Parse)
+}
+
+#[cfg(test)]
+mod test {
+    use stellar_xdr::curr as stellar_xdr;
+    use stellar_xdr::ScSpecEntry;
+
+    use super::from_wasm;
+
+    const MULTIIMPL_WASM: &[u8] =
+        include_bytes!("../../target/wasm32v1-none/release/test_multiimpl.wasm");
+
+    #[test]
+    fn test_from_wasm_dedups_generic_instances() {
+        // The Wrapper<u32> instance is referenced by functions in two impl
+        // blocks, that each emit its spec entry.
+        let entries = from_wasm(MULTIIMPL_WASM).unwrap();
+        let instances = entries
+            .iter()
+            .filter(|e| matches!(e, ScSpecEntry::UdtStructV0(s) if s.name.to_utf8_string_lossy() == "Wrapper_u32"))
+            .count();
+        assert_eq!(instances, 1);
+    }
+}
@@ -4,6 +4,20 @@ use soroban_sdk::{contract, contractimpl, symbol_short, Env};
 #[contract]
 pub struct Contract;
 
+/// Spec of the greetings event published by `hello`, so that contracts
+/// importing this contract get a type for decoding it. The entry is the XDR of
+/// an `ScSpecEntry::EventV0` named `Greeting` with the prefix topic
+/// `greetings`, a `topic` symbol param in the topics, and a `message` symbol
+/// param as the single value data.
+#[doc(hidden)]
+#[cfg_attr(target_family = "wasm", link_section = "contractspecv0")]
+pub static __SPEC_XDR_EVENT_GREETING: [u8; 136] = *b"\
+    \x00\x00\x00\x05\x00\x00\x00#Published when the contract greets.\x00\
+    \x00\x00\x00\x00\x00\x00\x00\x08Greeting\x00\x00\x00\x01\x00\x00\x00\x09greetings\
+    \x00\x00\x00\x00\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\x05topic\x00\x00\x00\
+    \x00\x00\x00\x11\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x07message\x00\
+    \x00\x00\x00\x11\x00\x00\x00\x00\x00\x00\x00\x00";
+
 #[contractimpl]
 impl Contract {
     pub fn hello(env: Env) {
@@ -1,17 +1,29 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl};
+use soroban_sdk::{contract, contractimpl, contracttype};
 
 #[contract]
 pub struct Contract;
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wrapper<T>(pub T);
+
 #[contractimpl]
 impl Contract {
     pub fn empty() {}
+
+    pub fn wrap(v: u32) -> Wrapper<u32> {
+        Wrapper(v)
+    }
 }
 
 #[contractimpl]
 impl Contract {
     pub fn empty2() {}
+
+    pub fn unwrap(w: Wrapper<u32>) -> u32 {
+        w.0
+    }
 }
 
 trait Trait {
@@ -27,7 +39,7 @@ impl Trait for Contract {
 mod test {
     use soroban_sdk::Env;
 
-    use crate::{Contract, ContractClient};
+    use crate::{Contract, ContractClient, Wrapper};
 
     #[test]
     fn test_hello() {
@@ -38,5 +50,7 @@ mod test {
         client.empty();
         client.empty2();
         client.empty3();
+        assert_eq!(client.unwrap(&client.wrap(&7)), 7);
+        assert_eq!(client.wrap(&8), Wrapper(8));
     }
 }
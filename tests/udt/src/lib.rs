@@ -15,12 +15,26 @@ pub enum UdtEnum {
     UdtB(UdtStruct),
     UdtC(UdtEnum2),
     UdtD(UdtTuple),
+    UdtE { a: i64, b: Vec<i64> },
+    UdtF(),
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UdtTuple(pub i64, pub Vec<i64>);
 
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UdtUnit;
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UdtEmpty {}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UdtEmptyTuple();
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UdtStruct {
@@ -40,12 +54,16 @@ impl Contract {
             UdtEnum::UdtB(udt) => udt.a + udt.b,
             UdtEnum::UdtC(val) => val as i64,
             UdtEnum::UdtD(tup) => tup.0 + tup.1.try_iter().fold(0i64, |sum, i| sum + i.unwrap()),
+            UdtEnum::UdtE { a, b } => a + b.try_iter().fold(0i64, |sum, i| sum + i.unwrap()),
+            UdtEnum::UdtF() => 0,
         };
         let b = match b {
             UdtEnum::UdtA => 0,
             UdtEnum::UdtB(udt) => udt.a + udt.b,
             UdtEnum::UdtC(val) => val as i64,
             UdtEnum::UdtD(tup) => tup.0 + tup.1.try_iter().fold(0i64, |sum, i| sum + i.unwrap()),
+            UdtEnum::UdtE { a, b } => a + b.try_iter().fold(0i64, |sum, i| sum + i.unwrap()),
+            UdtEnum::UdtF() => 0,
         };
         a + b
     }
@@ -94,6 +112,13 @@ mod test {
         let udt2 = UdtTuple(1, vec![&e, 2, 3]);
         let z = client.add(&UdtEnum::UdtC(udt1), &UdtEnum::UdtD(udt2));
         assert_eq!(z, 16);
+
+        let udt = UdtEnum::UdtE {
+            a: 4,
+            b: vec![&e, 5, 6],
+        };
+        let z = client.add(&udt, &UdtEnum::UdtA);
+        assert_eq!(z, 15);
     }
 
     #[test]
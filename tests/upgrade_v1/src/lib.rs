@@ -0,0 +1,45 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contractupgradeable, symbol_short,
+    upgrade::{self, Upgradeable},
+    Address, Env,
+};
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        upgrade::init::<Self>(&env);
+    }
+
+    pub fn increment(env: Env) -> u32 {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("count"))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("count"), &(count + 1));
+        count + 1
+    }
+}
+
+#[contractupgradeable]
+impl Upgradeable for Contract {
+    const VERSION: u32 = 1;
+
+    fn authorize_upgrade(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+        admin.require_auth();
+    }
+}
@@ -0,0 +1,158 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contractupgradeable, symbol_short,
+    upgrade::{self, Upgradeable},
+    Address, Env,
+};
+
+#[contracttype]
+pub struct Counter {
+    pub count: u64,
+    pub updated: u32,
+}
+
+#[contract]
+pub struct Contract;
+
+#[contractimpl]
+impl Contract {
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        upgrade::init::<Self>(&env);
+    }
+
+    pub fn increment(env: Env) -> u64 {
+        let mut counter = Self::counter(env.clone());
+        counter.count += 1;
+        counter.updated = env.ledger().sequence();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("counter"), &counter);
+        counter.count
+    }
+
+    pub fn counter(env: Env) -> Counter {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("counter"))
+            .unwrap_or(Counter {
+                count: 0,
+                updated: 0,
+            })
+    }
+}
+
+#[contractupgradeable]
+impl Upgradeable for Contract {
+    const VERSION: u32 = 2;
+
+    fn authorize_upgrade(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+        admin.require_auth();
+    }
+
+    fn migrate(env: &Env, from_version: u32) {
+        match from_version {
+            // Version 1 stored the count alone as a u32.
+            1 => {
+                let count: u32 = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("count"))
+                    .unwrap_or(0);
+                env.storage().instance().remove(&symbol_short!("count"));
+                let counter = Counter {
+                    count: count.into(),
+                    updated: env.ledger().sequence(),
+                };
+                env.storage()
+                    .instance()
+                    .set(&symbol_short!("counter"), &counter);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use soroban_sdk::{testutils::Address as _, Address, Env};
+
+    use crate::ContractClient;
+
+    mod v1 {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32v1-none/release/test_upgrade_v1.wasm"
+        );
+    }
+
+    mod v2 {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32v1-none/release/test_upgrade_v2.wasm"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_and_migrate() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let admin = Address::generate(&e);
+
+        let contract_id = e.register(v1::WASM, (&admin,));
+        let client_v1 = v1::Client::new(&e, &contract_id);
+        assert_eq!(client_v1.version(), 1);
+        client_v1.increment();
+        assert_eq!(client_v1.increment(), 2);
+
+        let wasm_hash = e.deployer().upload_contract_wasm(v2::WASM);
+        client_v1.upgrade(&wasm_hash);
+
+        let client = ContractClient::new(&e, &contract_id);
+        assert_eq!(client.version(), 1);
+        assert_eq!(client.migrate(), 2);
+        assert_eq!(client.version(), 2);
+        assert_eq!(client.counter().count, 2);
+        assert_eq!(client.increment(), 3);
+
+        // Migrating again does not repeat the migration.
+        assert_eq!(client.migrate(), 2);
+        assert_eq!(client.counter().count, 3);
+    }
+
+    #[test]
+    fn test_upgrade_interface_changes() {
+        extern crate std;
+        use soroban_sdk::xdr::ScSpecTypeDef;
+        use soroban_spec::compat::{compare_wasm, Change};
+
+        let changes = compare_wasm(v1::WASM, v2::WASM).unwrap();
+        let breaking: std::vec::Vec<_> = changes.iter().filter(|c| c.is_breaking()).collect();
+        assert_eq!(
+            breaking,
+            [&Change::FunctionOutputChanged {
+                function: "increment".into(),
+                old: Some(ScSpecTypeDef::U32),
+                new: Some(ScSpecTypeDef::U64),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_upgrade_unauthorized() {
+        let e = Env::default();
+        let admin = Address::generate(&e);
+
+        let contract_id = e.register(v1::WASM, (&admin,));
+        let client = v1::Client::new(&e, &contract_id);
+
+        let wasm_hash = e.deployer().upload_contract_wasm(v2::WASM);
+        assert!(client.try_upgrade(&wasm_hash).is_err());
+        assert_eq!(client.version(), 1);
+    }
+}
@@ -1,22 +1,32 @@
 #![no_std]
 use soroban_sdk::{contract, contractimpl};
 
-use test_workspace_lib::Value;
+// Only the trait is imported, the default functions of the trait resolve the
+// types they use themselves.
+use test_workspace_lib::Valuable;
 
 #[contract]
 pub struct Contract;
 
 #[contractimpl]
 impl Contract {
-    pub fn value() -> Value {
-        return Value { value: 13 };
+    pub fn value() -> test_workspace_lib::Value {
+        return test_workspace_lib::Value { value: 13 };
+    }
+}
+
+#[contractimpl(contracttrait)]
+impl Valuable for Contract {
+    fn base(_env: soroban_sdk::Env) -> test_workspace_lib::Value {
+        test_workspace_lib::Value { value: 21 }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::Env;
+    use soroban_sdk::{vec, Env};
+    use test_workspace_lib::Value;
 
     #[test]
     fn test_add() {
@@ -28,4 +38,18 @@ mod test {
         let z = client.value();
         assert_eq!(z, Value { value: 13 });
     }
+
+    #[test]
+    fn test_trait() {
+        let e = Env::default();
+
+        let contract_id = e.register(Contract, ());
+        let client = ContractClient::new(&e, &contract_id);
+
+        assert_eq!(client.doubled(), Value { value: 42 });
+        assert_eq!(
+            client.values(),
+            vec![&e, Value { value: 21 }, Value { value: 42 }]
+        );
+    }
 }
@@ -1,8 +1,25 @@
 #![no_std]
-use soroban_sdk::contracttype;
+use soroban_sdk::{contracttrait, contracttype, vec, Env, Vec};
 
 #[contracttype]
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Value {
     pub value: i32,
 }
+
+#[contracttrait]
+pub trait Valuable {
+    /// Returns the doubled value.
+    fn doubled(env: Env) -> Value {
+        Value {
+            value: Self::base(env).value * 2,
+        }
+    }
+
+    /// Returns the base and the doubled value.
+    fn values(env: Env) -> Vec<crate::Value> {
+        vec![&env, Self::base(env.clone()), Self::doubled(env.clone())]
+    }
+
+    fn base(env: Env) -> Value;
+}